@@ -5,8 +5,11 @@ extern crate criterion;
 use criterion::BenchmarkId;
 use criterion::Criterion;
 
+use advent_of_code_2022::error::Error;
+use advent_of_code_2022::solutions::Solution;
+
 #[derive(Clone, Copy)]
-struct DayPart(u8, u8, fn(&str) -> advent_of_code_2022::solutions::Solution);
+struct DayPart(u8, u8, fn(&str) -> Result<Solution, Error>);
 
 impl std::fmt::Display for DayPart {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
@@ -65,7 +68,7 @@ fn criterion_benchmark(c: &mut Criterion) {
             for day_part in &day_parts {
                 let file = &inputs[(day_part.0 as usize) - 1];
                 #[allow(clippy::needless_borrow)]
-                day_part.2(&file);
+                let _ = day_part.2(&file);
             }
         });
     });
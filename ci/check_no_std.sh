@@ -0,0 +1,17 @@
+#!/bin/sh
+# Builds the solver core without `std`: for the host, and for a bare metal target when its
+# standard library is installed (`rustup target add thumbv7em-none-eabihf`), which proves that
+# nothing reaches for `std` behind our back. Only the rlib is built, as the cdylib needs `std` to
+# link.
+set -eu
+
+cd "$(dirname "$0")/.."
+TARGET=${TARGET:-thumbv7em-none-eabihf}
+
+cargo rustc --lib --no-default-features --crate-type rlib
+
+if rustup target list --installed 2>/dev/null | grep -qx "$TARGET"; then
+    cargo rustc --lib --no-default-features --crate-type rlib --target "$TARGET"
+else
+    echo "$TARGET is not installed, only checked the host build" >&2
+fi
@@ -0,0 +1,67 @@
+#ifndef ADVENT_OF_CODE_2022_H
+#define ADVENT_OF_CODE_2022_H
+
+/* Generated by cbindgen from src/ffi.rs, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Set in the result of `aoc_parts` when part a is solved.
+#define AOC_PART_A 1
+
+// Set in the result of `aoc_parts` when part b is solved.
+#define AOC_PART_B 2
+
+// What became of a call. The result string holds the answer on `Ok`, and an explanation on
+// anything else.
+typedef enum AocStatus {
+  AOC_STATUS_OK = 0,
+  AOC_STATUS_UNKNOWN_DAY = 1,
+  AOC_STATUS_UNKNOWN_PART = 2,
+  // The input was rejected by the solver, or isn't UTF-8.
+  AOC_STATUS_INVALID_INPUT = 3,
+  // A required pointer was null.
+  AOC_STATUS_NULL_POINTER = 4,
+  // The solver panicked, which is a bug.
+  AOC_STATUS_PANIC = 5,
+} AocStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// The last day with a solution. Days go from 1 to this.
+uint8_t aoc_max_day(void);
+
+// Which parts of `day` are solved, as `AOC_PART_A` and `AOC_PART_B` flags.
+uint8_t aoc_parts(uint8_t day);
+
+// The crate version, as a static string that must not be freed.
+const char *aoc_version(void);
+
+// Solves `part` (`'a'` or `'b'`) of `day` for the `len` bytes of `input`.
+//
+// Points `result` at a new string with the answer or what went wrong, which must be given back
+// to `aoc_free`. If `result` itself is null, nothing is written and `NullPointer` is returned.
+//
+// # Safety
+//
+// `input` must point to `len` readable bytes, or may be null when `len` is 0. `result` must be
+// null or point to writable memory for a pointer.
+enum AocStatus aoc_solve(uint8_t day, char part, const uint8_t *input, size_t len, char **result);
+
+// Frees a result string from `aoc_solve`. Null is ignored.
+//
+// # Safety
+//
+// `result` must be null or a string from `aoc_solve` that hasn't been freed yet.
+void aoc_free(char *result);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ADVENT_OF_CODE_2022_H */
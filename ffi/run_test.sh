@@ -0,0 +1,11 @@
+#!/bin/sh
+# Builds the shared library, then compiles and runs the C test program against it.
+set -eu
+
+cd "$(dirname "$0")/.."
+cargo build --release --lib
+
+mkdir -p target/ffi
+${CC:-cc} -Wall -Wextra -Werror -std=c99 -Iffi ffi/test_days.c \
+    -Ltarget/release -ladvent_of_code_2022 -o target/ffi/test_days
+LD_LIBRARY_PATH=target/release DYLD_LIBRARY_PATH=target/release ./target/ffi/test_days
@@ -0,0 +1,86 @@
+/* Solves every registered day through the C API and checks the calls that should fail do.
+ * Run from the repository root with ffi/run_test.sh. */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "advent_of_code_2022.h"
+
+static int failures = 0;
+
+static void check(int ok, const char *what) {
+    if (!ok) {
+        fprintf(stderr, "FAILED: %s\n", what);
+        failures++;
+    }
+}
+
+static char *read_file(const char *path, size_t *len) {
+    FILE *file = fopen(path, "rb");
+    if (file == NULL) {
+        return NULL;
+    }
+
+    fseek(file, 0, SEEK_END);
+    long size = ftell(file);
+    fseek(file, 0, SEEK_SET);
+
+    char *data = malloc(size > 0 ? (size_t)size : 1);
+    *len = fread(data, 1, (size_t)size, file);
+    fclose(file);
+    return data;
+}
+
+int main(void) {
+    printf("advent_of_code_2022 %s, days 1 to %d\n", aoc_version(), aoc_max_day());
+
+    for (uint8_t day = 1; day <= aoc_max_day(); day++) {
+        char path[32];
+        snprintf(path, sizeof path, "input/day%02d.txt", day);
+
+        size_t len = 0;
+        char *input = read_file(path, &len);
+        check(input != NULL, path);
+        if (input == NULL) {
+            continue;
+        }
+
+        const char parts[] = {'a', 'b'};
+        const uint8_t flags[] = {AOC_PART_A, AOC_PART_B};
+        for (int i = 0; i < 2; i++) {
+            if (!(aoc_parts(day) & flags[i])) {
+                continue;
+            }
+
+            char *result = NULL;
+            AocStatus status = aoc_solve(day, parts[i], (const uint8_t *)input, len, &result);
+            printf("%d%c: %s\n", day, parts[i], result);
+            check(status == AOC_STATUS_OK && result != NULL, "solving a registered day");
+            aoc_free(result);
+        }
+
+        free(input);
+    }
+
+    char *result = NULL;
+    check(aoc_solve(0, 'a', NULL, 0, &result) == AOC_STATUS_UNKNOWN_DAY, "day 0 is unknown");
+    aoc_free(result);
+    check(aoc_solve(1, 'c', NULL, 0, &result) == AOC_STATUS_UNKNOWN_PART, "part c is unknown");
+    aoc_free(result);
+    check(aoc_solve(1, 'a', NULL, 5, &result) == AOC_STATUS_NULL_POINTER, "null input");
+    aoc_free(result);
+    check(aoc_solve(10, 'a', (const uint8_t *)"noop", 4, &result) == AOC_STATUS_INVALID_INPUT,
+          "short program");
+    check(result != NULL && strstr(result, "cycle 220") != NULL, "error is explained");
+    aoc_free(result);
+    check(aoc_solve(1, 'a', NULL, 0, NULL) == AOC_STATUS_NULL_POINTER, "null result");
+    aoc_free(NULL);
+
+    if (failures > 0) {
+        fprintf(stderr, "%d checks failed\n", failures);
+        return 1;
+    }
+    printf("All checks passed\n");
+    return 0;
+}
@@ -0,0 +1,23 @@
+#!/bin/sh
+# Builds a seed corpus for every fuzz target from the inputs and examples in input/, plus a
+# few small generated inputs. Afterwards run a target with e.g.
+#
+#     cargo +nightly fuzz run day11 -- -max_len=4096 -timeout=10
+set -e
+cd "$(dirname "$0")"
+
+for input in ../input/day*.txt; do
+    day=$(basename "$input" .txt | cut -c1-5)
+    mkdir -p "corpus/$day"
+    cp "$input" "corpus/$day/"
+done
+
+for target in fuzz_targets/day*.rs; do
+    day=$(basename "$target" .rs)
+    mkdir -p "corpus/$day"
+    for seed in 1 2 3; do
+        cargo run --quiet --manifest-path ../Cargo.toml -- \
+            generate "$(echo "$day" | cut -c4-)" --seed "$seed" --size 3 \
+            > "corpus/$day/generated_$seed.txt" 2> /dev/null
+    done
+done
@@ -0,0 +1,14 @@
+use advent_of_code_2022::solutions::get_solution;
+
+/// Runs both parts of `day` on arbitrary bytes. Malformed input may be rejected with an error,
+/// but parsing and solving must never panic or hang.
+pub fn solve(day: u8, data: &[u8]) {
+    let Ok(file) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    let (part_a, part_b) = get_solution(day);
+    for part in [part_a, part_b].into_iter().flatten() {
+        let _ = part(file);
+    }
+}
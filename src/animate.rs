@@ -0,0 +1,307 @@
+use std::fmt::Write as _;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+use crate::error::Error;
+use crate::render::{Frame, Palette, Rgb, Scene};
+
+/// Plain characters from empty to full, for terminals that can't take colours.
+const RAMP: &[u8] = b" .:-=+*#%@";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key {
+    /// Stops or resumes playing.
+    Pause,
+    /// Shows the next frame and stays paused.
+    Step,
+    Quit,
+}
+
+impl Key {
+    #[must_use]
+    pub const fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            b' ' | b'p' => Some(Self::Pause),
+            b'n' | b's' | b'.' => Some(Self::Step),
+            b'q' | b'Q' => Some(Self::Quit),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    pub palette: Palette,
+    /// The most frames to play.
+    pub frames: usize,
+    /// Time each frame is shown, in milliseconds.
+    pub delay: u32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            palette: Palette::Gray,
+            frames: 1000,
+            delay: 100,
+        }
+    }
+}
+
+/// Draws `frame` with ANSI colours, packing two rows into each line with half blocks.
+#[must_use]
+pub fn draw_ansi(frame: &Frame, colors: &[Rgb]) -> String {
+    let mut out = String::new();
+
+    for y in (0..frame.height()).step_by(2) {
+        for x in 0..frame.width() {
+            let Rgb(r, g, b) = colors[usize::from(frame[(x, y)])];
+            let _ = write!(out, "\x1b[38;2;{r};{g};{b}m");
+
+            if y + 1 < frame.height() {
+                let Rgb(r, g, b) = colors[usize::from(frame[(x, y + 1)])];
+                let _ = write!(out, "\x1b[48;2;{r};{g};{b}m");
+            } else {
+                out += "\x1b[49m";
+            }
+            out += "\u{2580}";
+        }
+        out += "\x1b[0m\x1b[K\n";
+    }
+
+    out
+}
+
+/// Draws `frame` in plain characters, from blank for the lowest level to `@` for the highest, with
+/// `O` for the accent.
+#[must_use]
+pub fn draw_plain(frame: &Frame, scene: &Scene) -> String {
+    let gradient = usize::from(scene.levels.saturating_sub(u8::from(scene.accent)));
+    let glyph = |level: u8| {
+        let level = usize::from(level);
+        if scene.accent && level == gradient {
+            'O'
+        } else {
+            char::from(RAMP[level * (RAMP.len() - 1) / (gradient.max(2) - 1)])
+        }
+    };
+
+    let mut out = String::new();
+    for y in 0..frame.height() {
+        out.extend(frame.row(y).map(|&level| glyph(level)));
+        out.push('\n');
+    }
+    out
+}
+
+/// Writes every frame one after the other, for when the output isn't a terminal.
+///
+/// # Errors
+///
+/// If a frame has a level the scene doesn't, or writing to `out` fails.
+pub fn dump<W: Write>(scene: &Scene, options: &Options, mut out: W) -> Result<(), Error> {
+    scene.check_levels()?;
+    let frames = scene.sample(options.frames);
+
+    for (i, frame) in frames.iter().enumerate() {
+        writeln!(out, "Frame {}/{}", i + 1, frames.len())?;
+        write!(out, "{}", draw_plain(frame, scene))?;
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
+
+/// Plays the frames in place, one every `delay`, reacting to `keys` as they come in. Returns once
+/// the last frame is shown or on `Key::Quit`.
+///
+/// # Errors
+///
+/// If a frame has a level the scene doesn't, or writing to `out` fails.
+pub fn play<W: Write>(
+    scene: &Scene,
+    options: &Options,
+    mut out: W,
+    keys: &Receiver<Key>,
+) -> Result<(), Error> {
+    scene.check_levels()?;
+    let frames = scene.sample(options.frames);
+    let colors = scene.colors(options.palette);
+    let delay = Duration::from_millis(u64::from(options.delay));
+    let mut paused = false;
+    let mut i = 0;
+
+    write!(out, "\x1b[2J")?;
+    while i < frames.len() {
+        write!(out, "\x1b[H{}", draw_ansi(frames[i], &colors))?;
+        writeln!(
+            out,
+            "Frame {}/{}{}  [space] pause  [n] step  [q] quit\x1b[K",
+            i + 1,
+            frames.len(),
+            if paused { " (paused)" } else { "" }
+        )?;
+        out.flush()?;
+
+        let key = if paused {
+            keys.recv().ok().or(Some(Key::Quit))
+        } else {
+            match keys.recv_timeout(delay) {
+                Ok(key) => Some(key),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => {
+                    std::thread::sleep(delay);
+                    None
+                }
+            }
+        };
+
+        match key {
+            Some(Key::Quit) => break,
+            Some(Key::Pause) => paused = !paused,
+            Some(Key::Step) => {
+                paused = true;
+                i += 1;
+            }
+            None => i += 1,
+        }
+    }
+
+    Ok(())
+}
+
+/// Sends the keys typed on stdin, as they are typed, from a background thread.
+#[must_use]
+pub fn read_keys() -> Receiver<Key> {
+    let (sender, keys) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        for byte in std::io::stdin().lock().bytes() {
+            let Some(key) = byte.ok().and_then(Key::from_byte) else {
+                continue;
+            };
+            if sender.send(key).is_err() {
+                break;
+            }
+        }
+    });
+
+    keys
+}
+
+/// Puts the terminal in a mode where keys arrive without waiting for enter and aren't echoed, with
+/// the cursor hidden. Everything is put back when dropped.
+pub struct RawMode;
+
+impl RawMode {
+    /// # Errors
+    ///
+    /// If `stty` can't be run or refuses the settings.
+    pub fn enable() -> Result<Self, Error> {
+        stty(&["-icanon", "-echo", "min", "1"])?;
+        print!("\x1b[?25l");
+        Ok(Self)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        print!("\x1b[0m\x1b[?25h");
+        let _ = std::io::stdout().flush();
+        let _ = stty(&["icanon", "echo"]);
+    }
+}
+
+fn stty(args: &[&str]) -> Result<(), Error> {
+    let status = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .status()?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::UsageError("Can't set up the terminal".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    fn scene() -> Scene {
+        let frames = (0..5)
+            .map(|i| Grid::new(3, 1, vec![0, 1, i % 3]).unwrap())
+            .collect();
+
+        Scene {
+            frames,
+            levels: 3,
+            accent: true,
+        }
+    }
+
+    #[test]
+    fn test_keys() {
+        assert_eq!(Some(Key::Pause), Key::from_byte(b' '));
+        assert_eq!(Some(Key::Step), Key::from_byte(b'n'));
+        assert_eq!(Some(Key::Quit), Key::from_byte(b'q'));
+        assert_eq!(None, Key::from_byte(b'x'));
+    }
+
+    #[test]
+    fn test_draw_plain() {
+        let scene = scene();
+
+        assert_eq!(" @O\n", draw_plain(&scene.frames[2], &scene));
+    }
+
+    #[test]
+    fn test_draw_ansi() {
+        let frame = Grid::new(1, 3, vec![0, 1, 2]).unwrap();
+        let drawn = draw_ansi(&frame, &scene().colors(Palette::Gray));
+
+        assert_eq!(2, drawn.lines().count());
+        assert!(drawn.starts_with("\x1b[38;2;16;16;16m\x1b[48;2;240;240;240m\u{2580}"));
+        assert!(drawn.contains("\x1b[38;2;230;30;40m\x1b[49m\u{2580}"));
+    }
+
+    #[test]
+    fn test_dump() {
+        let mut out = vec![];
+        dump(&scene(), &Options::default(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(out.starts_with("Frame 1/5\n @ \n\nFrame 2/5\n @@\n"));
+        assert_eq!(15, out.lines().count());
+    }
+
+    #[test]
+    fn test_play_keys() {
+        let options = Options {
+            delay: 0,
+            ..Options::default()
+        };
+        let frames_shown = |keys: &[Key]| {
+            let (sender, receiver) = mpsc::channel();
+            for &key in keys {
+                sender.send(key).unwrap();
+            }
+
+            let mut out = vec![];
+            play(&scene(), &options, &mut out, &receiver).unwrap();
+            String::from_utf8(out).unwrap().matches("Frame ").count()
+        };
+
+        // Quitting straight away shows only the first frame, and every key press redraws.
+        assert_eq!(1, frames_shown(&[Key::Quit]));
+        assert_eq!(
+            8,
+            frames_shown(&[Key::Pause, Key::Pause, Key::Step, Key::Pause])
+        );
+        assert_eq!(5, frames_shown(&[]));
+    }
+}
@@ -0,0 +1,183 @@
+use core::hash::Hash;
+
+use hashbrown::HashMap;
+
+use crate::prelude::*;
+
+/// Where the states of a simulation start repeating.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// The first step whose state comes round again.
+    pub start: u64,
+    /// How many steps it takes to come round.
+    pub length: u64,
+}
+
+/// Values measured on a simulation that grow by the same amount each time its states repeat, like
+/// a tower height or counters.
+pub trait Metric: Clone {
+    /// `self` grown by `times` more of the growth from `from` to `to`.
+    #[must_use]
+    fn extrapolate(&self, from: &Self, to: &Self, times: u64) -> Self;
+}
+
+impl Metric for i64 {
+    #[allow(clippy::cast_possible_wrap)]
+    fn extrapolate(&self, from: &Self, to: &Self, times: u64) -> Self {
+        self + (to - from) * times as Self
+    }
+}
+
+/// Counters must only grow over a cycle, as the growth is taken unsigned.
+impl Metric for u64 {
+    fn extrapolate(&self, from: &Self, to: &Self, times: u64) -> Self {
+        debug_assert!(from <= to, "A u64 metric shrank over a cycle");
+        self + (to - from) * times
+    }
+}
+
+impl<M: Metric> Metric for Vec<M> {
+    fn extrapolate(&self, from: &Self, to: &Self, times: u64) -> Self {
+        self.iter()
+            .zip(from)
+            .zip(to)
+            .map(|((m, from), to)| m.extrapolate(from, to, times))
+            .collect()
+    }
+}
+
+/// Steps from `state` until the state `key` repeats, trying at most `limit` steps.
+///
+/// # Errors
+///
+/// If `step` fails.
+pub fn find_cycle<S, K, E, FS, FK>(
+    mut state: S,
+    limit: u64,
+    mut step: FS,
+    mut key: FK,
+) -> Result<Option<Cycle>, E>
+where
+    K: Eq + Hash,
+    FS: FnMut(S) -> Result<S, E>,
+    FK: FnMut(&S) -> K,
+{
+    let mut seen = HashMap::new();
+
+    for i in 0..=limit {
+        if let Some(start) = seen.insert(key(&state), i) {
+            return Ok(Some(Cycle {
+                start,
+                length: i - start,
+            }));
+        }
+        if i < limit {
+            state = step(state)?;
+        }
+    }
+
+    Ok(None)
+}
+
+/// The `metric` of the state reached after `steps` steps from `state`.
+///
+/// Once the state `key` repeats, the growth of the metric over one cycle is extrapolated over the
+/// remaining steps instead of simulating them, so `steps` can be far beyond what could be run one
+/// by one.
+///
+/// # Errors
+///
+/// If `step` fails.
+pub fn fast_forward<S, K, M, E, FS, FK, FM>(
+    mut state: S,
+    steps: u64,
+    mut step: FS,
+    mut key: FK,
+    mut metric: FM,
+) -> Result<M, E>
+where
+    K: Eq + Hash,
+    M: Metric,
+    FS: FnMut(S) -> Result<S, E>,
+    FK: FnMut(&S) -> K,
+    FM: FnMut(&S) -> M,
+{
+    let mut seen = HashMap::new();
+    let mut history = vec![];
+
+    for i in 0..steps {
+        history.push(metric(&state));
+
+        if let Some(start) = seen.insert(key(&state), i) {
+            let length = i - start;
+            let remaining = steps - i;
+            let offset = usize::try_from(start + remaining % length).unwrap_or(usize::MAX);
+            let at = |j: u64| &history[usize::try_from(j).unwrap_or(usize::MAX)];
+
+            return Ok(history[offset].extrapolate(at(start), at(i), remaining / length + 1));
+        }
+
+        state = step(state)?;
+    }
+
+    Ok(metric(&state))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use super::*;
+
+    /// Counts up modulo 7 from 10, adding the value to a running total on every step.
+    fn step((value, total): (u64, u64)) -> Result<(u64, u64), Infallible> {
+        let value = if value >= 7 { 3 } else { (value + 1) % 7 };
+        Ok((value, total + value))
+    }
+
+    fn simulate(steps: u64) -> u64 {
+        (0..steps).fold((10, 0), |s, _| step(s).unwrap()).1
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        assert_eq!(
+            Ok(Some(Cycle {
+                start: 1,
+                length: 7
+            })),
+            find_cycle((10, 0), 100, step, |s| s.0)
+        );
+        assert_eq!(Ok(None), find_cycle((10, 0), 5, step, |s| s.0));
+    }
+
+    #[test]
+    fn test_fast_forward() {
+        for steps in [0, 1, 2, 7, 8, 9, 30, 100, 101] {
+            assert_eq!(
+                Ok(simulate(steps)),
+                fast_forward((10, 0), steps, step, |s| s.0, |s| s.1),
+                "{steps} steps"
+            );
+        }
+
+        let far = 1_000_000_000_000;
+        assert_eq!(
+            Ok(simulate(8 + far % 7) + far / 7 * 21),
+            fast_forward((10, 0), far + 8, step, |s| s.0, |s| s.1)
+        );
+    }
+
+    #[test]
+    fn test_vector_metric() {
+        let metric = vec![1_i64, 10].extrapolate(&vec![0, 5], &vec![2, 6], 3);
+        assert_eq!(vec![7, 13], metric);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "A u64 metric shrank over a cycle")]
+    fn test_shrinking_u64_metric() {
+        let _ = 10_u64.extrapolate(&5, &3, 2);
+    }
+}
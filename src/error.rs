@@ -1,15 +1,29 @@
+use crate::prelude::*;
+
 #[derive(Debug)]
 pub enum Error {
     ParseIntError,
+    #[cfg(feature = "std")]
     IoError(std::io::Error),
+    UsageError(String),
+    InputError(String),
+}
+
+impl Error {
+    /// Shorthand for rejecting a malformed puzzle input.
+    #[must_use]
+    pub fn input(message: &str) -> Self {
+        Self::InputError(message.to_string())
+    }
 }
 
-impl From<std::num::ParseIntError> for Error {
-    fn from(_error: std::num::ParseIntError) -> Self {
+impl From<core::num::ParseIntError> for Error {
+    fn from(_error: core::num::ParseIntError) -> Self {
         Self::ParseIntError
     }
 }
 
+#[cfg(feature = "std")]
 impl From<std::io::Error> for Error {
     fn from(error: std::io::Error) -> Self {
         Self::IoError(error)
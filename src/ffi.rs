@@ -0,0 +1,231 @@
+//! A C API over the solutions, for calling them from C, Python's `ctypes` and the like through
+//! the `cdylib` build of the crate. The header in `ffi/` is generated from this file by cbindgen.
+
+use std::ffi::{c_char, CString};
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::solutions::{self, Solution};
+
+/// What became of a call. The result string holds the answer on `Ok`, and an explanation on
+/// anything else.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AocStatus {
+    Ok = 0,
+    UnknownDay = 1,
+    UnknownPart = 2,
+    /// The input was rejected by the solver, or isn't UTF-8.
+    InvalidInput = 3,
+    /// A required pointer was null.
+    NullPointer = 4,
+    /// The solver panicked, which is a bug.
+    Panic = 5,
+}
+
+/// Set in the result of `aoc_parts` when part a is solved.
+pub const AOC_PART_A: u8 = 1;
+/// Set in the result of `aoc_parts` when part b is solved.
+pub const AOC_PART_B: u8 = 2;
+
+/// The last day with a solution. Days go from 1 to this.
+#[no_mangle]
+pub const extern "C" fn aoc_max_day() -> u8 {
+    solutions::MAX_SOLVED_DAY
+}
+
+/// Which parts of `day` are solved, as `AOC_PART_A` and `AOC_PART_B` flags.
+#[no_mangle]
+pub extern "C" fn aoc_parts(day: u8) -> u8 {
+    let (part_a, part_b) = solutions::get_solution(day);
+    u8::from(part_a.is_some()) * AOC_PART_A + u8::from(part_b.is_some()) * AOC_PART_B
+}
+
+/// The crate version, as a static string that must not be freed.
+#[no_mangle]
+pub const extern "C" fn aoc_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Solves `part` (`'a'` or `'b'`) of `day` for the `len` bytes of `input`.
+///
+/// Points `result` at a new string with the answer or what went wrong, which must be given back
+/// to `aoc_free`. If `result` itself is null, nothing is written and `NullPointer` is returned.
+///
+/// # Safety
+///
+/// `input` must point to `len` readable bytes, or may be null when `len` is 0. `result` must be
+/// null or point to writable memory for a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(
+    day: u8,
+    part: c_char,
+    input: *const u8,
+    len: usize,
+    result: *mut *mut c_char,
+) -> AocStatus {
+    if result.is_null() {
+        return AocStatus::NullPointer;
+    }
+
+    let (status, message) = if input.is_null() && len > 0 {
+        (AocStatus::NullPointer, "Input is null".to_string())
+    } else {
+        let input = if len == 0 {
+            &[][..]
+        } else {
+            std::slice::from_raw_parts(input, len)
+        };
+        solve(day, part, input)
+    };
+
+    *result = CString::new(message.replace('\0', ""))
+        .unwrap_or_default()
+        .into_raw();
+    status
+}
+
+/// Frees a result string from `aoc_solve`. Null is ignored.
+///
+/// # Safety
+///
+/// `result` must be null or a string from `aoc_solve` that hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_free(result: *mut c_char) {
+    if !result.is_null() {
+        drop(CString::from_raw(result));
+    }
+}
+
+#[allow(clippy::cast_sign_loss)]
+fn solve(day: u8, part: c_char, input: &[u8]) -> (AocStatus, String) {
+    let (part_a, part_b) = solutions::get_solution(day);
+    if part_a.is_none() && part_b.is_none() {
+        return (AocStatus::UnknownDay, format!("No solution for day {day}"));
+    }
+
+    let solution = match part as u8 {
+        b'a' => part_a,
+        b'b' => part_b,
+        _ => None,
+    };
+    let Some(solution) = solution else {
+        return (
+            AocStatus::UnknownPart,
+            format!("No part {:?} for day {}", part as u8 as char, day),
+        );
+    };
+
+    let Ok(input) = std::str::from_utf8(input) else {
+        return (AocStatus::InvalidInput, "Input isn't UTF-8".to_string());
+    };
+
+    // Unwinding across the C boundary would abort the caller, so panics become a status.
+    match panic::catch_unwind(AssertUnwindSafe(|| solution(input))) {
+        Ok(Ok(Solution::Integer(i))) => (AocStatus::Ok, i.to_string()),
+        Ok(Ok(Solution::String(s))) => (AocStatus::Ok, s),
+        Ok(Err(e)) => (AocStatus::InvalidInput, format!("{e:?}")),
+        Err(_) => (AocStatus::Panic, "Solver panicked".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+    use std::ptr;
+
+    use super::*;
+
+    unsafe fn read(result: *const c_char) -> String {
+        CStr::from_ptr(result).to_string_lossy().into_owned()
+    }
+
+    fn call(day: u8, part: u8, input: &[u8]) -> (AocStatus, String) {
+        let mut result = ptr::null_mut();
+        unsafe {
+            let status = aoc_solve(
+                day,
+                part as c_char,
+                input.as_ptr(),
+                input.len(),
+                &mut result,
+            );
+            let message = read(result);
+            aoc_free(result);
+            (status, message)
+        }
+    }
+
+    #[test]
+    fn test_days() {
+        assert_eq!(solutions::MAX_SOLVED_DAY, aoc_max_day());
+        assert_eq!(AOC_PART_A | AOC_PART_B, aoc_parts(1));
+        assert_eq!(0, aoc_parts(0));
+        assert_eq!("0.0.1", unsafe { read(aoc_version()) });
+    }
+
+    #[test]
+    fn test_solve() {
+        let input = b"1000\n2000\n\n4000\n\n5000\n6000";
+
+        assert_eq!((AocStatus::Ok, "11000".to_string()), call(1, b'a', input));
+        assert_eq!((AocStatus::Ok, "18000".to_string()), call(1, b'b', input));
+        assert_eq!(AocStatus::UnknownDay, call(99, b'a', input).0);
+        assert_eq!(AocStatus::UnknownPart, call(1, b'c', input).0);
+        assert_eq!(AocStatus::InvalidInput, call(1, b'a', b"\xff").0);
+        assert_eq!(
+            (
+                AocStatus::InvalidInput,
+                "InputError(\"Program ends before cycle 220\")".to_string()
+            ),
+            call(10, b'a', b"noop")
+        );
+    }
+
+    /// The header is generated from this file; set `AOC_UPDATE_HEADER` to write it out afresh.
+    #[test]
+    fn test_header_is_current() {
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+        let config = cbindgen::Config::from_file(root.join("cbindgen.toml")).unwrap();
+        let mut header = vec![];
+        cbindgen::Builder::new()
+            .with_config(config)
+            .with_src(root.join("src").join("ffi.rs"))
+            .generate()
+            .unwrap()
+            .write(&mut header);
+
+        let path = root.join("ffi").join("advent_of_code_2022.h");
+        if std::env::var_os("AOC_UPDATE_HEADER").is_some() {
+            std::fs::write(&path, &header).unwrap();
+        }
+        assert_eq!(
+            String::from_utf8(header).unwrap(),
+            std::fs::read_to_string(&path).unwrap(),
+            "ffi/advent_of_code_2022.h is out of date"
+        );
+    }
+
+    #[test]
+    fn test_null_pointers() {
+        let mut result = ptr::null_mut();
+        unsafe {
+            assert_eq!(
+                AocStatus::NullPointer,
+                aoc_solve(1, b'a' as c_char, ptr::null(), 3, &mut result)
+            );
+            aoc_free(result);
+
+            assert_eq!(
+                AocStatus::InvalidInput,
+                aoc_solve(10, b'a' as c_char, ptr::null(), 0, &mut result)
+            );
+            aoc_free(result);
+
+            assert_eq!(
+                AocStatus::NullPointer,
+                aoc_solve(1, b'a' as c_char, b"1".as_ptr(), 1, ptr::null_mut())
+            );
+            aoc_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use core::fmt::Write;
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+/// `size` is the number of elves.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let mut input = String::new();
+    let mut totals = vec![];
+
+    for elf in 0..size.max(1) {
+        if elf > 0 {
+            input.push('\n');
+        }
+
+        let mut total = 0;
+        for _ in 0..rng.range(1, 6) {
+            let calories = rng.range(1000, 60000);
+            total += calories;
+            writeln!(input, "{calories}").unwrap();
+        }
+        totals.push(total);
+    }
+
+    totals.sort_unstable_by(|a, b| b.cmp(a));
+
+    Generated {
+        input,
+        part_a: Some(Solution::Integer(totals[0])),
+        part_b: Some(Solution::Integer(totals.iter().take(3).sum())),
+    }
+}
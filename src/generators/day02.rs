@@ -0,0 +1,53 @@
+use core::fmt::Write;
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+/// Score of a round where shapes are 0 (rock), 1 (paper) and 2 (scissors).
+const fn score(opponent: i64, me: i64) -> i64 {
+    me + 1 + (me - opponent + 4) % 3 * 3
+}
+
+/// `size` is the number of rounds in the strategy guide.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let mut input = String::new();
+    let mut total_a = 0;
+    let mut total_b = 0;
+
+    for _ in 0..size.max(1) {
+        let opponent = rng.range(0, 2);
+        let column = rng.range(0, 2);
+
+        total_a += score(opponent, column);
+        // In part b the column is the outcome: 0 lose, 1 draw, 2 win.
+        total_b += score(opponent, (opponent + column + 2) % 3);
+
+        let to_char = |base: u8, v: i64| char::from(base + u8::try_from(v).unwrap());
+        writeln!(
+            input,
+            "{} {}",
+            to_char(b'A', opponent),
+            to_char(b'X', column)
+        )
+        .unwrap();
+    }
+
+    Generated {
+        input,
+        part_a: Some(Solution::Integer(total_a)),
+        part_b: Some(Solution::Integer(total_b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score() {
+        assert_eq!(8, score(0, 1));
+        assert_eq!(1, score(1, 0));
+        assert_eq!(6, score(2, 2));
+    }
+}
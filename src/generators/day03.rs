@@ -0,0 +1,51 @@
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+const ITEMS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+#[allow(clippy::cast_possible_wrap)]
+fn priority(item: u8) -> i64 {
+    ITEMS.iter().position(|&i| i == item).unwrap() as i64 + 1
+}
+
+/// `size` is the number of groups of three elves.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let mut input = String::new();
+    let mut total_a = 0;
+    let mut total_b = 0;
+
+    for _ in 0..size.max(1) {
+        let mut items = ITEMS.to_vec();
+        rng.shuffle(&mut items);
+
+        // The badge is in every backpack of the group, every other item type belongs to a
+        // single backpack so the badge is the only one shared by all three.
+        let badge = items[0];
+        total_b += priority(badge);
+
+        for pool in items[1..].chunks(17) {
+            let (shared, rest) = pool.split_first().unwrap();
+            let (first_pool, second_pool) = rest.split_at(rest.len() / 2);
+            let length = rng.below(14) + 2;
+
+            let mut first = vec![badge, *shared];
+            first.extend((2..length).map(|_| *rng.choose(first_pool)));
+            rng.shuffle(&mut first);
+
+            let mut second = vec![*shared];
+            second.extend((1..length).map(|_| *rng.choose(second_pool)));
+            rng.shuffle(&mut second);
+
+            total_a += priority(*shared);
+            input.extend(first.iter().chain(&second).map(|&c| char::from(c)));
+            input.push('\n');
+        }
+    }
+
+    Generated {
+        input,
+        part_a: Some(Solution::Integer(total_a)),
+        part_b: Some(Solution::Integer(total_b)),
+    }
+}
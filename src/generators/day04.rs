@@ -0,0 +1,34 @@
+use core::fmt::Write;
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+/// `size` is the number of pairs of elves.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let mut input = String::new();
+    let mut contained = 0;
+    let mut overlapping = 0;
+
+    for _ in 0..size.max(1) {
+        let a = rng.range(1, 99);
+        let b = rng.range(a, 99);
+        let c = rng.range(1, 99);
+        let d = rng.range(c, 99);
+
+        if (a <= c && d <= b) || (c <= a && b <= d) {
+            contained += 1;
+        }
+        if a <= d && c <= b {
+            overlapping += 1;
+        }
+
+        writeln!(input, "{a}-{b},{c}-{d}").unwrap();
+    }
+
+    Generated {
+        input,
+        part_a: Some(Solution::Integer(contained)),
+        part_b: Some(Solution::Integer(overlapping)),
+    }
+}
@@ -0,0 +1,94 @@
+use core::fmt::Write;
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+fn tops(stacks: &[Vec<char>]) -> Solution {
+    Solution::String(stacks.iter().filter_map(|s| s.last()).collect())
+}
+
+fn draw_stacks(stacks: &[Vec<char>]) -> String {
+    let mut drawing = String::new();
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+
+    for level in (0..height).rev() {
+        let line = stacks
+            .iter()
+            .map(|s| {
+                s.get(level)
+                    .map_or_else(|| "   ".to_string(), |c| format!("[{c}]"))
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        drawing.push_str(&line);
+        drawing.push('\n');
+    }
+
+    let labels = (1..=stacks.len())
+        .map(|i| format!("{i:^3}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    drawing.push_str(&labels);
+    drawing.push('\n');
+
+    drawing
+}
+
+/// `size` is the number of stacks, ten moves are generated per stack.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let count = size.max(1);
+
+    let stacks: Vec<Vec<char>> = (0..count)
+        .map(|_| {
+            (0..=rng.below(8))
+                .map(|_| char::from(b'A' + u8::try_from(rng.below(26)).unwrap()))
+                .collect()
+        })
+        .collect();
+
+    let mut input = draw_stacks(&stacks);
+    input.push('\n');
+
+    let mut stacks_a = stacks.clone();
+    let mut stacks_b = stacks;
+
+    for _ in 0..count * 10 {
+        let non_empty = (0..count)
+            .filter(|&i| !stacks_a[i].is_empty())
+            .collect::<Vec<_>>();
+        let from = *rng.choose(&non_empty);
+        let to = rng.below(count);
+        let crates = rng.below(stacks_a[from].len()) + 1;
+
+        writeln!(input, "move {} from {} to {}", crates, from + 1, to + 1).unwrap();
+
+        for _ in 0..crates {
+            let c = stacks_a[from].pop().unwrap();
+            stacks_a[to].push(c);
+        }
+
+        let split = stacks_b[from].len() - crates;
+        let mut taken = stacks_b[from].split_off(split);
+        stacks_b[to].append(&mut taken);
+    }
+
+    Generated {
+        input,
+        part_a: Some(tops(&stacks_a)),
+        part_b: Some(tops(&stacks_b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_stacks() {
+        assert_eq!(
+            "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n",
+            draw_stacks(&[vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']])
+        );
+    }
+}
@@ -0,0 +1,29 @@
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+#[allow(clippy::cast_possible_wrap)]
+fn find_marker(stream: &[u8], length: usize) -> i64 {
+    stream
+        .windows(length)
+        .position(|w| (1..w.len()).all(|i| !w[..i].contains(&w[i])))
+        .map_or(0, |p| (p + length) as i64)
+}
+
+/// `size` is the length of the noise before and after the guaranteed start-of-message marker.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    // Three letters can never form a marker, so the prefix pushes the answers further in.
+    let mut stream: Vec<u8> = (0..size).map(|_| *rng.choose(b"abc")).collect();
+
+    let mut letters = (b'a'..=b'z').collect::<Vec<_>>();
+    rng.shuffle(&mut letters);
+    stream.extend(&letters[..14]);
+
+    stream.extend((0..size).map(|_| b'a' + u8::try_from(rng.below(26)).unwrap()));
+
+    Generated {
+        part_a: Some(Solution::Integer(find_marker(&stream, 4))),
+        part_b: Some(Solution::Integer(find_marker(&stream, 14))),
+        input: String::from_utf8(stream).unwrap(),
+    }
+}
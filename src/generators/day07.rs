@@ -0,0 +1,128 @@
+use core::fmt::Write;
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+struct Directory {
+    files: Vec<(String, i64)>,
+    children: Vec<(String, Self)>,
+}
+
+impl Directory {
+    fn total_size(&self, sizes: &mut Vec<i64>) -> i64 {
+        let size = self.files.iter().map(|f| f.1).sum::<i64>()
+            + self
+                .children
+                .iter()
+                .map(|c| c.1.total_size(sizes))
+                .sum::<i64>();
+        sizes.push(size);
+        size
+    }
+
+    fn write_session(&self, input: &mut String) {
+        input.push_str("$ ls\n");
+        for (name, _) in &self.children {
+            writeln!(input, "dir {name}").unwrap();
+        }
+        for (name, size) in &self.files {
+            writeln!(input, "{size} {name}").unwrap();
+        }
+        for (name, child) in &self.children {
+            writeln!(input, "$ cd {name}").unwrap();
+            child.write_session(input);
+            input.push_str("$ cd ..\n");
+        }
+    }
+}
+
+fn random_name(rng: &mut Rng, taken: &[String]) -> String {
+    loop {
+        let name = (0..rng.range(1, 8))
+            .map(|_| char::from(b'a' + u8::try_from(rng.below(26)).unwrap()))
+            .collect::<String>();
+
+        if !taken.contains(&name) {
+            return name;
+        }
+    }
+}
+
+fn random_files(rng: &mut Rng) -> Vec<(String, i64)> {
+    let mut names: Vec<String> = vec![];
+    for _ in 0..rng.below(5) {
+        let mut name = random_name(rng, &names);
+        if rng.one_in(2) {
+            name.push_str(rng.choose(&[".txt", ".dat", ".lst"]).as_ref());
+        }
+        names.push(name);
+    }
+
+    names
+        .into_iter()
+        .map(|n| {
+            let size = if rng.one_in(3) {
+                rng.range(1, 100_000)
+            } else {
+                rng.range(100_000, 400_000)
+            };
+            (n, size)
+        })
+        .collect()
+}
+
+fn random_tree(rng: &mut Rng, directories: usize) -> Directory {
+    let mut children = vec![];
+    let mut remaining = directories;
+
+    while remaining > 0 {
+        let names = children
+            .iter()
+            .map(|c: &(String, Directory)| c.0.clone())
+            .collect::<Vec<_>>();
+        let name = random_name(rng, &names);
+        let nested = rng.below(remaining);
+        remaining -= nested + 1;
+        children.push((name, random_tree(rng, nested)));
+    }
+
+    Directory {
+        files: random_files(rng),
+        children,
+    }
+}
+
+/// `size` is the number of directories below the root.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let mut root = random_tree(rng, size);
+
+    let mut sizes = vec![];
+    let mut used = root.total_size(&mut sizes);
+
+    // Make sure the disk is full enough that something has to be deleted.
+    if used <= 40_000_000 {
+        let names = root.files.iter().map(|f| f.0.clone()).collect::<Vec<_>>();
+        let name = random_name(rng, &names);
+        root.files.push((name, 40_000_001 - used));
+        sizes.clear();
+        used = root.total_size(&mut sizes);
+    }
+
+    let mut input = "$ cd /\n".to_string();
+    root.write_session(&mut input);
+
+    let to_free = used - 40_000_000;
+
+    Generated {
+        input,
+        part_a: Some(Solution::Integer(
+            sizes.iter().filter(|&&s| s <= 100_000).sum(),
+        )),
+        part_b: sizes
+            .iter()
+            .filter(|&&s| s >= to_free)
+            .min()
+            .map(|&s| Solution::Integer(s)),
+    }
+}
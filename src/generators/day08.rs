@@ -0,0 +1,69 @@
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+const DIRECTIONS: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+/// Walks from `(x, y)` in direction `d`, returning the trees passed and whether the edge was reached.
+fn look(forest: &[Vec<u8>], x: usize, y: usize, d: (isize, isize)) -> (i64, bool) {
+    let height = forest[y][x];
+    let mut seen = 0;
+    let (mut x, mut y) = (x, y);
+
+    loop {
+        let (Some(nx), Some(ny)) = (x.checked_add_signed(d.0), y.checked_add_signed(d.1)) else {
+            return (seen, true);
+        };
+        let Some(&tree) = forest.get(ny).and_then(|row| row.get(nx)) else {
+            return (seen, true);
+        };
+
+        seen += 1;
+        if tree >= height {
+            return (seen, false);
+        }
+        (x, y) = (nx, ny);
+    }
+}
+
+/// `size` is the width and height of the forest.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let width = size.max(1);
+    let forest: Vec<Vec<u8>> = (0..width)
+        .map(|_| {
+            (0..width)
+                .map(|_| u8::try_from(rng.below(10)).unwrap())
+                .collect()
+        })
+        .collect();
+
+    let mut visible = 0;
+    let mut best_scene = 0;
+
+    for y in 0..width {
+        for x in 0..width {
+            let views = DIRECTIONS.map(|d| look(&forest, x, y, d));
+
+            if views.iter().any(|v| v.1) {
+                visible += 1;
+            }
+            best_scene = best_scene.max(views.iter().map(|v| v.0).product());
+        }
+    }
+
+    let input = forest
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&t| char::from(b'0' + t))
+                .collect::<String>()
+                + "\n"
+        })
+        .collect();
+
+    Generated {
+        input,
+        part_a: Some(Solution::Integer(visible)),
+        part_b: Some(Solution::Integer(best_scene)),
+    }
+}
@@ -0,0 +1,58 @@
+use core::fmt::Write;
+use hashbrown::HashSet;
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+#[allow(clippy::cast_possible_wrap)]
+fn tail_positions(moves: &[(char, i32)], knots: usize) -> i64 {
+    let mut rope = vec![(0, 0); knots];
+    let mut visited: HashSet<_> = HashSet::from([(0, 0)]);
+
+    for &(direction, steps) in moves {
+        for _ in 0..steps {
+            match direction {
+                'L' => rope[0].0 -= 1,
+                'R' => rope[0].0 += 1,
+                'U' => rope[0].1 -= 1,
+                _ => rope[0].1 += 1,
+            }
+
+            for i in 1..knots {
+                let (ahead, knot): ((i32, i32), (i32, i32)) = (rope[i - 1], rope[i]);
+                if (ahead.0 - knot.0).abs() > 1 || (ahead.1 - knot.1).abs() > 1 {
+                    rope[i] = (
+                        knot.0 + (ahead.0 - knot.0).signum(),
+                        knot.1 + (ahead.1 - knot.1).signum(),
+                    );
+                }
+            }
+
+            visited.insert(rope[knots - 1]);
+        }
+    }
+
+    visited.len() as i64
+}
+
+/// `size` is the number of head motions.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let moves = (0..size.max(1))
+        .map(|_| {
+            let steps = i32::try_from(rng.range(1, 20)).unwrap();
+            (*rng.choose(&['U', 'D', 'L', 'R']), steps)
+        })
+        .collect::<Vec<_>>();
+
+    let mut input = String::new();
+    for (direction, steps) in &moves {
+        writeln!(input, "{direction} {steps}").unwrap();
+    }
+
+    Generated {
+        input,
+        part_a: Some(Solution::Integer(tail_positions(&moves, 2))),
+        part_b: Some(Solution::Integer(tail_positions(&moves, 10))),
+    }
+}
@@ -0,0 +1,49 @@
+use core::fmt::Write;
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+const CYCLES: usize = 240;
+
+/// `size` bounds the magnitude of the `addx` arguments; the program always runs for 240 cycles.
+#[allow(clippy::cast_possible_wrap)]
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let limit = i64::try_from(size.max(1)).unwrap();
+
+    let mut input = String::new();
+    let mut during = vec![];
+    let mut x: i64 = 1;
+
+    while during.len() < CYCLES {
+        if during.len() + 2 <= CYCLES && !rng.one_in(3) {
+            let v = rng.range(-limit, limit);
+            writeln!(input, "addx {v}").unwrap();
+            during.extend([x, x]);
+            x += v;
+        } else {
+            input.push_str("noop\n");
+            during.push(x);
+        }
+    }
+
+    let strength = (20..=220)
+        .step_by(40)
+        .map(|cycle| cycle as i64 * during[cycle - 1])
+        .sum();
+
+    let mut screen = "\n".to_string();
+    for (cycle, x) in during.iter().enumerate() {
+        let column = (cycle % 40) as i64;
+        screen.push(if (column - x).abs() <= 1 { '#' } else { '.' });
+        if column == 39 {
+            screen.push('\n');
+        }
+    }
+
+    Generated {
+        input,
+        part_a: Some(Solution::Integer(strength)),
+        part_b: Some(Solution::String(screen)),
+    }
+}
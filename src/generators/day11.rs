@@ -0,0 +1,141 @@
+use core::fmt::Write;
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+/// Keeps the product of the tests small enough that squaring a managed worry fits in `i64`.
+const TESTS: [i64; 9] = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+
+#[derive(Clone)]
+enum Operation {
+    Add(i64),
+    Multiply(i64),
+    Square,
+}
+
+#[derive(Clone)]
+struct Monkey {
+    items: Vec<i64>,
+    operation: Operation,
+    test: i64,
+    targets: (usize, usize),
+}
+
+/// Plays `rounds` rounds, returning `None` if a worry level would overflow.
+fn monkey_business(mut monkeys: Vec<Monkey>, rounds: usize, relief: bool) -> Option<i64> {
+    let modulus: i64 = monkeys.iter().map(|m| m.test).product();
+    let mut inspections = vec![0; monkeys.len()];
+
+    for _ in 0..rounds {
+        for m in 0..monkeys.len() {
+            for worry in core::mem::take(&mut monkeys[m].items) {
+                let worry = match monkeys[m].operation {
+                    Operation::Add(v) => worry.checked_add(v)?,
+                    Operation::Multiply(v) => worry.checked_mul(v)?,
+                    Operation::Square => worry.checked_mul(worry)?,
+                };
+                let worry = if relief { worry / 3 } else { worry % modulus };
+
+                let (if_true, if_false) = monkeys[m].targets;
+                let target = if worry % monkeys[m].test == 0 {
+                    if_true
+                } else {
+                    if_false
+                };
+                monkeys[target].items.push(worry);
+                inspections[m] += 1;
+            }
+        }
+    }
+
+    inspections.sort_unstable_by(|a, b| b.cmp(a));
+    Some(inspections[0] * inspections[1])
+}
+
+fn random_monkeys(rng: &mut Rng, count: usize, with_square: bool) -> Vec<Monkey> {
+    let mut tests = TESTS.to_vec();
+    rng.shuffle(&mut tests);
+    let square = with_square.then(|| rng.below(count));
+
+    (0..count)
+        .map(|i| {
+            let others = (0..count).filter(|&o| o != i).collect::<Vec<_>>();
+            let if_true = *rng.choose(&others);
+            let others = others
+                .into_iter()
+                .filter(|&o| o != if_true)
+                .collect::<Vec<_>>();
+            let if_false = if others.is_empty() {
+                if_true
+            } else {
+                *rng.choose(&others)
+            };
+
+            Monkey {
+                items: (0..rng.range(1, 6)).map(|_| rng.range(50, 99)).collect(),
+                operation: if Some(i) == square {
+                    Operation::Square
+                } else if rng.one_in(2) {
+                    Operation::Add(rng.range(1, 8))
+                } else {
+                    Operation::Multiply(rng.range(2, 19))
+                },
+                test: tests[i],
+                targets: (if_true, if_false),
+            }
+        })
+        .collect()
+}
+
+fn describe(monkeys: &[Monkey]) -> String {
+    let mut input = String::new();
+
+    for (i, monkey) in monkeys.iter().enumerate() {
+        if i > 0 {
+            input.push('\n');
+        }
+
+        let items = monkey
+            .items
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        let operation = match monkey.operation {
+            Operation::Add(v) => format!("+ {v}"),
+            Operation::Multiply(v) => format!("* {v}"),
+            Operation::Square => "* old".to_string(),
+        };
+
+        writeln!(input, "Monkey {i}:").unwrap();
+        writeln!(input, "  Starting items: {items}").unwrap();
+        writeln!(input, "  Operation: new = old {operation}").unwrap();
+        writeln!(input, "  Test: divisible by {}", monkey.test).unwrap();
+        writeln!(input, "    If true: throw to monkey {}", monkey.targets.0).unwrap();
+        writeln!(input, "    If false: throw to monkey {}", monkey.targets.1).unwrap();
+    }
+
+    input
+}
+
+/// `size` is the number of monkeys, between 2 and 9.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let count = size.clamp(2, TESTS.len());
+
+    // Part a has no modulus to keep worries small, so retry until a troop stays in range. Small
+    // troops pass items back to the squaring monkey too often, so those go without one.
+    for attempt in 0.. {
+        let monkeys = random_monkeys(rng, count, count >= 4 && attempt < 100);
+
+        if let Some(part_a) = monkey_business(monkeys.clone(), 20, true) {
+            return Generated {
+                input: describe(&monkeys),
+                part_a: Some(Solution::Integer(part_a)),
+                part_b: monkey_business(monkeys, 10_000, false).map(Solution::Integer),
+            };
+        }
+    }
+
+    unreachable!()
+}
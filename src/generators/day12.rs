@@ -0,0 +1,92 @@
+use alloc::collections::VecDeque;
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+/// Breadth first search from `start`, where `can_step(from, to)` compares heights.
+fn steps<F, G>(heights: &[Vec<u8>], start: (usize, usize), is_goal: G, can_step: F) -> Option<i64>
+where
+    F: Fn(u8, u8) -> bool,
+    G: Fn((usize, usize)) -> bool,
+{
+    let mut distance = vec![vec![None; heights[0].len()]; heights.len()];
+    let mut queue = VecDeque::from([start]);
+    distance[start.1][start.0] = Some(0);
+
+    while let Some((x, y)) = queue.pop_front() {
+        let d = distance[y][x]?;
+        if is_goal((x, y)) {
+            return Some(d);
+        }
+
+        for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+            let (Some(nx), Some(ny)) = (x.checked_add_signed(dx), y.checked_add_signed(dy)) else {
+                continue;
+            };
+            if ny < heights.len()
+                && nx < heights[0].len()
+                && distance[ny][nx].is_none()
+                && can_step(heights[y][x], heights[ny][nx])
+            {
+                distance[ny][nx] = Some(d + 1);
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+
+    None
+}
+
+/// `size` is the width of the heightmap, which is at least 26 so the summit can be reached.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let width = size.max(26);
+    let height = (width / 3).max(5);
+    let trail = rng.below(height);
+
+    // Heights climb one letter at a time from the left edge to the right edge. The trail row
+    // and the left column keep that clean gradient, everything else gets random noise.
+    let heights: Vec<Vec<u8>> = (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    let gradient = u8::try_from(x * 25 / (width - 1)).unwrap();
+                    if y != trail && x > 0 && rng.one_in(3) {
+                        u8::try_from(rng.below(26)).unwrap()
+                    } else {
+                        gradient
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    let start = (0, rng.below(height));
+    let end = (width - 1, trail);
+
+    let mut input = String::new();
+    for (y, row) in heights.iter().enumerate() {
+        for (x, &h) in row.iter().enumerate() {
+            input.push(match (x, y) {
+                p if p == start => 'S',
+                p if p == end => 'E',
+                _ => char::from(b'a' + h),
+            });
+        }
+        input.push('\n');
+    }
+
+    let up = steps(&heights, start, |p| p == end, |from, to| to <= from + 1);
+    let down = steps(
+        &heights,
+        end,
+        |(x, y)| heights[y][x] == 0,
+        |from, to| from <= to + 1,
+    );
+
+    Generated {
+        input,
+        part_a: up.map(Solution::Integer),
+        part_b: down.map(Solution::Integer),
+    }
+}
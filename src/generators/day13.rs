@@ -0,0 +1,105 @@
+use core::cmp::Ordering;
+use core::fmt::Write;
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+enum Packet {
+    Integer(u32),
+    List(Vec<Self>),
+}
+
+impl Packet {
+    fn random(rng: &mut Rng, depth: usize) -> Self {
+        if depth > 0 && rng.one_in(3) {
+            Self::Integer(u32::try_from(rng.below(11)).unwrap())
+        } else {
+            let length = if depth >= 4 { 0 } else { rng.below(5) };
+            Self::List((0..length).map(|_| Self::random(rng, depth + 1)).collect())
+        }
+    }
+
+    fn divider(value: u32) -> Self {
+        Self::List(vec![Self::List(vec![Self::Integer(value)])])
+    }
+
+    fn compare(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Integer(a), Self::Integer(b)) => a.cmp(b),
+            (Self::List(a), Self::List(b)) => {
+                for (x, y) in a.iter().zip(b) {
+                    let order = x.compare(y);
+                    if order != Ordering::Equal {
+                        return order;
+                    }
+                }
+                a.len().cmp(&b.len())
+            }
+            (Self::Integer(a), b) => Self::List(vec![Self::Integer(*a)]).compare(b),
+            (a, Self::Integer(b)) => a.compare(&Self::List(vec![Self::Integer(*b)])),
+        }
+    }
+
+    fn write(&self, out: &mut String) {
+        match self {
+            Self::Integer(v) => write!(out, "{v}").unwrap(),
+            Self::List(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    item.write(out);
+                }
+                out.push(']');
+            }
+        }
+    }
+}
+
+/// `size` is the number of packet pairs.
+#[allow(clippy::cast_possible_wrap)]
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let dividers = [Packet::divider(2), Packet::divider(6)];
+
+    let mut input = String::new();
+    let mut ordered = 0;
+    let mut before_dividers = [1, 2];
+
+    for pair in 0..size.max(1) {
+        // Packets equal to a divider would make the divider position ambiguous.
+        let mut packets = [Packet::random(rng, 0), Packet::random(rng, 0)];
+        while packets
+            .iter()
+            .any(|p| dividers.iter().any(|d| p.compare(d) == Ordering::Equal))
+        {
+            packets = [Packet::random(rng, 0), Packet::random(rng, 0)];
+        }
+
+        if packets[0].compare(&packets[1]) == Ordering::Less {
+            ordered += pair as i64 + 1;
+        }
+        for packet in &packets {
+            for (count, divider) in before_dividers.iter_mut().zip(&dividers) {
+                if packet.compare(divider) == Ordering::Less {
+                    *count += 1;
+                }
+            }
+        }
+
+        if pair > 0 {
+            input.push('\n');
+        }
+        for packet in &packets {
+            packet.write(&mut input);
+            input.push('\n');
+        }
+    }
+
+    Generated {
+        input,
+        part_a: Some(Solution::Integer(ordered)),
+        part_b: Some(Solution::Integer(before_dividers[0] * before_dividers[1])),
+    }
+}
@@ -0,0 +1,77 @@
+use core::fmt::Write;
+
+use hashbrown::HashSet;
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+/// Drops grains from (500, 0) one at a time until one falls below the lowest rock, or with
+/// `floor` until the source is covered, and counts the ones that stay.
+fn pour(rocks: &HashSet<(i64, i64)>, floor: bool) -> i64 {
+    let lowest = rocks.iter().map(|&(_, y)| y).max().unwrap_or(0);
+    let mut filled = rocks.clone();
+    let mut resting = 0;
+
+    while !filled.contains(&(500, 0)) {
+        let (mut x, mut y) = (500, 0);
+        loop {
+            if y > lowest {
+                break;
+            }
+            if let Some(dx) = [0, -1, 1]
+                .into_iter()
+                .find(|dx| !filled.contains(&(x + dx, y + 1)))
+            {
+                x += dx;
+                y += 1;
+            } else {
+                break;
+            }
+        }
+
+        // Past the lowest rock a grain falls forever, or lands on the floor just below it.
+        if y > lowest && !floor {
+            break;
+        }
+        filled.insert((x, y));
+        resting += 1;
+    }
+
+    resting
+}
+
+/// `size` is the number of rock paths.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let mut rocks = HashSet::new();
+    let mut input = String::new();
+
+    for _ in 0..size.max(1) {
+        let mut x = rng.range(480, 520);
+        let mut y = rng.range(2, 30);
+        let _ = write!(input, "{x},{y}");
+        rocks.insert((x, y));
+
+        for corner in 0..rng.range(1, 4) {
+            let length = rng.range(1, 8);
+            let (dx, dy) = if corner % 2 == 0 {
+                (*rng.choose(&[-1, 1]), 0)
+            } else {
+                (0, 1)
+            };
+            for _ in 0..length {
+                x += dx;
+                y += dy;
+                rocks.insert((x, y));
+            }
+            let _ = write!(input, " -> {x},{y}");
+        }
+        input.push('\n');
+    }
+
+    Generated {
+        input,
+        part_a: Some(Solution::Integer(pour(&rocks, false))),
+        part_b: Some(Solution::Integer(pour(&rocks, true))),
+    }
+}
@@ -0,0 +1,44 @@
+use core::fmt::Write;
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+/// The row part a looks along.
+const ROW: i64 = 2_000_000;
+
+/// `size` is the number of sensors, all placed near the row part a looks along. Where the
+/// distress beacon is isn't known, so there is no answer for part b.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let mut input = String::new();
+    let mut sensors = vec![];
+
+    for _ in 0..size.max(1) {
+        let sensor = (rng.range(-50, 50), ROW + rng.range(-30, 30));
+        let beacon = (sensor.0 + rng.range(-20, 20), sensor.1 + rng.range(-20, 20));
+        let radius = (sensor.0 - beacon.0).abs() + (sensor.1 - beacon.1).abs();
+        sensors.push((sensor, beacon, radius));
+
+        writeln!(
+            input,
+            "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+            sensor.0, sensor.1, beacon.0, beacon.1
+        )
+        .unwrap();
+    }
+
+    let excluded = (-200..=200)
+        .filter(|&x| {
+            sensors
+                .iter()
+                .any(|&(s, _, radius)| (s.0 - x).abs() + (s.1 - ROW).abs() <= radius)
+                && !sensors.iter().any(|&(_, b, _)| b == (x, ROW))
+        })
+        .count();
+
+    Generated {
+        input,
+        part_a: Some(Solution::Integer(i64::try_from(excluded).unwrap())),
+        part_b: None,
+    }
+}
@@ -0,0 +1,121 @@
+use core::fmt::Write;
+
+use hashbrown::HashMap;
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+struct Cave {
+    flows: Vec<u32>,
+    tunnels: Vec<Vec<usize>>,
+}
+
+impl Cave {
+    /// The most pressure released from `at` with `left` minutes, a minute at a time: opening the
+    /// valve there if it's `allowed`, or walking down a tunnel.
+    fn release(
+        &self,
+        at: usize,
+        opened: u64,
+        left: u32,
+        allowed: u64,
+        memo: &mut HashMap<(usize, u64, u32), u32>,
+    ) -> u32 {
+        if left == 0 {
+            return 0;
+        }
+        if let Some(&best) = memo.get(&(at, opened, left)) {
+            return best;
+        }
+
+        let mut best = if self.flows[at] > 0 && allowed & !opened & (1 << at) != 0 {
+            self.flows[at] * (left - 1)
+                + self.release(at, opened | 1 << at, left - 1, allowed, memo)
+        } else {
+            0
+        };
+        for &next in &self.tunnels[at] {
+            best = best.max(self.release(next, opened, left - 1, allowed, memo));
+        }
+
+        memo.insert((at, opened, left), best);
+        best
+    }
+}
+
+fn name(i: usize) -> String {
+    let letter = |n: usize| char::from(b'A' + u8::try_from(n % 26).unwrap());
+    [letter(i / 26), letter(i)].iter().collect()
+}
+
+/// `size` is the number of valves, at most 26 of which are generated, with up to 5 of them
+/// releasing pressure. The valves are joined up along a random tree with a few extra tunnels.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let count = size.clamp(2, 26);
+    let mut tunnels = vec![vec![]; count];
+    for i in 1..count {
+        let j = rng.below(i);
+        tunnels[i].push(j);
+        tunnels[j].push(i);
+    }
+    for _ in 0..count / 4 {
+        let (i, j) = (rng.below(count), rng.below(count));
+        if i != j && !tunnels[i].contains(&j) {
+            tunnels[i].push(j);
+            tunnels[j].push(i);
+        }
+    }
+
+    let mut flows = vec![0; count];
+    for _ in 0..5 {
+        let i = 1 + rng.below(count - 1);
+        flows[i] = u32::try_from(rng.range(1, 25)).unwrap();
+    }
+
+    let mut input = String::new();
+    for (i, (flow, tunnels)) in flows.iter().zip(&tunnels).enumerate() {
+        let names: Vec<String> = tunnels.iter().map(|&j| name(j)).collect();
+        let (plural, verb) = if names.len() == 1 {
+            ("", "s")
+        } else {
+            ("s", "")
+        };
+        writeln!(
+            input,
+            "Valve {} has flow rate={}; tunnel{} lead{} to valve{} {}",
+            name(i),
+            flow,
+            plural,
+            verb,
+            plural,
+            names.join(", ")
+        )
+        .unwrap();
+    }
+
+    let cave = Cave { flows, tunnels };
+    let all = (1 << count) - 1;
+    let alone = cave.release(0, 0, 30, all, &mut HashMap::new());
+
+    // The agents never open the same valve, so try every way of sharing the flowing ones out.
+    let flowing: Vec<usize> = (0..count).filter(|&i| cave.flows[i] > 0).collect();
+    let together = (0..1_u64 << flowing.len())
+        .map(|split| {
+            let mine = flowing
+                .iter()
+                .enumerate()
+                .filter(|(bit, _)| split & (1 << bit) != 0)
+                .fold(0, |mask, (_, &i)| mask | 1 << i);
+            cave.release(0, 0, 26, mine, &mut HashMap::new())
+                + cave.release(0, 0, 26, all ^ mine, &mut HashMap::new())
+        })
+        .max()
+        .unwrap_or_default();
+
+    Generated {
+        input,
+        part_a: Some(Solution::Integer(i64::from(alone))),
+        part_b: Some(Solution::Integer(i64::from(together))),
+    }
+}
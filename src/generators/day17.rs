@@ -0,0 +1,68 @@
+use hashbrown::HashSet;
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+/// The rock shapes as cells from their bottom left corner.
+const SHAPES: [&[(i64, i64)]; 5] = [
+    &[(0, 0), (1, 0), (2, 0), (3, 0)],
+    &[(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)],
+    &[(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)],
+    &[(0, 0), (0, 1), (0, 2), (0, 3)],
+    &[(0, 0), (1, 0), (0, 1), (1, 1)],
+];
+
+/// The tower height after `rocks` rocks, cell by cell.
+fn height(jets: &[i64], rocks: usize) -> i64 {
+    let mut settled = HashSet::new();
+    let mut top = 0;
+    let mut jet = 0;
+
+    for rock in 0..rocks {
+        let shape = SHAPES[rock % SHAPES.len()];
+        let (mut x, mut y) = (2, top + 3);
+        let free = |x: i64, y: i64, settled: &HashSet<(i64, i64)>| {
+            shape.iter().all(|&(dx, dy)| {
+                (0..7).contains(&(x + dx)) && y + dy >= 0 && !settled.contains(&(x + dx, y + dy))
+            })
+        };
+
+        loop {
+            let push = jets[jet % jets.len()];
+            jet += 1;
+            if free(x + push, y, &settled) {
+                x += push;
+            }
+            if !free(x, y - 1, &settled) {
+                break;
+            }
+            y -= 1;
+        }
+
+        for &(dx, dy) in shape {
+            settled.insert((x + dx, y + dy));
+            top = top.max(y + dy + 1);
+        }
+    }
+
+    top
+}
+
+/// `size` is the length of the jet pattern. The tower for part b is far too tall to build, so
+/// there is no answer for it.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let jets: Vec<i64> = (0..size.max(1)).map(|_| *rng.choose(&[-1, 1])).collect();
+
+    let mut input: String = jets
+        .iter()
+        .map(|&push| if push < 0 { '<' } else { '>' })
+        .collect();
+    input.push('\n');
+
+    Generated {
+        input,
+        part_a: Some(Solution::Integer(height(&jets, 2022))),
+        part_b: None,
+    }
+}
@@ -0,0 +1,74 @@
+use core::fmt::Write;
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+/// `size` is the number of cubes, scattered in a box just big enough to leave some air pockets.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let edge = 3 + size.max(1).ilog2() as usize;
+    // One empty layer on every side, so the outside air is connected all the way round.
+    let cells = edge + 2;
+    let mut filled = vec![vec![vec![false; cells]; cells]; cells];
+    let mut input = String::new();
+
+    for _ in 0..size.max(1) {
+        let (x, y, z) = (rng.below(edge), rng.below(edge), rng.below(edge));
+        if !filled[x + 1][y + 1][z + 1] {
+            filled[x + 1][y + 1][z + 1] = true;
+            writeln!(input, "{x},{y},{z}").unwrap();
+        }
+    }
+
+    let neighbours = |(x, y, z): (usize, usize, usize)| {
+        [
+            (x.wrapping_sub(1), y, z),
+            (x + 1, y, z),
+            (x, y.wrapping_sub(1), z),
+            (x, y + 1, z),
+            (x, y, z.wrapping_sub(1)),
+            (x, y, z + 1),
+        ]
+        .into_iter()
+        .filter(|&(x, y, z)| x < cells && y < cells && z < cells)
+    };
+    let cubes =
+        (0..cells).flat_map(|x| (0..cells).flat_map(move |y| (0..cells).map(move |z| (x, y, z))));
+
+    // Every face of a cube whose neighbour isn't a cube is exposed.
+    let exposed = cubes
+        .clone()
+        .filter(|&(x, y, z)| filled[x][y][z])
+        .map(|cube| {
+            6 - neighbours(cube)
+                .filter(|&(x, y, z)| filled[x][y][z])
+                .count()
+        })
+        .sum::<usize>();
+
+    let mut outside = vec![vec![vec![false; cells]; cells]; cells];
+    outside[0][0][0] = true;
+    let mut stack = vec![(0, 0, 0)];
+    while let Some(air) = stack.pop() {
+        for (x, y, z) in neighbours(air) {
+            if !filled[x][y][z] && !outside[x][y][z] {
+                outside[x][y][z] = true;
+                stack.push((x, y, z));
+            }
+        }
+    }
+    let exterior = cubes
+        .filter(|&(x, y, z)| filled[x][y][z])
+        .map(|cube| {
+            neighbours(cube)
+                .filter(|&(x, y, z)| outside[x][y][z])
+                .count()
+        })
+        .sum::<usize>();
+
+    Generated {
+        input,
+        part_a: Some(Solution::Integer(i64::try_from(exposed).unwrap())),
+        part_b: Some(Solution::Integer(i64::try_from(exterior).unwrap())),
+    }
+}
@@ -0,0 +1,33 @@
+use core::fmt::Write;
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+
+/// `size` is the number of blueprints, with costs in the ranges the puzzle uses. Working out the
+/// best geode counts takes the same search as the solution, so neither answer is known.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let mut input = String::new();
+
+    for id in 1..=size.max(1) {
+        writeln!(
+            input,
+            "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. \
+             Each obsidian robot costs {} ore and {} clay. \
+             Each geode robot costs {} ore and {} obsidian.",
+            id,
+            rng.range(2, 4),
+            rng.range(2, 4),
+            rng.range(2, 4),
+            rng.range(5, 20),
+            rng.range(2, 4),
+            rng.range(5, 20),
+        )
+        .unwrap();
+    }
+
+    Generated {
+        input,
+        part_a: None,
+        part_b: None,
+    }
+}
@@ -0,0 +1,65 @@
+use core::fmt::Write;
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+/// Mixes `numbers` `rounds` times by rotating each one along a copy of the list step by step,
+/// and sums the numbers 1000, 2000 and 3000 after the 0.
+fn mix(numbers: &[i64], rounds: usize) -> i64 {
+    let len = numbers.len();
+    // `ring[i]` is the input index of the number in place `i`.
+    let mut ring: Vec<usize> = (0..len).collect();
+
+    if len > 1 {
+        let steps_round = i64::try_from(len - 1).unwrap();
+        for _ in 0..rounds {
+            for (number, &value) in numbers.iter().enumerate() {
+                let mut at = ring.iter().position(|&n| n == number).unwrap();
+                // Moving a whole lap past the others puts a number back where it started.
+                let steps = value.rem_euclid(steps_round);
+                for _ in 0..steps {
+                    let next = (at + 1) % len;
+                    ring.swap(at, next);
+                    at = next;
+                }
+            }
+        }
+    }
+
+    let zero = ring.iter().position(|&n| numbers[n] == 0).unwrap();
+    [1000, 2000, 3000]
+        .iter()
+        .map(|offset| numbers[ring[(zero + offset) % len]])
+        .sum()
+}
+
+/// `size` is the number of numbers, which come from a small range so that many are repeated,
+/// with a single 0 among them.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let len = size.max(1);
+    let mut numbers: Vec<i64> = (0..len)
+        .map(|_| {
+            let n = rng.range(-9, 9);
+            if n == 0 {
+                10
+            } else {
+                n
+            }
+        })
+        .collect();
+    numbers[rng.below(len)] = 0;
+
+    let mut input = String::new();
+    for n in &numbers {
+        writeln!(input, "{n}").unwrap();
+    }
+
+    let decrypted: Vec<i64> = numbers.iter().map(|n| n * 811_589_153).collect();
+
+    Generated {
+        input,
+        part_a: Some(Solution::Integer(mix(&numbers, 1))),
+        part_b: Some(Solution::Integer(mix(&decrypted, 10))),
+    }
+}
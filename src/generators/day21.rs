@@ -0,0 +1,229 @@
+use core::fmt::Write;
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::rational::Rational;
+use crate::solutions::Solution;
+
+#[derive(Clone, Copy)]
+enum Job {
+    Number(i64),
+    Operation(usize, char, usize),
+}
+
+/// A tree of monkeys built from the top down, so that every monkey is made to yell a number
+/// chosen before its children are.
+struct Builder<'a> {
+    rng: &'a mut Rng,
+    jobs: Vec<Job>,
+}
+
+impl Builder<'_> {
+    fn add(&mut self, job: Job) -> usize {
+        self.jobs.push(job);
+        self.jobs.len() - 1
+    }
+
+    /// A small divisor of `value` other than 1, if it has one.
+    fn divisor(&mut self, value: i64) -> Option<i64> {
+        let divisors: Vec<i64> = (2..=9).filter(|d| value != 0 && value % d == 0).collect();
+        (!divisors.is_empty()).then(|| *self.rng.choose(&divisors))
+    }
+
+    /// Monkeys yelling exactly `value` between them, about `size` of them, using all four
+    /// operations without any division leaving a remainder.
+    fn subtree(&mut self, value: i64, size: usize) -> usize {
+        if size < 3 {
+            return self.add(Job::Number(value));
+        }
+
+        let (a, op, b) = match self.rng.below(4) {
+            1 => {
+                let b = self.rng.range(0, 20);
+                (value + b, '-', b)
+            }
+            2 => self
+                .divisor(value)
+                .map_or_else(|| (value - 1, '+', 1), |d| (value / d, '*', d)),
+            3 if value.abs() < 1_000_000 => {
+                let d = self.rng.range(1, 9);
+                (value * d, '/', d)
+            }
+            _ => {
+                let a = self.rng.range(0, 20);
+                (a, '+', value - a)
+            }
+        };
+
+        let left_size = self.rng.below(size - 1);
+        let left = self.subtree(a, left_size);
+        let right = self.subtree(b, size - 1 - left_size);
+        self.add(Job::Operation(left, op, right))
+    }
+
+    /// A chain of `length` operations from a monkey yelling `target` down to the human, with a
+    /// small subtree on the other side of each. Returns the top of the chain, the human, and the
+    /// number the human must yell.
+    fn human_path(&mut self, mut target: i64, length: usize) -> (usize, usize, i64) {
+        // The operations from the top down, each with its other monkey and the side the human is
+        // on, worked out before the monkeys are added.
+        let mut steps = vec![];
+        for _ in 0..length {
+            let human_left = self.rng.one_in(2);
+            let (op, other, next) = match self.rng.below(4) {
+                1 => {
+                    let other = self.rng.range(0, 20);
+                    let next = if human_left {
+                        target + other
+                    } else {
+                        other - target
+                    };
+                    ('-', other, next)
+                }
+                2 => self
+                    .divisor(target)
+                    .map_or_else(|| ('+', 1, target - 1), |d| ('*', d, target / d)),
+                3 if target != 0 && target.abs() < 1_000_000 => {
+                    if human_left {
+                        let d = self.rng.range(1, 9);
+                        ('/', d, target * d)
+                    } else {
+                        let k = self.rng.range(1, 9);
+                        ('/', target * k, k)
+                    }
+                }
+                _ => {
+                    let other = self.rng.range(0, 20);
+                    ('+', other, target - other)
+                }
+            };
+            steps.push((op, other, human_left));
+            target = next;
+        }
+
+        // The human is listed as yelling the answer, which keeps every division on the way up
+        // exact.
+        let human = self.add(Job::Number(target));
+        let mut top = human;
+        for &(op, other, human_left) in steps.iter().rev() {
+            let size = self.rng.below(4);
+            let other = self.subtree(other, size);
+            top = self.add(if human_left {
+                Job::Operation(top, op, other)
+            } else {
+                Job::Operation(other, op, top)
+            });
+        }
+
+        (top, human, target)
+    }
+}
+
+/// The number `monkey` yells, or `None` if it isn't a whole number along the way or divides by
+/// zero.
+fn evaluate(jobs: &[Job], monkey: usize) -> Option<Rational> {
+    match jobs[monkey] {
+        Job::Number(n) => Some(Rational::from(n)),
+        Job::Operation(a, op, b) => {
+            let (a, b) = (evaluate(jobs, a)?, evaluate(jobs, b)?);
+            match op {
+                '+' => a.checked_add(b),
+                '-' => a.checked_sub(b),
+                '*' => a.checked_mul(b),
+                _ => a.checked_div(b),
+            }
+        }
+    }
+}
+
+fn name(mut n: usize) -> String {
+    let mut name = String::new();
+    for _ in 0..4 {
+        name.push(char::from(b'a' + u8::try_from(n % 26).unwrap()));
+        n /= 26;
+    }
+    name
+}
+
+/// `size` is roughly the number of monkeys, about a quarter of them on the way from root to the
+/// human.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let size = size.max(1);
+    // Never 0, so that root dividing its two equal sides still gives a number.
+    let target = rng.range(1, 5000);
+    let mut builder = Builder { rng, jobs: vec![] };
+
+    let (path, human, answer) = builder.human_path(target, size.div_ceil(4));
+    let other = builder.subtree(target, size);
+    let root_op = *builder.rng.choose(&['+', '-', '*', '/']);
+    let root = if builder.rng.one_in(2) {
+        builder.add(Job::Operation(path, root_op, other))
+    } else {
+        builder.add(Job::Operation(other, root_op, path))
+    };
+    let jobs = builder.jobs;
+
+    // Every other monkey gets a name of its own, from a counter that steps over the two that
+    // are spoken for.
+    let mut names = vec![String::new(); jobs.len()];
+    let mut counter = builder.rng.below(1000);
+    for (monkey, slot) in names.iter_mut().enumerate() {
+        *slot = if monkey == root {
+            "root".to_string()
+        } else if monkey == human {
+            "humn".to_string()
+        } else {
+            loop {
+                counter += 1 + builder.rng.below(50);
+                let candidate = name(counter);
+                if candidate != "root" && candidate != "humn" {
+                    break candidate;
+                }
+            }
+        };
+    }
+
+    let mut order: Vec<usize> = (0..jobs.len()).collect();
+    builder.rng.shuffle(&mut order);
+    let mut input = String::new();
+    for monkey in order {
+        match jobs[monkey] {
+            Job::Number(n) => writeln!(input, "{}: {}", names[monkey], n).unwrap(),
+            Job::Operation(a, op, b) => {
+                writeln!(input, "{}: {} {} {}", names[monkey], names[a], op, names[b]).unwrap();
+            }
+        }
+    }
+
+    let part_a = evaluate(&jobs, root)
+        .and_then(|value| i64::try_from(value.to_integer()?).ok())
+        .expect("Every monkey yells a whole number");
+
+    Generated {
+        input,
+        part_a: Some(Solution::Integer(part_a)),
+        part_b: Some(Solution::Integer(answer)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solutions::get_solution;
+
+    #[test]
+    fn test_part_a_always_checked() {
+        let part_a = get_solution(21).0.unwrap();
+
+        for seed in 0..20 {
+            for size in [1, 2, 10, 40] {
+                let generated = generate(&mut Rng::new(seed), size);
+                assert_eq!(
+                    Some(part_a(&generated.input).unwrap()),
+                    generated.part_a,
+                    "seed {seed}, size {size}"
+                );
+            }
+        }
+    }
+}
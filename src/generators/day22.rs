@@ -0,0 +1,282 @@
+use core::fmt::Write;
+
+use hashbrown::{HashMap, HashSet};
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+type Vec3 = [i64; 3];
+
+const fn dot(a: Vec3, b: Vec3) -> i64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn combine(terms: &[(i64, Vec3)]) -> Vec3 {
+    let mut sum = [0; 3];
+    for &(k, v) in terms {
+        for (s, c) in sum.iter_mut().zip(v) {
+            *s += k * c;
+        }
+    }
+    sum
+}
+
+/// The cross product `a × b`.
+const fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// Where a face of the cube is laid out in the net: its block, and the directions in space its
+/// right and down point in.
+#[derive(Clone, Copy)]
+struct Placed {
+    block: (i64, i64),
+    right: Vec3,
+    down: Vec3,
+}
+
+/// Turns `u` the way the cube rolls when it tips from resting on `normal` over onto `towards`.
+fn roll(u: Vec3, normal: Vec3, towards: Vec3) -> Vec3 {
+    let (a, b) = (dot(u, normal), dot(u, towards));
+    combine(&[
+        (1, u),
+        (-a, normal),
+        (-b, towards),
+        (a, towards),
+        (-b, normal),
+    ])
+}
+
+/// Unfolds the cube along a random spanning tree of its faces, keyed by their outward normals,
+/// with blocks counted from the top left. `None` if the faces would overlap.
+fn unfold(rng: &mut Rng) -> Option<HashMap<Vec3, Placed>> {
+    let first = [0, 0, -1];
+    let mut faces = HashMap::new();
+    faces.insert(
+        first,
+        Placed {
+            block: (0, 0),
+            right: [1, 0, 0],
+            down: [0, 1, 0],
+        },
+    );
+
+    let mut stack = vec![first];
+    while let Some(normal) = stack.pop() {
+        let face = faces[&normal];
+        let mut ways = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+        rng.shuffle(&mut ways);
+
+        for (dx, dy) in ways {
+            let towards = combine(&[(dx, face.right), (dy, face.down)]);
+            if faces.contains_key(&towards) {
+                continue;
+            }
+            faces.insert(
+                towards,
+                Placed {
+                    block: (face.block.0 + dx, face.block.1 + dy),
+                    right: roll(face.right, normal, towards),
+                    down: roll(face.down, normal, towards),
+                },
+            );
+            // Going deep first makes the long, winding nets as well as the compact ones.
+            stack.push(normal);
+            stack.push(towards);
+            break;
+        }
+    }
+
+    let blocks: HashSet<(i64, i64)> = faces.values().map(|f| f.block).collect();
+    if blocks.len() != 6 {
+        return None;
+    }
+    let min_x = blocks.iter().map(|b| b.0).min()?;
+    let min_y = blocks.iter().map(|b| b.1).min()?;
+    for face in faces.values_mut() {
+        face.block = (face.block.0 - min_x, face.block.1 - min_y);
+    }
+    Some(faces)
+}
+
+/// The point in space at the middle of cell `(x, y)` of a face, at twice the scale so that it is
+/// whole, for a cube centred on the origin.
+fn point(side: i64, normal: Vec3, face: &Placed, (x, y): (i64, i64)) -> Vec3 {
+    combine(&[
+        (side, normal),
+        (2 * x + 1 - side, face.right),
+        (2 * y + 1 - side, face.down),
+    ])
+}
+
+const fn password((x, y): (i64, i64), (dx, dy): (i64, i64)) -> i64 {
+    let facing = match (dx, dy) {
+        (1, 0) => 0,
+        (0, 1) => 1,
+        (-1, 0) => 2,
+        _ => 3,
+    };
+    1000 * (y + 1) + 4 * (x + 1) + facing
+}
+
+/// The path on the flat board, stepping round to the other end of the row or column whenever it
+/// runs off.
+fn walk_flat(rows: &[Vec<u8>], path: &[(i64, char)], start: (i64, i64)) -> i64 {
+    let width = i64::try_from(rows.iter().map(Vec::len).max().unwrap()).unwrap();
+    let height = i64::try_from(rows.len()).unwrap();
+    let at = |(x, y): (i64, i64)| {
+        rows.get(usize::try_from(y).ok()?)?
+            .get(usize::try_from(x).ok()?)
+            .copied()
+            .filter(|&c| c != b' ')
+    };
+
+    let (mut pos, mut dir) = (start, (1, 0));
+    for &(steps, turn) in path {
+        for _ in 0..steps {
+            let step = |(x, y): (i64, i64)| {
+                (
+                    (x + dir.0).rem_euclid(width),
+                    (y + dir.1).rem_euclid(height),
+                )
+            };
+            let mut next = step(pos);
+            while at(next).is_none() {
+                next = step(next);
+            }
+            if at(next) == Some(b'#') {
+                break;
+            }
+            pos = next;
+        }
+        dir = match turn {
+            'R' => (-dir.1, dir.0),
+            'L' => (dir.1, -dir.0),
+            _ => dir,
+        };
+    }
+
+    password(pos, dir)
+}
+
+/// The path on the surface of the cube itself, in space: over an edge the walk simply carries
+/// on round onto the next face.
+fn walk_cube(
+    side: i64,
+    faces: &HashMap<Vec3, Placed>,
+    walls: &HashSet<Vec3>,
+    path: &[(i64, char)],
+    (start, start_normal): (Vec3, Vec3),
+) -> i64 {
+    let mut pos = start;
+    let mut normal = start_normal;
+    let mut heading = faces[&normal].right;
+
+    for &(steps, turn) in path {
+        for _ in 0..steps {
+            let (mut next, mut next_normal, mut next_heading) =
+                (combine(&[(1, pos), (2, heading)]), normal, heading);
+            if dot(next, heading) > side {
+                next = combine(&[(1, pos), (1, heading), (-1, normal)]);
+                (next_normal, next_heading) = (heading, combine(&[(-1, normal)]));
+            }
+            if walls.contains(&next) {
+                break;
+            }
+            (pos, normal, heading) = (next, next_normal, next_heading);
+        }
+        heading = match turn {
+            'R' => cross(heading, normal),
+            'L' => cross(normal, heading),
+            _ => heading,
+        };
+    }
+
+    let face = &faces[&normal];
+    let x = (dot(pos, face.right) + side - 1) / 2;
+    let y = (dot(pos, face.down) + side - 1) / 2;
+    password(
+        (face.block.0 * side + x, face.block.1 * side + y),
+        (dot(heading, face.right), dot(heading, face.down)),
+    )
+}
+
+/// `size` is the length of the cube's sides, up to 50, folded from a random one of its nets.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let edge = i64::try_from(size.clamp(1, 50)).unwrap();
+    let faces = loop {
+        if let Some(faces) = unfold(rng) {
+            break faces;
+        }
+    };
+
+    let columns = faces.values().map(|f| f.block.0).max().unwrap() + 1;
+    let lines = faces.values().map(|f| f.block.1).max().unwrap() + 1;
+    let mut rows = vec![
+        vec![b' '; usize::try_from(columns * edge).unwrap()];
+        usize::try_from(lines * edge).unwrap()
+    ];
+    let mut walls = HashSet::new();
+    let mut cells = HashMap::new();
+    // Laid out in a fixed order, as the map's order could change from run to run.
+    let mut laid_out: Vec<(Vec3, Placed)> = faces.iter().map(|(&n, &f)| (n, f)).collect();
+    laid_out.sort_by_key(|(_, f)| f.block);
+    for &(normal, ref face) in &laid_out {
+        for y in 0..edge {
+            for x in 0..edge {
+                let at = (face.block.0 * edge + x, face.block.1 * edge + y);
+                let wall = rng.one_in(6);
+                cells.insert(at, (point(edge, normal, face, (x, y)), normal, wall));
+            }
+        }
+    }
+
+    // The walk starts on the leftmost tile of the top row, so that one is kept open.
+    let start = (0..columns * edge)
+        .map(|x| (x, 0))
+        .find(|at| cells.contains_key(at))
+        .unwrap();
+    cells.get_mut(&start).unwrap().2 = false;
+    for (&(x, y), &(point, _, wall)) in &cells {
+        rows[usize::try_from(y).unwrap()][usize::try_from(x).unwrap()] =
+            if wall { b'#' } else { b'.' };
+        if wall {
+            walls.insert(point);
+        }
+    }
+
+    // Turns come between the numbers of steps, so the path starts and ends with a number.
+    let mut path: Vec<(i64, char)> = (0..2 * size + 3)
+        .map(|_| (rng.range(1, 3 * edge), *rng.choose(&['L', 'R'])))
+        .collect();
+    path.last_mut().unwrap().1 = ' ';
+
+    let mut input = String::new();
+    for row in &rows {
+        let line = String::from_utf8(row.clone()).unwrap();
+        writeln!(input, "{}", line.trim_end()).unwrap();
+    }
+    input.push('\n');
+    for (steps, turn) in &path {
+        write!(input, "{steps}{turn}").unwrap();
+    }
+    input = input.trim_end().to_string();
+    input.push('\n');
+
+    Generated {
+        input,
+        part_a: Some(Solution::Integer(walk_flat(&rows, &path, start))),
+        part_b: Some(Solution::Integer(walk_cube(
+            edge,
+            &faces,
+            &walls,
+            &path,
+            (cells[&start].0, cells[&start].1),
+        ))),
+    }
+}
@@ -0,0 +1,109 @@
+use hashbrown::{HashMap, HashSet};
+
+use crate::generators::{Generated, Rng};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+type Offset = (i64, i64);
+
+/// The step for each direction, in the order of the first round, and the three tiles that
+/// direction needs clear.
+const DIRECTIONS: [(Offset, [Offset; 3]); 4] = [
+    ((0, -1), [(-1, -1), (0, -1), (1, -1)]),
+    ((0, 1), [(-1, 1), (0, 1), (1, 1)]),
+    ((-1, 0), [(-1, -1), (-1, 0), (-1, 1)]),
+    ((1, 0), [(1, -1), (1, 0), (1, 1)]),
+];
+
+/// Plays a round as the puzzle describes it, with the elves as a set of points. Returns whether
+/// any elf moved.
+fn round(elves: &mut HashSet<(i64, i64)>, round: usize) -> bool {
+    let free = |(x, y): (i64, i64), (dx, dy): (i64, i64)| !elves.contains(&(x + dx, y + dy));
+
+    let mut proposals: HashMap<(i64, i64), Vec<(i64, i64)>> = HashMap::new();
+    for &elf in elves.iter() {
+        let alone = (-1..=1)
+            .flat_map(|dx| (-1..=1).map(move |dy| (dx, dy)))
+            .filter(|&d| d != (0, 0))
+            .all(|d| free(elf, d));
+        if alone {
+            continue;
+        }
+
+        if let Some(((dx, dy), _)) = (0..4)
+            .map(|i| DIRECTIONS[(round + i) % 4])
+            .find(|(_, checks)| checks.iter().all(|&d| free(elf, d)))
+        {
+            proposals
+                .entry((elf.0 + dx, elf.1 + dy))
+                .or_default()
+                .push(elf);
+        }
+    }
+
+    let mut moved = false;
+    for (to, from) in proposals {
+        if let [from] = from[..] {
+            elves.remove(&from);
+            elves.insert(to);
+            moved = true;
+        }
+    }
+    moved
+}
+
+/// `size` is the side of the square the elves start in, where about half the tiles have one.
+/// Squares bigger than 24 only get a `part_a` answer.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let edge = size.max(1);
+    let mut rows = vec![vec!['.'; edge]; edge];
+    for row in &mut rows {
+        for tile in row.iter_mut() {
+            if rng.one_in(2) {
+                *tile = '#';
+            }
+        }
+    }
+    // At least one elf, so there is a rectangle to measure.
+    rows[rng.below(edge)][rng.below(edge)] = '#';
+
+    let mut input = String::new();
+    let mut elves = HashSet::new();
+    for (y, row) in rows.iter().enumerate() {
+        input.extend(row.iter());
+        input.push('\n');
+        for (x, &tile) in row.iter().enumerate() {
+            if tile == '#' {
+                elves.insert((i64::try_from(x).unwrap(), i64::try_from(y).unwrap()));
+            }
+        }
+    }
+
+    // Waiting for a big square to settle takes this plain simulation too long.
+    let wait_to_settle = edge <= 24;
+    let mut empty = None;
+    let mut settled = None;
+    for number in 1.. {
+        let moved = round(&mut elves, number - 1);
+
+        if number == 10 {
+            let xs = || elves.iter().map(|e| e.0);
+            let ys = || elves.iter().map(|e| e.1);
+            let width = xs().max().unwrap() - xs().min().unwrap() + 1;
+            let height = ys().max().unwrap() - ys().min().unwrap() + 1;
+            empty = Some(width * height - i64::try_from(elves.len()).unwrap());
+        }
+        if !moved && settled.is_none() {
+            settled = Some(i64::try_from(number).unwrap());
+        }
+        if number >= 10 && (settled.is_some() || !wait_to_settle) {
+            break;
+        }
+    }
+
+    Generated {
+        input,
+        part_a: empty.map(Solution::Integer),
+        part_b: settled.map(Solution::Integer),
+    }
+}
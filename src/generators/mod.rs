@@ -0,0 +1,172 @@
+mod day01;
+mod day02;
+mod day03;
+mod day04;
+mod day05;
+mod day06;
+mod day07;
+mod day08;
+mod day09;
+mod day10;
+mod day11;
+mod day12;
+mod day13;
+mod day14;
+mod day15;
+mod day16;
+mod day17;
+mod day18;
+mod day19;
+mod day20;
+mod day21;
+mod day22;
+mod day23;
+
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+/// A synthetic puzzle input together with the answers it is known to produce, where the
+/// generator is able to work them out while building the input.
+#[derive(Debug)]
+pub struct Generated {
+    pub input: String,
+    pub part_a: Option<Solution>,
+    pub part_b: Option<Solution>,
+}
+
+pub type PuzzleGenerator = Option<fn(&mut Rng, usize) -> Generated>;
+
+const GENERATORS: [PuzzleGenerator; 23] = [
+    Some(day01::generate),
+    Some(day02::generate),
+    Some(day03::generate),
+    Some(day04::generate),
+    Some(day05::generate),
+    Some(day06::generate),
+    Some(day07::generate),
+    Some(day08::generate),
+    Some(day09::generate),
+    Some(day10::generate),
+    Some(day11::generate),
+    Some(day12::generate),
+    Some(day13::generate),
+    Some(day14::generate),
+    Some(day15::generate),
+    Some(day16::generate),
+    Some(day17::generate),
+    Some(day18::generate),
+    Some(day19::generate),
+    Some(day20::generate),
+    Some(day21::generate),
+    Some(day22::generate),
+    Some(day23::generate),
+];
+
+#[must_use]
+pub fn get_generator(day: u8) -> PuzzleGenerator {
+    if day > 0 && day as usize <= GENERATORS.len() {
+        GENERATORS[day as usize - 1]
+    } else {
+        None
+    }
+}
+
+/// Small deterministic `SplitMix64` generator, so that a seed always reproduces the same input.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub const fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `0..n`. `n` must be positive.
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Uniform value in the inclusive range `low..=high`.
+    #[allow(clippy::cast_possible_wrap)]
+    #[allow(clippy::cast_sign_loss)]
+    pub const fn range(&mut self, low: i64, high: i64) -> i64 {
+        low + (self.next_u64() % ((high - low) as u64 + 1)) as i64
+    }
+
+    /// True with a probability of one in `n`.
+    pub const fn one_in(&mut self, n: usize) -> bool {
+        self.below(n) == 0
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solutions::{get_solution, MAX_SOLVED_DAY};
+
+    #[test]
+    fn test_rng_is_deterministic() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+
+        assert_eq!(
+            (0..10).map(|_| a.next_u64()).collect::<Vec<_>>(),
+            (0..10).map(|_| b.next_u64()).collect::<Vec<_>>()
+        );
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+    }
+
+    #[test]
+    fn test_generated_answers_match_solutions() {
+        for day in 1..=MAX_SOLVED_DAY {
+            let generate = get_generator(day).unwrap();
+            let (part_a, part_b) = get_solution(day);
+
+            for seed in 0..5 {
+                for size in [1, 10, 40] {
+                    let generated = generate(&mut Rng::new(seed), size);
+
+                    if let Some(expected) = generated.part_a {
+                        assert_eq!(
+                            expected,
+                            part_a.unwrap()(&generated.input).unwrap(),
+                            "day {day}a"
+                        );
+                    }
+                    if let Some(expected) = generated.part_b {
+                        assert_eq!(
+                            expected,
+                            part_b.unwrap()(&generated.input).unwrap(),
+                            "day {day}b"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_unknown_day() {
+        assert!(get_generator(0).is_none());
+        assert!(get_generator(MAX_SOLVED_DAY + 1).is_none());
+    }
+}
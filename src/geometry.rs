@@ -0,0 +1,468 @@
+use core::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use crate::error::Error;
+use crate::grid::Pos;
+use crate::prelude::*;
+
+/// A point on the plane. As in the puzzle maps, `y` grows downwards.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// The displacement between two points.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const ORIGIN: Self = Self::new(0, 0);
+
+    #[must_use]
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    #[must_use]
+    pub const fn manhattan(self, other: Self) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    #[must_use]
+    pub fn chebyshev(self, other: Self) -> u64 {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// The grid position of this point, if it has no negative coordinate.
+    #[must_use]
+    pub fn to_pos(self) -> Option<Pos> {
+        Some((usize::try_from(self.x).ok()?, usize::try_from(self.y).ok()?))
+    }
+}
+
+impl From<Pos> for Point {
+    #[allow(clippy::cast_possible_wrap)]
+    fn from(pos: Pos) -> Self {
+        Self::new(pos.0 as i64, pos.1 as i64)
+    }
+}
+
+impl Vec2 {
+    #[must_use]
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    /// The vector with each component clamped to `-1..=1`, a single king's move in its direction.
+    #[must_use]
+    pub const fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
+    }
+
+    #[must_use]
+    pub const fn manhattan(self) -> u64 {
+        self.x.unsigned_abs() + self.y.unsigned_abs()
+    }
+
+    #[must_use]
+    pub fn chebyshev(self) -> u64 {
+        self.x.unsigned_abs().max(self.y.unsigned_abs())
+    }
+}
+
+impl Add<Vec2> for Point {
+    type Output = Self;
+
+    fn add(self, v: Vec2) -> Self {
+        Self::new(self.x + v.x, self.y + v.y)
+    }
+}
+
+impl AddAssign<Vec2> for Point {
+    fn add_assign(&mut self, v: Vec2) {
+        *self = *self + v;
+    }
+}
+
+impl Sub<Vec2> for Point {
+    type Output = Self;
+
+    fn sub(self, v: Vec2) -> Self {
+        Self::new(self.x - v.x, self.y - v.y)
+    }
+}
+
+impl SubAssign<Vec2> for Point {
+    fn sub_assign(&mut self, v: Vec2) {
+        *self = *self - v;
+    }
+}
+
+impl Sub for Point {
+    type Output = Vec2;
+
+    fn sub(self, other: Self) -> Vec2 {
+        Vec2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<i64> for Vec2 {
+    type Output = Self;
+
+    fn mul(self, factor: i64) -> Self {
+        Self::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+/// One of the four orthogonal directions, in clockwise order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    /// The unit step in this direction.
+    #[must_use]
+    pub const fn vec(self) -> Vec2 {
+        match self {
+            Self::Up => Vec2::new(0, -1),
+            Self::Right => Vec2::new(1, 0),
+            Self::Down => Vec2::new(0, 1),
+            Self::Left => Vec2::new(-1, 0),
+        }
+    }
+
+    #[must_use]
+    pub const fn turn_right(self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+        }
+    }
+
+    #[must_use]
+    pub const fn turn_left(self) -> Self {
+        match self {
+            Self::Up => Self::Left,
+            Self::Right => Self::Up,
+            Self::Down => Self::Right,
+            Self::Left => Self::Down,
+        }
+    }
+
+    #[must_use]
+    pub const fn reverse(self) -> Self {
+        self.turn_right().turn_right()
+    }
+}
+
+/// Parses `U`/`D`/`L`/`R` as well as the compass letters `N`/`S`/`W`/`E`.
+impl TryFrom<char> for Direction {
+    type Error = Error;
+
+    fn try_from(c: char) -> Result<Self, Error> {
+        match c {
+            'U' | 'N' => Ok(Self::Up),
+            'R' | 'E' => Ok(Self::Right),
+            'D' | 'S' => Ok(Self::Down),
+            'L' | 'W' => Ok(Self::Left),
+            _ => Err(Error::InputError(format!("Unknown direction {c}"))),
+        }
+    }
+}
+
+/// The smallest rectangle, edges included, holding a set of points.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl BoundingBox {
+    #[must_use]
+    pub const fn new(point: Point) -> Self {
+        Self {
+            min: point,
+            max: point,
+        }
+    }
+
+    /// The bounding box of `points`, or `None` if there are none.
+    pub fn of(points: impl IntoIterator<Item = Point>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = Self::new(points.next()?);
+
+        Some(points.fold(first, |mut bounds, point| {
+            bounds.include(point);
+            bounds
+        }))
+    }
+
+    /// Grows the box to hold `point`.
+    pub fn include(&mut self, point: Point) {
+        self.min = Point::new(self.min.x.min(point.x), self.min.y.min(point.y));
+        self.max = Point::new(self.max.x.max(point.x), self.max.y.max(point.y));
+    }
+
+    #[must_use]
+    pub const fn contains(&self, point: Point) -> bool {
+        self.min.x <= point.x
+            && point.x <= self.max.x
+            && self.min.y <= point.y
+            && point.y <= self.max.y
+    }
+
+    #[must_use]
+    pub const fn width(&self) -> u64 {
+        self.max.x.abs_diff(self.min.x) + 1
+    }
+
+    #[must_use]
+    pub const fn height(&self) -> u64 {
+        self.max.y.abs_diff(self.min.y) + 1
+    }
+
+    #[must_use]
+    pub const fn area(&self) -> u64 {
+        self.width() * self.height()
+    }
+}
+
+/// A point in space, for the puzzles that leave the plane.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Point3 {
+    pub const ORIGIN: Self = Self::new(0, 0, 0);
+
+    #[must_use]
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Self { x, y, z }
+    }
+
+    #[must_use]
+    pub const fn manhattan(self, other: Self) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    /// The six points sharing a face with this one, as unit cubes.
+    #[must_use]
+    pub const fn neighbours6(self) -> [Self; 6] {
+        let Self { x, y, z } = self;
+        [
+            Self::new(x - 1, y, z),
+            Self::new(x + 1, y, z),
+            Self::new(x, y - 1, z),
+            Self::new(x, y + 1, z),
+            Self::new(x, y, z - 1),
+            Self::new(x, y, z + 1),
+        ]
+    }
+}
+
+/// The smallest box, faces included, holding a set of points in space.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BoundingBox3 {
+    pub min: Point3,
+    pub max: Point3,
+}
+
+impl BoundingBox3 {
+    #[must_use]
+    pub const fn new(point: Point3) -> Self {
+        Self {
+            min: point,
+            max: point,
+        }
+    }
+
+    /// The bounding box of `points`, or `None` if there are none.
+    pub fn of(points: impl IntoIterator<Item = Point3>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = Self::new(points.next()?);
+
+        Some(points.fold(first, |mut bounds, point| {
+            bounds.include(point);
+            bounds
+        }))
+    }
+
+    /// Grows the box to hold `point`.
+    pub fn include(&mut self, point: Point3) {
+        self.min = Point3::new(
+            self.min.x.min(point.x),
+            self.min.y.min(point.y),
+            self.min.z.min(point.z),
+        );
+        self.max = Point3::new(
+            self.max.x.max(point.x),
+            self.max.y.max(point.y),
+            self.max.z.max(point.z),
+        );
+    }
+
+    /// The box grown by `margin` on every side.
+    #[must_use]
+    pub const fn padded(&self, margin: i64) -> Self {
+        Self {
+            min: Point3::new(
+                self.min.x - margin,
+                self.min.y - margin,
+                self.min.z - margin,
+            ),
+            max: Point3::new(
+                self.max.x + margin,
+                self.max.y + margin,
+                self.max.z + margin,
+            ),
+        }
+    }
+
+    #[must_use]
+    pub const fn contains(&self, point: Point3) -> bool {
+        self.min.x <= point.x
+            && point.x <= self.max.x
+            && self.min.y <= point.y
+            && point.y <= self.max.y
+            && self.min.z <= point.z
+            && point.z <= self.max.z
+    }
+
+    /// How many points are inside, saturating rather than overflowing for huge boxes.
+    #[must_use]
+    pub const fn volume(&self) -> u64 {
+        (self.max.x.abs_diff(self.min.x) + 1)
+            .saturating_mul(self.max.y.abs_diff(self.min.y) + 1)
+            .saturating_mul(self.max.z.abs_diff(self.min.z) + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point::new(1, 2);
+        let b = Point::new(4, -2);
+
+        assert_eq!(Vec2::new(3, -4), b - a);
+        assert_eq!(b, a + (b - a));
+        assert_eq!(a, b - (b - a));
+        assert_eq!(Vec2::new(6, -8), (b - a) * 2);
+        assert_eq!(Vec2::new(-1, 1), -(b - a).signum());
+
+        let mut c = a;
+        c += Vec2::new(1, 1);
+        c -= Vec2::new(0, 2);
+        assert_eq!(Point::new(2, 1), c);
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point::new(1, 2);
+        let b = Point::new(4, -2);
+
+        assert_eq!(7, a.manhattan(b));
+        assert_eq!(4, a.chebyshev(b));
+        assert_eq!(7, (b - a).manhattan());
+        assert_eq!(4, (b - a).chebyshev());
+    }
+
+    #[test]
+    fn test_direction() {
+        assert_eq!(Direction::Up, Direction::try_from('U').unwrap());
+        assert_eq!(Direction::Up, Direction::try_from('N').unwrap());
+        assert_eq!(Direction::Left, Direction::try_from('W').unwrap());
+        assert!(Direction::try_from('X').is_err());
+
+        for d in Direction::ALL {
+            assert_eq!(d, d.turn_left().turn_right());
+            assert_eq!(d.reverse(), d.turn_left().turn_left());
+            assert_eq!(-d.vec(), d.reverse().vec());
+        }
+        assert_eq!(Direction::Left, Direction::Up.turn_left());
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let bounds =
+            BoundingBox::of([Point::new(1, 5), Point::new(-2, 3), Point::new(0, 7)]).unwrap();
+
+        assert_eq!(Point::new(-2, 3), bounds.min);
+        assert_eq!(Point::new(1, 7), bounds.max);
+        assert_eq!(20, bounds.area());
+        assert!(bounds.contains(Point::new(0, 4)));
+        assert!(!bounds.contains(Point::new(2, 4)));
+        assert_eq!(None, BoundingBox::of([]));
+    }
+
+    #[test]
+    fn test_pos() {
+        assert_eq!(Some((3, 4)), Point::new(3, 4).to_pos());
+        assert_eq!(None, Point::new(-1, 4).to_pos());
+        assert_eq!(Point::new(3, 4), Point::from((3, 4)));
+    }
+
+    #[test]
+    fn test_point3() {
+        let a = Point3::new(1, 2, 3);
+
+        assert_eq!(6, Point3::ORIGIN.manhattan(a));
+        assert_eq!(6, a.neighbours6().len());
+        assert!(a.neighbours6().iter().all(|&n| n.manhattan(a) == 1));
+        assert!(a.neighbours6().contains(&Point3::new(1, 2, 4)));
+    }
+
+    #[test]
+    fn test_bounding_box3() {
+        let bounds = BoundingBox3::of([Point3::new(1, 5, 0), Point3::new(-2, 3, 2)]).unwrap();
+
+        assert_eq!(Point3::new(-2, 3, 0), bounds.min);
+        assert_eq!(Point3::new(1, 5, 2), bounds.max);
+        assert_eq!(36, bounds.volume());
+        assert!(bounds.contains(Point3::new(0, 4, 1)));
+        assert!(!bounds.contains(Point3::new(0, 4, 3)));
+        assert_eq!(Point3::new(-3, 2, -1), bounds.padded(1).min);
+        assert_eq!(150, bounds.padded(1).volume());
+        assert_eq!(None, BoundingBox3::of([]));
+    }
+}
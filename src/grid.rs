@@ -0,0 +1,287 @@
+use core::fmt;
+use core::ops::{Index, IndexMut};
+
+use crate::error::Error;
+use crate::prelude::*;
+
+/// A position in a grid as `(x, y)`, with `(0, 0)` the top left cell.
+pub type Pos = (usize, usize);
+
+/// Steps to the four orthogonal neighbours of a cell, as `(dx, dy)`.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Steps to all eight neighbours of a cell, diagonals included, as `(dx, dy)`.
+pub const ALL_AROUND: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its cells in row order. There must be exactly `width * height` of them.
+    ///
+    /// # Errors
+    ///
+    /// If the number of cells doesn't match the size.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Result<Self, Error> {
+        if width.checked_mul(height) != Some(cells.len()) {
+            return Err(Error::InputError(format!(
+                "{} cells don't make a {}x{} grid",
+                cells.len(),
+                width,
+                height
+            )));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Parses a character map, one row per line, turning every character into a cell with `cell`.
+    /// All rows must have the same length.
+    ///
+    /// # Errors
+    ///
+    /// If the rows differ in length, or `cell` rejects a character.
+    pub fn parse<F>(file: &str, mut cell: F) -> Result<Self, Error>
+    where
+        F: FnMut(char) -> Result<T, Error>,
+    {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = vec![];
+
+        for line in file.lines() {
+            let before = cells.len();
+            for c in line.chars() {
+                cells.push(cell(c)?);
+            }
+
+            if *width.get_or_insert(cells.len() - before) != cells.len() - before {
+                return Err(Error::input("Grid rows must all have the same length"));
+            }
+            height += 1;
+        }
+
+        Self::new(width.unwrap_or(0), height, cells)
+    }
+
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    #[must_use]
+    pub const fn contains(&self, pos: Pos) -> bool {
+        pos.0 < self.width && pos.1 < self.height
+    }
+
+    #[must_use]
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        if self.contains(pos) {
+            self.cells.get(pos.0 + pos.1 * self.width)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.contains(pos) {
+            self.cells.get_mut(pos.0 + pos.1 * self.width)
+        } else {
+            None
+        }
+    }
+
+    /// The position one `step` away from `pos`, if it is still inside the grid.
+    #[must_use]
+    pub fn offset(&self, pos: Pos, step: (isize, isize)) -> Option<Pos> {
+        let next = (
+            pos.0.checked_add_signed(step.0)?,
+            pos.1.checked_add_signed(step.1)?,
+        );
+
+        self.contains(next).then_some(next)
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// All cells with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The orthogonal neighbours of `pos` inside the grid.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        ORTHOGONAL
+            .into_iter()
+            .filter_map(move |step| self.offset(pos, step))
+    }
+
+    /// The neighbours of `pos` inside the grid, diagonals included.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        ALL_AROUND
+            .into_iter()
+            .filter_map(move |step| self.offset(pos, step))
+    }
+
+    /// The cells of row `y`, from left to right.
+    #[must_use]
+    pub fn row(&self, y: usize) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        let start = (y * self.width).min(self.cells.len());
+        let end = (start + self.width).min(self.cells.len());
+        self.cells[start..end].iter()
+    }
+
+    /// The cells of column `x`, from top to bottom.
+    #[must_use]
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        let cells = if x < self.width {
+            &self.cells[x..]
+        } else {
+            &[]
+        };
+        cells.iter().step_by(self.width.max(1))
+    }
+
+    /// The positions reached by stepping repeatedly from `pos` until leaving the grid, not
+    /// including `pos` itself.
+    pub fn ray(&self, pos: Pos, step: (isize, isize)) -> impl Iterator<Item = Pos> + '_ {
+        core::iter::successors(self.offset(pos, step), move |&p| self.offset(p, step))
+            .take_while(move |_| step != (0, 0))
+    }
+
+    /// The first position, row by row, whose cell matches `predicate`.
+    pub fn find<P>(&self, mut predicate: P) -> Option<Pos>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter()
+            .find_map(|(pos, cell)| predicate(cell).then_some(pos))
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos).expect("Position outside of the grid")
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos).expect("Position outside of the grid")
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..self.height {
+            for cell in self.row(y) {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits() -> Grid<u32> {
+        Grid::parse("123\n456\n", |c| c.to_digit(10).ok_or(Error::ParseIntError)).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits();
+
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(6, grid[(2, 1)]);
+        assert_eq!(None, grid.get((3, 0)));
+        assert_eq!("123\n456\n", grid.to_string());
+
+        assert!(Grid::parse("12\n3", Ok).is_err());
+        assert!(Grid::parse("1x", |c| c.to_digit(10).ok_or(Error::ParseIntError)).is_err());
+        assert_eq!(0, Grid::parse("", Ok).unwrap().width());
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = digits();
+
+        assert_eq!(
+            vec![(1, 0), (0, 1)],
+            grid.neighbours4((0, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(5, grid.neighbours8((1, 0)).count());
+        assert_eq!(3, grid.neighbours4((1, 1)).count());
+    }
+
+    #[test]
+    fn test_lines() {
+        let grid = digits();
+
+        assert_eq!(vec![&4, &5, &6], grid.row(1).collect::<Vec<_>>());
+        assert_eq!(vec![&3, &2, &1], grid.row(0).rev().collect::<Vec<_>>());
+        assert_eq!(vec![&2, &5], grid.column(1).collect::<Vec<_>>());
+        assert_eq!(vec![&6, &3], grid.column(2).rev().collect::<Vec<_>>());
+        assert_eq!(
+            vec![(1, 0), (2, 0)],
+            grid.ray((0, 0), (1, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(vec![(1, 1)], grid.ray((0, 0), (1, 1)).collect::<Vec<_>>());
+        assert_eq!(0, grid.ray((0, 0), (0, 0)).count());
+    }
+
+    #[test]
+    fn test_find() {
+        let mut grid = digits();
+
+        assert_eq!(Some((1, 1)), grid.find(|&d| d > 4));
+        assert_eq!(None, grid.find(|&d| d > 6));
+
+        grid[(0, 0)] = 9;
+        assert_eq!(Some((0, 0)), grid.find(|&d| d > 6));
+    }
+}
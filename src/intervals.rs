@@ -0,0 +1,266 @@
+use alloc::collections::BTreeMap;
+use core::ops::RangeInclusive;
+
+use crate::prelude::*;
+
+/// A set of integers stored as disjoint inclusive ranges, so that huge stretches cost no more than
+/// small ones. Ranges that overlap or touch are merged as they are inserted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    /// Start of each range mapped to its inclusive end.
+    ranges: BTreeMap<i64, i64>,
+}
+
+impl IntervalSet {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            ranges: BTreeMap::new(),
+        }
+    }
+
+    /// Adds every value in `range`, merging it with the ranges it overlaps or touches.
+    pub fn insert(&mut self, range: RangeInclusive<i64>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        let touching = self
+            .ranges
+            .range(..=end.saturating_add(1))
+            .rev()
+            .take_while(|(_, &e)| e >= start.saturating_sub(1))
+            .map(|(&s, &e)| (s, e))
+            .collect::<Vec<_>>();
+
+        for (s, e) in touching {
+            self.ranges.remove(&s);
+            start = start.min(s);
+            end = end.max(e);
+        }
+
+        self.ranges.insert(start, end);
+    }
+
+    /// Takes every value in `range` out of the set.
+    pub fn remove(&mut self, range: RangeInclusive<i64>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        let overlapping = self
+            .ranges
+            .range(..=end)
+            .rev()
+            .take_while(|(_, &e)| e >= start)
+            .map(|(&s, &e)| (s, e))
+            .collect::<Vec<_>>();
+
+        for (s, e) in overlapping {
+            self.ranges.remove(&s);
+            if s < start {
+                self.ranges.insert(s, start - 1);
+            }
+            if e > end {
+                self.ranges.insert(end + 1, e);
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for range in other.iter() {
+            union.insert(range);
+        }
+        union
+    }
+
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intersection = Self::new();
+        let mut a = self.ranges.iter().peekable();
+        let mut b = other.ranges.iter().peekable();
+
+        while let (Some(&(&a_start, &a_end)), Some(&(&b_start, &b_end))) = (a.peek(), b.peek()) {
+            let start = a_start.max(b_start);
+            let end = a_end.min(b_end);
+            if start <= end {
+                intersection.ranges.insert(start, end);
+            }
+
+            if a_end < b_end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+
+        intersection
+    }
+
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for range in other.iter() {
+            difference.remove(range);
+        }
+        difference
+    }
+
+    /// How many values are in the set.
+    #[must_use]
+    pub fn len(&self) -> u64 {
+        self.ranges
+            .iter()
+            .map(|(s, e)| e.abs_diff(*s).saturating_add(1))
+            .sum()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    #[must_use]
+    pub fn contains(&self, value: i64) -> bool {
+        self.ranges
+            .range(..=value)
+            .next_back()
+            .is_some_and(|(_, &end)| end >= value)
+    }
+
+    /// Whether every value of `range` is in the set.
+    #[must_use]
+    pub fn contains_range(&self, range: &RangeInclusive<i64>) -> bool {
+        range.is_empty()
+            || self
+                .ranges
+                .range(..=range.start())
+                .next_back()
+                .is_some_and(|(_, end)| end >= range.end())
+    }
+
+    /// The disjoint ranges making up the set, in order.
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<i64>> + '_ {
+        self.ranges.iter().map(|(&start, &end)| start..=end)
+    }
+
+    /// The ranges inside `within` that aren't in the set, in order.
+    pub fn gaps(&self, within: RangeInclusive<i64>) -> impl Iterator<Item = RangeInclusive<i64>> {
+        Self::from_iter([within])
+            .difference(self)
+            .ranges
+            .into_iter()
+            .map(|(start, end)| start..=end)
+    }
+}
+
+impl FromIterator<RangeInclusive<i64>> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<i64>>>(ranges: I) -> Self {
+        let mut set = Self::new();
+        for range in ranges {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_insert_merges() {
+        let mut set = IntervalSet::from_iter([1..=3, 10..=12, 5..=6, 4..=4]);
+        let (start, end) = (20, 19);
+        set.insert(start..=end);
+
+        assert_eq!(vec![1..=6, 10..=12], set.iter().collect::<Vec<_>>());
+        assert_eq!(9, set.len());
+        assert!(set.contains(4));
+        assert!(!set.contains(7));
+        assert!(set.contains_range(&(2..=5)));
+        assert!(!set.contains_range(&(5..=10)));
+    }
+
+    #[test]
+    fn test_algebra() {
+        let a = IntervalSet::from_iter([0..=10, 20..=30]);
+        let b = IntervalSet::from_iter([5..=25]);
+
+        assert_eq!(vec![0..=30], a.union(&b).iter().collect::<Vec<_>>());
+        assert_eq!(
+            vec![5..=10, 20..=25],
+            a.intersection(&b).iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![0..=4, 26..=30],
+            a.difference(&b).iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![-5..=-1, 11..=19, 31..=40],
+            a.gaps(-5..=40).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_huge_ranges() {
+        let mut set = IntervalSet::from_iter([-2_000_000..=4_000_000, 4_000_002..=i64::MAX]);
+        set.remove(0..=0);
+
+        assert_eq!(
+            vec![4_000_001..=4_000_001],
+            set.gaps(0..=i64::MAX).skip(1).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            6_000_000,
+            set.intersection(&IntervalSet::from_iter([-2_000_000..=4_000_000]))
+                .len()
+        );
+    }
+
+    fn naive(ranges: &[(i64, i64)]) -> BTreeSet<i64> {
+        ranges.iter().flat_map(|&(s, e)| s..=e).collect()
+    }
+
+    fn set(ranges: &[(i64, i64)]) -> IntervalSet {
+        ranges.iter().map(|&(s, e)| s..=e).collect()
+    }
+
+    fn values(set: &IntervalSet) -> BTreeSet<i64> {
+        set.iter().flatten().collect()
+    }
+
+    proptest! {
+        #[test]
+        fn prop_matches_naive_sets(
+            a in prop::collection::vec((-20..20_i64, 0..8_i64), 0..6),
+            b in prop::collection::vec((-20..20_i64, 0..8_i64), 0..6),
+        ) {
+            let a = a.into_iter().map(|(s, l)| (s, s + l)).collect::<Vec<_>>();
+            let b = b.into_iter().map(|(s, l)| (s, s + l)).collect::<Vec<_>>();
+            let (set_a, set_b) = (set(&a), set(&b));
+            let (naive_a, naive_b) = (naive(&a), naive(&b));
+
+            prop_assert_eq!(naive_a.len() as u64, set_a.len());
+            prop_assert_eq!(&naive_a | &naive_b, values(&set_a.union(&set_b)));
+            prop_assert_eq!(&naive_a & &naive_b, values(&set_a.intersection(&set_b)));
+            prop_assert_eq!(&naive_a - &naive_b, values(&set_a.difference(&set_b)));
+            prop_assert_eq!(
+                naive(&[(-30, 30)]).difference(&naive_a).copied().collect::<BTreeSet<_>>(),
+                set_a.gaps(-30..=30).flatten().collect::<BTreeSet<_>>()
+            );
+
+            // Stored ranges are kept apart by at least one missing value.
+            let ranges = set_a.iter().collect::<Vec<_>>();
+            prop_assert!(ranges.windows(2).all(|w| w[0].end() + 1 < *w[1].start()));
+        }
+    }
+}
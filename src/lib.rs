@@ -1,2 +1,33 @@
+//! The solvers only need `alloc`, so without the default `std` feature the crate builds as
+//! `no_std` and keeps just them and the toolkit they are built on.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod animate;
+pub mod cycle;
 pub mod error;
+#[cfg(feature = "std")]
+pub mod ffi;
+pub mod generators;
+pub mod geometry;
+pub mod grid;
+pub mod intervals;
+#[cfg(feature = "std")]
+pub mod minimize;
+pub mod parse;
+pub mod rational;
+pub mod render;
+pub mod search;
+#[cfg(feature = "std")]
+pub mod serve;
 pub mod solutions;
+
+/// The parts of the `std` prelude that come from `alloc`, for modules that build without `std`.
+mod prelude {
+    pub use alloc::format;
+    pub use alloc::string::{String, ToString};
+    pub use alloc::vec;
+    pub use alloc::vec::Vec;
+}
@@ -1,21 +1,48 @@
 use std::env;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::time::Duration;
 
-use error::Error;
-use solutions::Solution;
+use advent_of_code_2022::animate::{self, RawMode};
+use advent_of_code_2022::error::Error;
+use advent_of_code_2022::generators::{self, Rng};
+use advent_of_code_2022::minimize;
+use advent_of_code_2022::render::{self, Format, Options, Palette};
+use advent_of_code_2022::serve::{self, Server};
+use advent_of_code_2022::solutions::{self, Solution};
 
-mod error;
-mod solutions;
-
-fn print_solution(day: u8, part: char, solution: &Option<Solution>) {
+fn print_solution(day: u8, part: char, solution: Option<&Result<Solution, Error>>) {
     match solution {
-        Some(Solution::Integer(i)) => println!("{}{}: {}", day, part, i),
-        Some(Solution::String(string)) => println!("{}{}: {}", day, part, string),
-        None => println!("{}{}: Incomplete", day, part),
+        Some(Ok(Solution::Integer(i))) => println!("{day}{part}: {i}"),
+        Some(Ok(Solution::String(string))) => println!("{day}{part}: {string}"),
+        Some(Err(e)) => println!("{day}{part}: Error: {e:?}"),
+        None => println!("{day}{part}: Incomplete"),
     }
 }
 
 fn run() -> Result<(), Error> {
-    let (times, puzzles) = get_args()?;
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if args.first().map(String::as_str) == Some("generate") {
+        return generate(&args[1..]);
+    }
+    if args.first().map(String::as_str) == Some("minimize") {
+        return minimize(&args[1..]);
+    }
+    if args.first().map(String::as_str) == Some("render") {
+        return render(&args[1..]);
+    }
+    if args.first().map(String::as_str) == Some("animate") {
+        return animate(&args[1..]);
+    }
+    if args.first().map(String::as_str) == Some("serve") {
+        return serve(&args[1..]);
+    }
+    if args.first().map(String::as_str) == Some("stats") {
+        return stats(&args[1..]);
+    }
+
+    let (times, puzzles) = get_args(&args)?;
 
     for _ in 0..times {
         println!("Solving");
@@ -27,8 +54,8 @@ fn run() -> Result<(), Error> {
 
             let file: String = std::fs::read_to_string(&path)?;
 
-            print_solution(*day, 'a', &solution1.map(|s| s(&file)));
-            print_solution(*day, 'b', &solution2.map(|s| s(&file)));
+            print_solution(*day, 'a', solution1.map(|s| s(&file)).as_ref());
+            print_solution(*day, 'b', solution2.map(|s| s(&file)).as_ref());
         }
 
         println!("Done");
@@ -37,8 +64,256 @@ fn run() -> Result<(), Error> {
     Ok(())
 }
 
-fn get_args() -> Result<(u32, Vec<u8>), Error> {
-    let mut args = env::args().skip(1);
+/// `generate <day> [--seed <seed>] [--size <size>]` prints a synthetic input to stdout and the
+/// answers it is known to have to stderr.
+fn generate(args: &[String]) -> Result<(), Error> {
+    let usage = || Error::UsageError("generate <day> [--seed <seed>] [--size <size>]".to_string());
+
+    let day: u8 = args.first().ok_or_else(usage)?.parse()?;
+    let mut seed: u64 = 0;
+    let mut size: usize = 10;
+
+    let mut options = args[1..].iter();
+    while let Some(option) = options.next() {
+        let value = options.next().ok_or_else(usage)?;
+        match option.as_str() {
+            "--seed" => seed = value.parse()?,
+            "--size" => size = value.parse()?,
+            _ => return Err(usage()),
+        }
+    }
+
+    let generator = generators::get_generator(day)
+        .ok_or_else(|| Error::UsageError(format!("No generator for day {day}")))?;
+    let generated = generator(&mut Rng::new(seed), size);
+
+    print!("{}", generated.input);
+
+    for (part, answer) in [('a', &generated.part_a), ('b', &generated.part_b)] {
+        match answer {
+            Some(Solution::Integer(i)) => eprintln!("{day}{part}: {i}"),
+            Some(Solution::String(string)) => eprintln!("{day}{part}: {string}"),
+            None => eprintln!("{day}{part}: Unknown"),
+        }
+    }
+
+    Ok(())
+}
+
+/// `minimize <day> <input> [--part <a|b>] [--expected-a <answer>] [--expected-b <answer>]
+/// [--timeout <ms>] [--output <file>]` shrinks an input that makes a solver panic, fail, run too
+/// long or give a wrong answer down to the smallest input showing the same failure, and writes it
+/// to `<input>.min` unless told otherwise. Answers are checked against the expected ones given, or
+/// else the reference implementation.
+fn minimize(args: &[String]) -> Result<(), Error> {
+    let usage = || {
+        Error::UsageError(
+            "minimize <day> <input> [--part <a|b>] [--expected-a <answer>] \
+             [--expected-b <answer>] [--timeout <ms>] [--output <file>]"
+                .to_string(),
+        )
+    };
+
+    let day: u8 = args.first().ok_or_else(usage)?.parse()?;
+    let input = args.get(1).ok_or_else(usage)?;
+    let mut config = minimize::Config::default();
+    let mut output = format!("{input}.min");
+
+    let mut options = args[2..].iter();
+    while let Some(option) = options.next() {
+        let value = options.next().ok_or_else(usage)?;
+        match option.as_str() {
+            "--part" if value == "a" || value == "b" => config.part = value.chars().next(),
+            "--expected-a" => config.expected[0] = Some(value.clone()),
+            "--expected-b" => config.expected[1] = Some(value.clone()),
+            "--timeout" => config.timeout = Duration::from_millis(value.parse()?),
+            "--output" => output.clone_from(value),
+            _ => return Err(usage()),
+        }
+    }
+
+    if matches!(solutions::get_solution(day), (None, None)) {
+        return Err(Error::UsageError(format!("No solution for day {day}")));
+    }
+
+    let file = std::fs::read_to_string(input)?;
+    let (failure, minimized) = minimize::minimize_failure(day, &file, &config)
+        .ok_or_else(|| Error::UsageError(format!("{input} does not fail on day {day}")))?;
+
+    std::fs::write(&output, &minimized)?;
+
+    println!("Day {day}, {failure}");
+    println!(
+        "Reduced {} lines to {}, written to {}",
+        file.lines().count(),
+        minimized.lines().count(),
+        output
+    );
+
+    Ok(())
+}
+
+/// `render <day> [--input <file>] [--format <svg|ppm|png|gif>] [--out <dir>] [--scale <n>]
+/// [--palette <gray|heat|terrain>] [--frames <n>] [--delay <ms>]` draws the day's final state, or
+/// its whole run for a GIF, into `<dir>/dayNN.<format>`. It reads `input/dayNN.txt` and writes to
+/// `render` unless told otherwise.
+fn render(args: &[String]) -> Result<(), Error> {
+    let usage = || {
+        Error::UsageError(
+            "render <day> [--input <file>] [--format <svg|ppm|png|gif>] [--out <dir>] \
+             [--scale <n>] [--palette <gray|heat|terrain>] [--frames <n>] [--delay <ms>]"
+                .to_string(),
+        )
+    };
+
+    let day: u8 = args.first().ok_or_else(usage)?.parse()?;
+    let mut input = env::current_dir()?
+        .join("input")
+        .join(format!("day{day:0>2}.txt"));
+    let mut out = PathBuf::from("render");
+    let mut options = Options::default();
+
+    let mut values = args[1..].iter();
+    while let Some(option) = values.next() {
+        let value = values.next().ok_or_else(usage)?;
+        match option.as_str() {
+            "--input" => input = PathBuf::from(value),
+            "--format" => options.format = Format::try_from(value.as_str())?,
+            "--out" => out = PathBuf::from(value),
+            "--scale" => options.scale = value.parse()?,
+            "--palette" => options.palette = Palette::try_from(value.as_str())?,
+            "--frames" => options.frames = value.parse()?,
+            "--delay" => options.delay = value.parse()?,
+            _ => return Err(usage()),
+        }
+    }
+
+    let scene = solutions::get_scene(day)
+        .ok_or_else(|| Error::UsageError(format!("Nothing to render for day {day}")))?;
+    let file = std::fs::read_to_string(&input)?;
+    let scene = scene(&file, options.frames)?;
+
+    let path = render::write_file(&scene, &options, &out, &format!("day{day:0>2}"))?;
+    println!("Written to {}", path.display());
+
+    Ok(())
+}
+
+/// `animate <day> [--input <file>] [--delay <ms>] [--frames <n>] [--palette <gray|heat|terrain>]`
+/// plays the day's simulation in the terminal, where space pauses, `n` steps a frame and `q`
+/// quits. When stdout isn't a terminal the frames are printed one after the other instead.
+fn animate(args: &[String]) -> Result<(), Error> {
+    let usage = || {
+        Error::UsageError(
+            "animate <day> [--input <file>] [--delay <ms>] [--frames <n>] \
+             [--palette <gray|heat|terrain>]"
+                .to_string(),
+        )
+    };
+
+    let day: u8 = args.first().ok_or_else(usage)?.parse()?;
+    let mut input = env::current_dir()?
+        .join("input")
+        .join(format!("day{day:0>2}.txt"));
+    let mut options = animate::Options::default();
+
+    let mut values = args[1..].iter();
+    while let Some(option) = values.next() {
+        let value = values.next().ok_or_else(usage)?;
+        match option.as_str() {
+            "--input" => input = PathBuf::from(value),
+            "--delay" => options.delay = value.parse()?,
+            "--frames" => options.frames = value.parse()?,
+            "--palette" => options.palette = Palette::try_from(value.as_str())?,
+            _ => return Err(usage()),
+        }
+    }
+
+    let scene = solutions::get_scene(day)
+        .ok_or_else(|| Error::UsageError(format!("Nothing to animate for day {day}")))?;
+    let file = std::fs::read_to_string(&input)?;
+    let scene = scene(&file, options.frames)?;
+
+    let stdout = std::io::stdout();
+    if !stdout.is_terminal() {
+        return animate::dump(&scene, &options, stdout.lock());
+    }
+
+    let _raw = RawMode::enable()?;
+    animate::play(&scene, &options, stdout.lock(), &animate::read_keys())
+}
+
+/// `serve [--host <host>] [--port <port>] [--max-body <bytes>] [--timeout <ms>]
+/// [--max-connections <n>] [--max-solvers <n>]` answers puzzles over HTTP, on `127.0.0.1:2022`
+/// unless told otherwise. See `serve::Server` for the endpoints.
+fn serve(args: &[String]) -> Result<(), Error> {
+    let usage = || {
+        Error::UsageError(
+            "serve [--host <host>] [--port <port>] [--max-body <bytes>] [--timeout <ms>] \
+             [--max-connections <n>] [--max-solvers <n>]"
+                .to_string(),
+        )
+    };
+
+    let mut host = "127.0.0.1".to_string();
+    let mut port: u16 = 2022;
+    let mut config = serve::Config::default();
+
+    let mut options = args.iter();
+    while let Some(option) = options.next() {
+        let value = options.next().ok_or_else(usage)?;
+        match option.as_str() {
+            "--host" => host.clone_from(value),
+            "--port" => port = value.parse()?,
+            "--max-body" => config.max_body = value.parse()?,
+            "--timeout" => config.timeout = Duration::from_millis(value.parse()?),
+            "--max-connections" => config.max_connections = value.parse()?,
+            "--max-solvers" => config.max_solvers = value.parse()?,
+            _ => return Err(usage()),
+        }
+    }
+
+    let server = Server::bind(&format!("{host}:{port}"), config)?;
+    println!("Listening on http://{}", server.local_addr()?);
+
+    server.run()
+}
+
+/// `stats <day> [--input <file>]` prints how many nodes each of the day's searches expanded and
+/// how large its frontier grew, to help tune the pruning.
+fn stats(args: &[String]) -> Result<(), Error> {
+    let usage = || Error::UsageError("stats <day> [--input <file>]".to_string());
+
+    let day: u8 = args.first().ok_or_else(usage)?.parse()?;
+    let mut input = env::current_dir()?
+        .join("input")
+        .join(format!("day{day:0>2}.txt"));
+
+    let mut options = args[1..].iter();
+    while let Some(option) = options.next() {
+        let value = options.next().ok_or_else(usage)?;
+        match option.as_str() {
+            "--input" => input = PathBuf::from(value),
+            _ => return Err(usage()),
+        }
+    }
+
+    let search_stats = solutions::get_search_stats(day)
+        .ok_or_else(|| Error::UsageError(format!("No search stats for day {day}")))?;
+    let file = std::fs::read_to_string(&input)?;
+
+    for (label, stats) in search_stats(&file)? {
+        println!(
+            "{}: {} expanded, frontier up to {}",
+            label, stats.expanded, stats.max_frontier
+        );
+    }
+
+    Ok(())
+}
+
+fn get_args(args: &[String]) -> Result<(u32, Vec<u8>), Error> {
+    let mut args = args.iter();
     let times: Option<Result<u32, _>> = args.next().map(|s| s.parse());
 
     if times.is_none() {
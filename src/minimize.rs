@@ -0,0 +1,302 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::error::Error;
+use crate::solutions::{self, PuzzleSolution, Solution};
+
+type Solver = fn(&str) -> Result<Solution, Error>;
+
+/// What went wrong when solving an input.
+///
+/// Two failures are the same if they would point at the same bug: the same part panicking with
+/// the same message, failing with the same error, running too long, or giving the same wrong
+/// answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    Panic(char, String),
+    Error(char, String),
+    Timeout(char),
+    /// The part's answer, which isn't the expected one.
+    Mismatch(char, String),
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Panic(part, message) => write!(f, "part {part} panicked: {message}"),
+            Self::Error(part, message) => write!(f, "part {part} failed: {message}"),
+            Self::Timeout(part) => write!(f, "part {part} took too long"),
+            Self::Mismatch(part, answer) => write!(f, "part {part} gave the wrong answer {answer}"),
+        }
+    }
+}
+
+/// What counts as a failure when checking an input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// Only this part is checked, if given.
+    pub part: Option<char>,
+    /// The right answers to parts a and b. A part without one is checked against its reference
+    /// implementation, if it has one.
+    pub expected: [Option<String>; 2],
+    /// How long a part may run on one input before it counts as hanging.
+    pub timeout: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            part: None,
+            expected: [None, None],
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Runs `solve`, turning a panic into its message.
+fn catch_panic(solve: Solver, file: &str) -> Result<Result<Solution, Error>, String> {
+    panic::catch_unwind(AssertUnwindSafe(|| solve(file))).map_err(|payload| {
+        payload
+            .downcast_ref::<&str>()
+            .map(ToString::to_string)
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "<non-string panic payload>".to_string())
+    })
+}
+
+/// Runs `solve` on a worker thread, or gives `None` if it takes longer than `timeout`. A solver
+/// that runs over is left to finish in the background, as there's no way to stop a thread from
+/// outside.
+fn run(
+    solve: Solver,
+    file: &str,
+    timeout: Duration,
+) -> Option<Result<Result<Solution, Error>, String>> {
+    let (sender, receiver) = mpsc::channel();
+    let file = file.to_string();
+    std::thread::spawn(move || {
+        let _ = sender.send(catch_panic(solve, &file));
+    });
+
+    receiver.recv_timeout(timeout).ok()
+}
+
+fn answer_text(answer: &Solution) -> String {
+    match answer {
+        Solution::Integer(i) => i.to_string(),
+        Solution::String(s) => s.clone(),
+    }
+}
+
+fn check_part(
+    part: char,
+    (solve, reference): (PuzzleSolution, PuzzleSolution),
+    expected: Option<&str>,
+    file: &str,
+    timeout: Duration,
+) -> Option<Failure> {
+    let answer = match run(solve?, file, timeout) {
+        None => return Some(Failure::Timeout(part)),
+        Some(Err(message)) => return Some(Failure::Panic(part, message)),
+        Some(Ok(Err(e))) => return Some(Failure::Error(part, format!("{e:?}"))),
+        Some(Ok(Ok(answer))) => answer_text(&answer),
+    };
+
+    let expected = match expected {
+        Some(expected) => expected.to_string(),
+        None => match run(reference?, file, timeout) {
+            Some(Ok(Ok(expected))) => answer_text(&expected),
+            _ => return None,
+        },
+    };
+    (answer != expected).then_some(Failure::Mismatch(part, answer))
+}
+
+/// Solves `file` as the input of `day` and reports the first failure found. Panics are caught
+/// rather than propagated.
+#[must_use]
+pub fn check(day: u8, file: &str, config: &Config) -> Option<Failure> {
+    let (part_a, part_b) = solutions::get_solution(day);
+    let (reference_a, reference_b) = solutions::get_reference(day);
+
+    [('a', (part_a, reference_a)), ('b', (part_b, reference_b))]
+        .into_iter()
+        .zip(&config.expected)
+        .filter(|((p, _), _)| config.part.is_none_or(|part| part == *p))
+        .find_map(|((p, solvers), expected)| {
+            check_part(p, solvers, expected.as_deref(), file, config.timeout)
+        })
+}
+
+/// Delta debugging (ddmin): repeatedly tries to drop chunks of `units`, halving the chunk size
+/// whenever no chunk can be dropped, until every single unit is needed to keep `fails` true.
+fn ddmin<'a>(
+    mut units: Vec<&'a str>,
+    separator: &str,
+    fails: &impl Fn(&str) -> bool,
+) -> Vec<&'a str> {
+    let mut chunks = 2;
+
+    while units.len() >= 2 {
+        let size = units.len().div_ceil(chunks);
+        let mut reduced = false;
+
+        for start in (0..units.len()).step_by(size) {
+            let end = (start + size).min(units.len());
+            let subset = &units[start..end];
+            let complement: Vec<&str> = units[..start]
+                .iter()
+                .chain(&units[end..])
+                .copied()
+                .collect();
+
+            if fails(&subset.join(separator)) {
+                units = subset.to_vec();
+                chunks = 2;
+                reduced = true;
+                break;
+            }
+            if chunks > 2 && fails(&complement.join(separator)) {
+                units = complement;
+                chunks = (chunks - 1).max(2);
+                reduced = true;
+                break;
+            }
+        }
+
+        if !reduced {
+            if chunks >= units.len() {
+                break;
+            }
+            chunks = (chunks * 2).min(units.len());
+        }
+    }
+
+    units
+}
+
+/// Shrinks `file` to a smaller input for which `fails` still holds: first by whole blank-line
+/// separated blocks, then by single lines. `fails` must hold for `file` itself.
+pub fn minimize(file: &str, fails: impl Fn(&str) -> bool) -> String {
+    let file = file.trim_end_matches('\n');
+
+    let blocks = ddmin(file.split("\n\n").collect(), "\n\n", &|candidate| {
+        fails(&format!("{candidate}\n"))
+    });
+    let file = blocks.join("\n\n");
+
+    let lines = ddmin(file.lines().collect(), "\n", &|candidate| {
+        fails(&format!("{candidate}\n"))
+    });
+
+    format!("{}\n", lines.join("\n"))
+}
+
+/// Minimizes `file` for `day`, keeping the failure it originally shows. Panic output is silenced
+/// while the candidates are tried.
+#[must_use]
+pub fn minimize_failure(day: u8, file: &str, config: &Config) -> Option<(Failure, String)> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let result = check(day, file, config).map(|failure| {
+        let minimized = minimize(file, |candidate| {
+            check(day, candidate, config).as_ref() == Some(&failure)
+        });
+        (failure, minimized)
+    });
+
+    panic::set_hook(hook);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimize_keeps_needed_lines() {
+        let file = (1..=40)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let fails = |candidate: &str| {
+            let lines: Vec<&str> = candidate.lines().collect();
+            lines.contains(&"7") && lines.contains(&"31")
+        };
+
+        assert_eq!("7\n31\n", minimize(&file, fails));
+    }
+
+    #[test]
+    fn test_minimize_drops_whole_blocks() {
+        let file = "1\n2\n\n3\n4\n\n5\n6\n";
+        let fails = |candidate: &str| candidate.contains("3\n4");
+
+        assert_eq!("3\n4\n", minimize(file, fails));
+    }
+
+    #[test]
+    fn test_catch_panic() {
+        fn explode(_file: &str) -> Result<Solution, Error> {
+            panic!("boom")
+        }
+
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let result = catch_panic(explode, "");
+        panic::set_hook(hook);
+
+        assert_eq!(Err("boom".to_string()), result.map(|_| ()));
+    }
+
+    #[test]
+    fn test_minimize_error() {
+        let file = "1000\n2000\n\n3000\n\nfour\n\n5000\n6000\n";
+        let (failure, minimized) = minimize_failure(1, file, &Config::default()).unwrap();
+
+        assert_eq!(
+            Failure::Error('a', format!("{:?}", Error::ParseIntError)),
+            failure
+        );
+        assert_eq!("four\n", minimized);
+    }
+
+    #[test]
+    fn test_minimize_wrong_answer() {
+        let file = "1000\n2000\n\n3000\n\n4000\n\n500\n";
+        let config = Config {
+            part: Some('a'),
+            expected: [Some("5000".to_string()), None],
+            ..Config::default()
+        };
+        let (failure, minimized) = minimize_failure(1, file, &config).unwrap();
+
+        assert_eq!(Failure::Mismatch('a', "4000".to_string()), failure);
+        assert_eq!("4000\n", minimized);
+        assert_eq!(None, check(1, "5000\n", &config));
+    }
+
+    #[test]
+    fn test_timeout() {
+        fn hang(_file: &str) -> Result<Solution, Error> {
+            loop {
+                std::thread::park();
+            }
+        }
+
+        assert_eq!(
+            Some(Failure::Timeout('b')),
+            check_part('b', (Some(hang), None), None, "", Duration::from_millis(50))
+        );
+    }
+
+    #[test]
+    fn test_valid_input_has_no_failure() {
+        assert_eq!(
+            None,
+            minimize_failure(1, "1000\n\n2000\n", &Config::default())
+        );
+    }
+}
@@ -0,0 +1,387 @@
+use core::num::ParseIntError;
+use core::str::FromStr;
+
+use crate::error::Error;
+use crate::prelude::*;
+
+/// The groups of lines separated by blank lines, ignoring any extra blank lines around them.
+pub fn blocks(file: &str) -> impl Iterator<Item = &str> {
+    file.split("\n\n")
+        .map(|block| block.trim_matches('\n'))
+        .filter(|block| !block.is_empty())
+}
+
+/// Every integer in `line`, in order, with a `-` directly in front of the digits read as a sign.
+///
+/// # Errors
+///
+/// If a number doesn't fit in a `T`.
+pub fn integers<T>(line: &str) -> Result<Vec<T>, Error>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    let bytes = line.as_bytes();
+    let mut numbers = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let start = i;
+        if bytes[i] == b'-' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+
+        if bytes[i].is_ascii_digit() {
+            while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+                i += 1;
+            }
+            numbers.push(line[start..i].parse()?);
+        } else {
+            i += 1;
+        }
+    }
+
+    Ok(numbers)
+}
+
+/// The pieces of a line matched by the `{}` placeholders of a pattern, see [`capture`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct Captures<'a> {
+    values: Vec<(usize, &'a str)>,
+}
+
+impl<'a> Captures<'a> {
+    /// The text of capture `i`.
+    #[must_use]
+    pub fn text(&self, i: usize) -> Option<&'a str> {
+        self.values.get(i).map(|&(_, value)| value)
+    }
+
+    /// Capture `i` read as a `T`.
+    ///
+    /// # Errors
+    ///
+    /// If there is no capture `i`, or it isn't a valid `T`.
+    pub fn get<T: FromStr>(&self, i: usize) -> Result<T, Error> {
+        let &(column, value) = self
+            .values
+            .get(i)
+            .ok_or_else(|| Error::InputError(format!("No capture {i}")))?;
+
+        value
+            .parse()
+            .map_err(|_| Error::InputError(format!("Can't read {value:?} at column {column}")))
+    }
+
+    /// All captures read at once, as a tuple with one type per placeholder.
+    ///
+    /// # Errors
+    ///
+    /// If the number of captures doesn't match the tuple, or one of them isn't valid.
+    pub fn parse<T: FromCaptures>(&self) -> Result<T, Error> {
+        T::from_captures(self)
+    }
+}
+
+/// Tuples that a whole set of captures can be read into.
+pub trait FromCaptures: Sized {
+    /// # Errors
+    ///
+    /// If the captures can't be read as `Self`.
+    fn from_captures(captures: &Captures) -> Result<Self, Error>;
+}
+
+macro_rules! impl_from_captures {
+    ($count:literal: $($t:ident $i:tt),+) => {
+        impl<$($t: FromStr),+> FromCaptures for ($($t,)+) {
+            fn from_captures(captures: &Captures) -> Result<Self, Error> {
+                if captures.values.len() != $count {
+                    return Err(Error::InputError(format!(
+                        "Expected {} captures, found {}",
+                        $count,
+                        captures.values.len()
+                    )));
+                }
+
+                Ok(($(captures.get::<$t>($i)?,)+))
+            }
+        }
+    };
+}
+
+impl_from_captures!(1: A 0);
+impl_from_captures!(2: A 0, B 1);
+impl_from_captures!(3: A 0, B 1, C 2);
+impl_from_captures!(4: A 0, B 1, C 2, D 3);
+
+/// Matches `line` against `pattern`, where every `{}` in the pattern captures the text up to the
+/// literal that follows it, or the rest of the line if it comes last. Columns in errors start at 1.
+///
+/// # Errors
+///
+/// If `line` doesn't match `pattern`.
+///
+/// ```
+/// # use advent_of_code_2022::parse::capture;
+/// let (count, from, to): (u8, u8, u8) = capture("move {} from {} to {}", "move 3 from 1 to 2")
+///     .unwrap()
+///     .parse()
+///     .unwrap();
+/// assert_eq!((3, 1, 2), (count, from, to));
+/// ```
+pub fn capture<'a>(pattern: &str, line: &'a str) -> Result<Captures<'a>, Error> {
+    let mut literals = pattern.split("{}");
+    let first = literals.next().unwrap_or_default();
+
+    let mut rest = line
+        .strip_prefix(first)
+        .ok_or_else(|| Error::InputError(format!("Expected {first:?} at column 1")))?;
+    let mut values = vec![];
+
+    for literal in literals {
+        let column = line.len() - rest.len() + 1;
+        let end = if literal.is_empty() {
+            rest.len()
+        } else {
+            rest.find(literal).ok_or_else(|| {
+                Error::InputError(format!("Expected {literal:?} after column {column}"))
+            })?
+        };
+
+        values.push((column, &rest[..end]));
+        rest = &rest[end + literal.len()..];
+    }
+
+    if !rest.is_empty() {
+        return Err(Error::InputError(format!(
+            "Unexpected {:?} at column {}",
+            rest,
+            line.len() - rest.len() + 1
+        )));
+    }
+
+    Ok(Captures { values })
+}
+
+/// A position in a line of input, for writing small recursive descent parsers whose errors say
+/// where they went wrong.
+#[derive(Clone, Debug)]
+pub struct Cursor<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    #[must_use]
+    pub const fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    /// The column the cursor is at, starting at 1.
+    #[must_use]
+    pub const fn column(&self) -> usize {
+        self.position + 1
+    }
+
+    #[must_use]
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    #[must_use]
+    pub fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    pub fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    /// An input error pointing at the current column.
+    #[must_use]
+    pub fn error(&self, message: &str) -> Error {
+        Error::InputError(format!("{} at column {}", message, self.column()))
+    }
+
+    /// Skips `c` if it comes next.
+    pub fn eat(&mut self, c: char) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.position += c.len_utf8();
+        }
+        found
+    }
+
+    /// Skips `c`, which must come next.
+    ///
+    /// # Errors
+    ///
+    /// If something else comes next.
+    pub fn expect(&mut self, c: char) -> Result<(), Error> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected {c:?}")))
+        }
+    }
+
+    /// Reads an integer, with an optional leading `-`.
+    ///
+    /// # Errors
+    ///
+    /// If there are no digits, or the number doesn't fit in a `T`.
+    pub fn integer<T>(&mut self) -> Result<T, Error>
+    where
+        T: FromStr<Err = ParseIntError>,
+    {
+        let rest = self.rest();
+        let sign = usize::from(rest.starts_with('-'));
+        let digits = rest[sign..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len() - sign);
+
+        if digits == 0 {
+            return Err(self.error("Expected a number"));
+        }
+
+        let value = rest[..sign + digits].parse()?;
+        self.position += sign + digits;
+        Ok(value)
+    }
+
+    /// Reads `open`, then items separated by `separator` until `close`.
+    ///
+    /// # Errors
+    ///
+    /// If a delimiter is missing, or `item` fails.
+    pub fn separated<T, F>(
+        &mut self,
+        open: char,
+        separator: char,
+        close: char,
+        mut item: F,
+    ) -> Result<Vec<T>, Error>
+    where
+        F: FnMut(&mut Self) -> Result<T, Error>,
+    {
+        self.expect(open)?;
+
+        let mut items = vec![];
+        if self.eat(close) {
+            return Ok(items);
+        }
+
+        loop {
+            items.push(item(self)?);
+
+            if self.eat(close) {
+                return Ok(items);
+            }
+            self.expect(separator)?;
+        }
+    }
+
+    /// Checks that the whole input was read.
+    ///
+    /// # Errors
+    ///
+    /// If any input is left.
+    pub fn finish(&self) -> Result<(), Error> {
+        if self.position == self.input.len() {
+            Ok(())
+        } else {
+            Err(self.error("Unexpected trailing input"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks() {
+        assert_eq!(
+            vec!["a\nb", "c", "d"],
+            blocks("a\nb\n\nc\n\n\n\nd\n").collect::<Vec<_>>()
+        );
+        assert_eq!(0, blocks("\n\n").count());
+    }
+
+    #[test]
+    fn test_integers() {
+        assert_eq!(
+            vec![2, -15, 3, 0],
+            integers::<i64>("Sensor at x=2, y=-15: -a 3 - 0").unwrap()
+        );
+        assert!(integers::<u8>("move 300").is_err());
+        assert!(integers::<i32>("none").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_capture() {
+        let captures = capture(
+            "Valve {} has flow rate={}; tunnels",
+            "Valve AA has flow rate=0; tunnels",
+        )
+        .unwrap();
+        assert_eq!(Some("AA"), captures.text(0));
+        assert_eq!(0, captures.get::<u32>(1).unwrap());
+
+        let (name, rate): (String, u32) = captures.parse().unwrap();
+        assert_eq!(("AA".to_string(), 0), (name, rate));
+        assert!(captures.parse::<(u32, u32)>().is_err());
+        assert!(captures.parse::<(String,)>().is_err());
+
+        assert!(capture("move {} from {}", "mvoe 1 from 2").is_err());
+        assert!(capture("move {} from {}", "move 1 to 2").is_err());
+        assert!(capture("move {}!", "move 1!?").is_err());
+        assert_eq!(
+            vec![""],
+            capture("{}", "")
+                .unwrap()
+                .values
+                .iter()
+                .map(|v| v.1)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_capture_errors_have_columns() {
+        let error = capture("move {} from {}", "move 1 from x")
+            .unwrap()
+            .get::<u8>(1);
+
+        assert_eq!(
+            "InputError(\"Can't read \\\"x\\\" at column 13\")",
+            format!("{:?}", error.unwrap_err())
+        );
+    }
+
+    #[test]
+    fn test_cursor() {
+        fn list(cursor: &mut Cursor) -> Result<Vec<i32>, Error> {
+            cursor.separated('[', ',', ']', Cursor::integer)
+        }
+
+        let mut cursor = Cursor::new("[1,-20,3]");
+        assert_eq!(vec![1, -20, 3], list(&mut cursor).unwrap());
+        assert!(cursor.finish().is_ok());
+
+        assert_eq!(Vec::<i32>::new(), list(&mut Cursor::new("[]")).unwrap());
+
+        let mut cursor = Cursor::new("[1;2]");
+        assert_eq!(
+            "InputError(\"Expected ',' at column 3\")",
+            format!("{:?}", list(&mut cursor).unwrap_err())
+        );
+
+        let mut cursor = Cursor::new("[1]x");
+        list(&mut cursor).unwrap();
+        assert!(cursor.finish().is_err());
+        assert_eq!(Some('x'), cursor.advance());
+        assert_eq!(None, cursor.peek());
+    }
+}
@@ -0,0 +1,166 @@
+use core::fmt;
+
+/// An exact fraction, always kept in lowest terms with a positive denominator. The arithmetic is
+/// checked, so overflow and division by zero show up as `None` instead of a wrong answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numer: i128,
+    denom: i128,
+}
+
+const fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+impl Rational {
+    pub const ZERO: Self = Self::integer(0);
+    pub const ONE: Self = Self::integer(1);
+
+    /// `numer / denom`, or `None` if `denom` is zero or the fraction can't be held.
+    #[must_use]
+    pub const fn new(numer: i128, denom: i128) -> Option<Self> {
+        if denom == 0 || numer == i128::MIN || denom == i128::MIN {
+            return None;
+        }
+
+        let divisor = gcd(numer, denom);
+        let sign = denom.signum();
+        Some(Self {
+            numer: sign * numer / divisor,
+            denom: sign * denom / divisor,
+        })
+    }
+
+    #[must_use]
+    pub const fn integer(value: i128) -> Self {
+        Self {
+            numer: value,
+            denom: 1,
+        }
+    }
+
+    #[must_use]
+    pub const fn numer(self) -> i128 {
+        self.numer
+    }
+
+    #[must_use]
+    pub const fn denom(self) -> i128 {
+        self.denom
+    }
+
+    /// The value as an integer, if it is one.
+    #[must_use]
+    pub const fn to_integer(self) -> Option<i128> {
+        if self.denom == 1 {
+            Some(self.numer)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let divisor = gcd(self.denom, other.denom);
+        let denom = (self.denom / divisor).checked_mul(other.denom)?;
+        let numer = self
+            .numer
+            .checked_mul(other.denom / divisor)?
+            .checked_add(other.numer.checked_mul(self.denom / divisor)?)?;
+        Self::new(numer, denom)
+    }
+
+    #[must_use]
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.checked_add(other.checked_neg()?)
+    }
+
+    #[must_use]
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        // Cancelling across first keeps the products as small as they can be.
+        let a = gcd(self.numer, other.denom).max(1);
+        let b = gcd(other.numer, self.denom).max(1);
+        Self::new(
+            (self.numer / a).checked_mul(other.numer / b)?,
+            (self.denom / b).checked_mul(other.denom / a)?,
+        )
+    }
+
+    #[must_use]
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        self.checked_mul(other.checked_recip()?)
+    }
+
+    #[must_use]
+    pub fn checked_neg(self) -> Option<Self> {
+        Self::new(self.numer.checked_neg()?, self.denom)
+    }
+
+    /// `1 / self`, or `None` for zero.
+    #[must_use]
+    pub const fn checked_recip(self) -> Option<Self> {
+        Self::new(self.denom, self.numer)
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Self::integer(i128::from(value))
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.denom == 1 {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r(numer: i128, denom: i128) -> Rational {
+        Rational::new(numer, denom).unwrap()
+    }
+
+    #[test]
+    fn test_lowest_terms() {
+        assert_eq!(r(1, 2), r(3, 6));
+        assert_eq!(r(-1, 2), r(1, -2));
+        assert_eq!((-1, 2), (r(2, -4).numer(), r(2, -4).denom()));
+        assert_eq!(Rational::ZERO, r(0, -5));
+        assert_eq!(None, Rational::new(1, 0));
+        assert_eq!(Some(3), r(6, 2).to_integer());
+        assert_eq!(None, r(7, 2).to_integer());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(Some(r(5, 6)), r(1, 2).checked_add(r(1, 3)));
+        assert_eq!(Some(r(1, 6)), r(1, 2).checked_sub(r(1, 3)));
+        assert_eq!(Some(r(1, 6)), r(1, 2).checked_mul(r(1, 3)));
+        assert_eq!(Some(r(3, 2)), r(1, 2).checked_div(r(1, 3)));
+        assert_eq!(None, r(1, 2).checked_div(Rational::ZERO));
+        assert_eq!(
+            None,
+            Rational::integer(i128::MAX).checked_add(Rational::ONE)
+        );
+        assert_eq!(
+            Some(Rational::ONE),
+            r(i128::MAX, 3).checked_mul(r(3, i128::MAX))
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("-3/4", r(3, -4).to_string());
+        assert_eq!("7", Rational::from(7).to_string());
+    }
+}
@@ -0,0 +1,42 @@
+use std::borrow::Cow;
+use std::io::Write;
+
+use super::{Frame, Rgb};
+use crate::error::Error;
+
+fn encoding_error(error: ::gif::EncodingError) -> Error {
+    Error::IoError(std::io::Error::other(error))
+}
+
+/// Writes `frames` as a looping animated GIF, one pixel per cell and `delay` milliseconds apart.
+pub fn write<W: Write>(frames: &[Frame], colors: &[Rgb], delay: u32, out: W) -> Result<(), Error> {
+    let size =
+        |n: usize| u16::try_from(n).map_err(|_| Error::input("Image is too large for a GIF"));
+    let (width, height) = frames
+        .iter()
+        .fold((0, 0), |(w, h), f| (f.width().max(w), f.height().max(h)));
+
+    let palette = colors
+        .iter()
+        .flat_map(|&Rgb(r, g, b)| [r, g, b])
+        .collect::<Vec<_>>();
+    let mut encoder =
+        ::gif::Encoder::new(out, size(width)?, size(height)?, &palette).map_err(encoding_error)?;
+    encoder
+        .set_repeat(::gif::Repeat::Infinite)
+        .map_err(encoding_error)?;
+
+    for frame in frames {
+        let pixels = frame.iter().map(|(_, &level)| level).collect::<Vec<_>>();
+        let frame = ::gif::Frame {
+            width: size(frame.width())?,
+            height: size(frame.height())?,
+            buffer: Cow::Owned(pixels),
+            delay: u16::try_from(delay / 10).unwrap_or(u16::MAX),
+            ..::gif::Frame::default()
+        };
+        encoder.write_frame(&frame).map_err(encoding_error)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,149 @@
+#[cfg(feature = "std")]
+mod gif;
+#[cfg(feature = "std")]
+mod output;
+#[cfg(feature = "std")]
+mod png;
+#[cfg(feature = "std")]
+mod ppm;
+#[cfg(feature = "std")]
+mod svg;
+
+#[cfg(feature = "std")]
+pub use output::{write, write_file, Format, Options, Palette, Rgb};
+
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::prelude::*;
+
+/// One picture of a puzzle's state, as a level per cell that the palette turns into a colour.
+pub type Frame = Grid<u8>;
+
+/// What a day shows of itself: its state after every step worth seeing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Scene {
+    pub frames: Vec<Frame>,
+    /// Cells use levels `0..levels`.
+    pub levels: u8,
+    /// Whether the top level marks something special, drawn in the palette's accent colour
+    /// instead of at the end of its gradient.
+    pub accent: bool,
+}
+
+impl Scene {
+    /// At most `count` frames, spread evenly and always including the last one.
+    #[must_use]
+    pub fn sample(&self, count: usize) -> Vec<&Frame> {
+        let total = self.frames.len();
+        if count == 0 || total <= count {
+            return self.frames.iter().collect();
+        }
+
+        (1..=count)
+            .map(|i| &self.frames[i * total / count - 1])
+            .collect()
+    }
+
+    /// Checks that every cell of every frame is at one of the scene's levels, so that each has a
+    /// colour.
+    ///
+    /// # Errors
+    ///
+    /// If a frame holds a level of `levels` or above.
+    pub fn check_levels(&self) -> Result<(), Error> {
+        let highest = self
+            .frames
+            .iter()
+            .flat_map(Grid::iter)
+            .map(|(_, &level)| level)
+            .max();
+
+        match highest {
+            Some(level) if level >= self.levels => Err(Error::InputError(format!(
+                "A frame has level {level}, but the scene only has {}",
+                self.levels
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Picks frames as a simulation runs, so that long simulations don't keep a frame for each of
+/// their steps. About `count` of the `steps` are kept, evenly spread and ending with the last.
+pub struct Recorder {
+    pub frames: Vec<Frame>,
+    steps: usize,
+    every: usize,
+    step: usize,
+}
+
+impl Recorder {
+    #[must_use]
+    pub fn new(steps: usize, count: usize) -> Self {
+        Self {
+            frames: vec![],
+            steps,
+            every: steps.div_ceil(count.max(1)).max(1),
+            step: 0,
+        }
+    }
+
+    /// Counts a step, drawing the state after it with `draw` if this step is one to keep.
+    pub fn step<F: FnOnce() -> Frame>(&mut self, draw: F) {
+        self.step += 1;
+        if self.step.is_multiple_of(self.every) || self.step == self.steps {
+            self.frames.push(draw());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub fn scene() -> Scene {
+        let frames = (0..4)
+            .map(|i| Grid::new(2, 2, vec![0, 1, 2, i]).unwrap())
+            .collect();
+
+        Scene {
+            frames,
+            levels: 4,
+            accent: true,
+        }
+    }
+
+    #[test]
+    fn test_sample() {
+        let scene = scene();
+
+        assert_eq!(4, scene.sample(0).len());
+        assert_eq!(vec![&scene.frames[1], &scene.frames[3]], scene.sample(2));
+    }
+
+    #[test]
+    fn test_check_levels() {
+        let mut scene = scene();
+        assert!(scene.check_levels().is_ok());
+
+        scene.levels = 3;
+        assert!(scene.check_levels().is_err());
+    }
+
+    #[test]
+    fn test_recorder() {
+        let mut recorder = Recorder::new(10, 3);
+        for i in 0..10 {
+            recorder.step(|| Grid::filled(1, 1, i));
+        }
+
+        assert_eq!(
+            vec![3, 7, 9],
+            recorder
+                .frames
+                .iter()
+                .map(|f| f[(0, 0)])
+                .collect::<Vec<_>>()
+        );
+    }
+}
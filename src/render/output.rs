@@ -0,0 +1,311 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use super::{gif, png, ppm, svg, Frame, Scene};
+use crate::error::Error;
+use crate::grid::Grid;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    fn mix(self, other: Self, t: f64) -> Self {
+        let channel = |a: u8, b: u8| {
+            (f64::from(b) - f64::from(a))
+                .mul_add(t, f64::from(a))
+                .round() as u8
+        };
+        Self(
+            channel(self.0, other.0),
+            channel(self.1, other.1),
+            channel(self.2, other.2),
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Palette {
+    Gray,
+    Heat,
+    Terrain,
+}
+
+impl Palette {
+    const fn stops(self) -> &'static [Rgb] {
+        match self {
+            Self::Gray => &[Rgb(16, 16, 16), Rgb(240, 240, 240)],
+            Self::Heat => &[
+                Rgb(0, 0, 0),
+                Rgb(180, 30, 0),
+                Rgb(255, 200, 0),
+                Rgb(255, 255, 230),
+            ],
+            Self::Terrain => &[
+                Rgb(30, 60, 140),
+                Rgb(40, 140, 60),
+                Rgb(150, 110, 60),
+                Rgb(245, 245, 245),
+            ],
+        }
+    }
+
+    const fn accent(self) -> Rgb {
+        match self {
+            Self::Gray | Self::Terrain => Rgb(230, 30, 40),
+            Self::Heat => Rgb(40, 200, 255),
+        }
+    }
+
+    /// The colour `t` of the way along the palette, `t` going from 0 to 1.
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    #[allow(clippy::cast_precision_loss)]
+    fn gradient(self, t: f64) -> Rgb {
+        let stops = self.stops();
+        let position = t.clamp(0.0, 1.0) * (stops.len() - 1) as f64;
+        let i = (position.floor() as usize).min(stops.len() - 2);
+
+        stops[i].mix(stops[i + 1], position - i as f64)
+    }
+}
+
+impl TryFrom<&str> for Palette {
+    type Error = Error;
+
+    fn try_from(name: &str) -> Result<Self, Error> {
+        match name {
+            "gray" => Ok(Self::Gray),
+            "heat" => Ok(Self::Heat),
+            "terrain" => Ok(Self::Terrain),
+            _ => Err(Error::UsageError(format!("Unknown palette {name}"))),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Svg,
+    Ppm,
+    Png,
+    Gif,
+}
+
+impl Format {
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Ppm => "ppm",
+            Self::Png => "png",
+            Self::Gif => "gif",
+        }
+    }
+}
+
+impl TryFrom<&str> for Format {
+    type Error = Error;
+
+    fn try_from(name: &str) -> Result<Self, Error> {
+        match name {
+            "svg" => Ok(Self::Svg),
+            "ppm" => Ok(Self::Ppm),
+            "png" => Ok(Self::Png),
+            "gif" => Ok(Self::Gif),
+            _ => Err(Error::UsageError(format!("Unknown format {name}"))),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    pub format: Format,
+    pub palette: Palette,
+    /// Pixels per cell.
+    pub scale: usize,
+    /// The most frames an animation keeps.
+    pub frames: usize,
+    /// Time between frames of an animation, in milliseconds.
+    pub delay: u32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            format: Format::Png,
+            palette: Palette::Gray,
+            scale: 4,
+            frames: 200,
+            delay: 50,
+        }
+    }
+}
+
+impl Scene {
+    /// The colour of every level, for `palette`.
+    #[must_use]
+    pub fn colors(&self, palette: Palette) -> Vec<Rgb> {
+        let gradient = if self.accent {
+            self.levels.saturating_sub(1)
+        } else {
+            self.levels
+        };
+
+        let mut colors = (0..gradient)
+            .map(|level| palette.gradient(f64::from(level) / f64::from(gradient.max(2) - 1)))
+            .collect::<Vec<_>>();
+        if self.accent {
+            colors.push(palette.accent());
+        }
+        colors
+    }
+}
+
+/// Each cell of `frame` blown up to a `scale` by `scale` square.
+fn scaled(frame: &Frame, scale: usize) -> Frame {
+    let scale = scale.max(1);
+    let (width, height) = (frame.width() * scale, frame.height() * scale);
+    let cells = (0..height)
+        .flat_map(|y| (0..width).map(move |x| frame[(x / scale, y / scale)]))
+        .collect();
+
+    Grid::new(width, height, cells).expect("Scaled frame has every cell")
+}
+
+/// Writes `scene` in the chosen format: the last frame for stills, every sampled frame for
+/// animations.
+///
+/// # Errors
+///
+/// If the scene has no frames, a frame has a level without a colour, or writing to `out` fails.
+pub fn write<W: Write>(scene: &Scene, options: &Options, out: W) -> Result<(), Error> {
+    scene.check_levels()?;
+    let colors = scene.colors(options.palette);
+    let last = scene
+        .frames
+        .last()
+        .ok_or_else(|| Error::input("Nothing to render"))?;
+
+    match options.format {
+        Format::Svg => svg::write(last, &colors, options.scale, out),
+        Format::Ppm => ppm::write(&scaled(last, options.scale), &colors, out),
+        Format::Png => png::write(&scaled(last, options.scale), &colors, out),
+        Format::Gif => {
+            let frames = scene
+                .sample(options.frames)
+                .into_iter()
+                .map(|frame| scaled(frame, options.scale))
+                .collect::<Vec<_>>();
+            gif::write(&frames, &colors, options.delay, out)
+        }
+    }
+}
+
+/// Writes `scene` to `<dir>/<name>.<extension>`, creating the directory if needed.
+///
+/// # Errors
+///
+/// If the scene has no frames, a frame has a level without a colour, or the file can't be created
+/// or written.
+pub fn write_file(
+    scene: &Scene,
+    options: &Options,
+    dir: &Path,
+    name: &str,
+) -> Result<PathBuf, Error> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.{}", name, options.format.extension()));
+
+    let mut out = BufWriter::new(File::create(&path)?);
+    write(scene, options, &mut out)?;
+    out.flush()?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::tests::scene;
+
+    #[test]
+    fn test_colors() {
+        let colors = scene().colors(Palette::Gray);
+
+        assert_eq!(
+            vec![
+                Rgb(16, 16, 16),
+                Rgb(128, 128, 128),
+                Rgb(240, 240, 240),
+                Rgb(230, 30, 40)
+            ],
+            colors
+        );
+        assert_eq!(Palette::Heat, Palette::try_from("heat").unwrap());
+        assert!(Palette::try_from("plaid").is_err());
+    }
+
+    #[test]
+    fn test_scaled() {
+        let frame = scaled(&scene().frames[0], 2);
+
+        assert_eq!((4, 4), (frame.width(), frame.height()));
+        assert_eq!("0011\n0011\n2200\n2200\n", frame.to_string());
+    }
+
+    #[test]
+    fn test_write_formats() {
+        let scene = scene();
+        let mut options = Options {
+            scale: 3,
+            ..Options::default()
+        };
+
+        let mut out = vec![];
+        options.format = Format::Ppm;
+        write(&scene, &options, &mut out).unwrap();
+        assert!(out.starts_with(b"P6\n6 6\n255\n"));
+        assert_eq!(11 + 6 * 6 * 3, out.len());
+
+        let mut out = vec![];
+        options.format = Format::Png;
+        write(&scene, &options, &mut out).unwrap();
+        assert!(out.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+        let mut out = vec![];
+        options.format = Format::Gif;
+        write(&scene, &options, &mut out).unwrap();
+        assert!(out.starts_with(b"GIF89a"));
+
+        let mut out = vec![];
+        options.format = Format::Svg;
+        write(&scene, &options, &mut out).unwrap();
+        let svg = String::from_utf8(out).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("fill=\"#e61e28\""));
+
+        let empty = Scene {
+            frames: vec![],
+            ..scene
+        };
+        assert!(write(&empty, &options, vec![]).is_err());
+    }
+
+    #[test]
+    fn test_level_without_color() {
+        let scene = Scene {
+            levels: 2,
+            ..scene()
+        };
+
+        for format in [Format::Svg, Format::Ppm, Format::Png, Format::Gif] {
+            let options = Options {
+                format,
+                ..Options::default()
+            };
+            assert!(write(&scene, &options, vec![]).is_err(), "{format:?}");
+        }
+    }
+}
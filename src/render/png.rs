@@ -0,0 +1,28 @@
+use std::io::Write;
+
+use super::{Frame, Rgb};
+use crate::error::Error;
+
+fn encoding_error(error: ::png::EncodingError) -> Error {
+    Error::IoError(std::io::Error::other(error))
+}
+
+/// Writes `frame` as an indexed PNG, one pixel per cell.
+pub fn write<W: Write>(frame: &Frame, colors: &[Rgb], out: W) -> Result<(), Error> {
+    let size = |n: usize| u32::try_from(n).map_err(|_| Error::input("Image is too large"));
+
+    let mut encoder = ::png::Encoder::new(out, size(frame.width())?, size(frame.height())?);
+    encoder.set_color(::png::ColorType::Indexed);
+    encoder.set_depth(::png::BitDepth::Eight);
+    encoder.set_palette(
+        colors
+            .iter()
+            .flat_map(|&Rgb(r, g, b)| [r, g, b])
+            .collect::<Vec<_>>(),
+    );
+
+    let mut writer = encoder.write_header().map_err(encoding_error)?;
+    let pixels = frame.iter().map(|(_, &level)| level).collect::<Vec<_>>();
+    writer.write_image_data(&pixels).map_err(encoding_error)?;
+    writer.finish().map_err(encoding_error)
+}
@@ -0,0 +1,20 @@
+use std::io::Write;
+
+use super::{Frame, Rgb};
+use crate::error::Error;
+
+/// Writes `frame` as a binary PPM, one pixel per cell.
+pub fn write<W: Write>(frame: &Frame, colors: &[Rgb], mut out: W) -> Result<(), Error> {
+    write!(out, "P6\n{} {}\n255\n", frame.width(), frame.height())?;
+
+    let pixels = frame
+        .iter()
+        .flat_map(|(_, &level)| {
+            let Rgb(r, g, b) = colors[usize::from(level)];
+            [r, g, b]
+        })
+        .collect::<Vec<_>>();
+    out.write_all(&pixels)?;
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use std::io::Write;
+
+use super::{Frame, Rgb};
+use crate::error::Error;
+
+/// Draws `frame` with one rectangle per run of same coloured cells along a row, in a view box
+/// measured in cells so the picture stays sharp at any size.
+pub fn write<W: Write>(
+    frame: &Frame,
+    colors: &[Rgb],
+    scale: usize,
+    mut out: W,
+) -> Result<(), Error> {
+    let (width, height) = (frame.width(), frame.height());
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\">",
+        width * scale.max(1),
+        height * scale.max(1),
+        width,
+        height
+    )?;
+
+    for y in 0..height {
+        let mut x = 0;
+        for run in frame.row(y).collect::<Vec<_>>().chunk_by(|a, b| a == b) {
+            let Rgb(r, g, b) = colors[usize::from(*run[0])];
+            writeln!(
+                out,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"1\" fill=\"#{:02x}{:02x}{:02x}\"/>",
+                x,
+                y,
+                run.len(),
+                r,
+                g,
+                b
+            )?;
+            x += run.len();
+        }
+    }
+
+    writeln!(out, "</svg>")?;
+    Ok(())
+}
@@ -0,0 +1,357 @@
+use alloc::collections::{BinaryHeap, VecDeque};
+use core::cmp::{Ordering, Reverse};
+use core::hash::Hash;
+use core::ops::Add;
+
+use hashbrown::{HashMap, HashSet};
+
+use crate::prelude::*;
+
+/// How much work a search did, to compare heuristics and pruning between puzzles.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Nodes whose successors were generated.
+    pub expanded: usize,
+    /// The largest number of nodes waiting to be expanded at any time.
+    pub max_frontier: usize,
+}
+
+impl Stats {
+    fn frontier(&mut self, size: usize) {
+        self.max_frontier = self.max_frontier.max(size);
+    }
+}
+
+/// The best path found, from the start node to the goal, both included.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub nodes: Vec<N>,
+    pub cost: C,
+}
+
+/// What a search found, if anything, and what it took to find it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Outcome<N, C> {
+    pub path: Option<Path<N, C>>,
+    pub stats: Stats,
+}
+
+impl<N, C: Copy> Outcome<N, C> {
+    #[must_use]
+    pub fn cost(&self) -> Option<C> {
+        self.path.as_ref().map(|path| path.cost)
+    }
+}
+
+/// Walks the parent links back from `end` to the node without a parent.
+fn unwind<N: Clone + Eq + Hash>(parents: &HashMap<N, N>, end: N) -> Vec<N> {
+    let mut nodes = vec![end];
+    while let Some(parent) = nodes.last().and_then(|node| parents.get(node)) {
+        nodes.push(parent.clone());
+    }
+    nodes.reverse();
+    nodes
+}
+
+/// Breadth first search on an unweighted graph. The cost is the number of steps.
+pub fn bfs<N, FN, IN, FG>(start: N, mut successors: FN, mut goal: FG) -> Outcome<N, usize>
+where
+    N: Clone + Eq + Hash,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+    FG: FnMut(&N) -> bool,
+{
+    let mut stats = Stats::default();
+    let mut parents: HashMap<N, N> = HashMap::new();
+    let mut queue = VecDeque::from([(start.clone(), 0)]);
+    let mut seen: HashSet<_> = HashSet::from([start]);
+
+    while let Some((node, steps)) = queue.pop_front() {
+        if goal(&node) {
+            return Outcome {
+                path: Some(Path {
+                    nodes: unwind(&parents, node),
+                    cost: steps,
+                }),
+                stats,
+            };
+        }
+
+        stats.expanded += 1;
+        for next in successors(&node) {
+            if seen.insert(next.clone()) {
+                parents.insert(next.clone(), node.clone());
+                queue.push_back((next, steps + 1));
+            }
+        }
+        stats.frontier(queue.len());
+    }
+
+    Outcome { path: None, stats }
+}
+
+/// A frontier entry, ordered for the max-heap so the lowest estimated total cost comes out first,
+/// and between equal estimates the most known cost so far: the deeper node, nearer the goal.
+struct Entry<N, C> {
+    estimate: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> PartialEq for Entry<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N, C: Ord> Eq for Entry<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Entry<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Entry<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (Reverse(&self.estimate), &self.cost).cmp(&(Reverse(&other.estimate), &other.cost))
+    }
+}
+
+/// A* search.
+///
+/// `heuristic` must never overestimate the remaining cost for the path to be the cheapest; nodes
+/// are reopened when a cheaper way to them is found, so it need not be consistent.
+/// `C::default()` is taken as the zero cost.
+pub fn astar<N, C, FN, IN, FH, FG>(
+    start: N,
+    mut successors: FN,
+    mut heuristic: FH,
+    mut goal: FG,
+) -> Outcome<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FH: FnMut(&N) -> C,
+    FG: FnMut(&N) -> bool,
+{
+    let mut stats = Stats::default();
+    let mut parents: HashMap<N, N> = HashMap::new();
+    let mut best: HashMap<N, C> = HashMap::from([(start.clone(), C::default())]);
+    let mut frontier = BinaryHeap::from([Entry {
+        estimate: heuristic(&start),
+        cost: C::default(),
+        node: start,
+    }]);
+
+    while let Some(Entry { cost, node, .. }) = frontier.pop() {
+        if best.get(&node).is_some_and(|&known| known < cost) {
+            continue;
+        }
+
+        if goal(&node) {
+            return Outcome {
+                path: Some(Path {
+                    nodes: unwind(&parents, node),
+                    cost,
+                }),
+                stats,
+            };
+        }
+
+        stats.expanded += 1;
+        for (next, step) in successors(&node) {
+            let next_cost = cost + step;
+            if best.get(&next).is_some_and(|&known| known <= next_cost) {
+                continue;
+            }
+
+            best.insert(next.clone(), next_cost);
+            parents.insert(next.clone(), node.clone());
+            frontier.push(Entry {
+                estimate: next_cost + heuristic(&next),
+                cost: next_cost,
+                node: next,
+            });
+        }
+        stats.frontier(frontier.len());
+    }
+
+    Outcome { path: None, stats }
+}
+
+/// Dijkstra's algorithm, which is A* without a heuristic.
+pub fn dijkstra<N, C, FN, IN, FG>(start: N, successors: FN, goal: FG) -> Outcome<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FG: FnMut(&N) -> bool,
+{
+    astar(start, successors, |_| C::default(), goal)
+}
+
+/// Finds the most valuable path from `start` in a graph without cycles.
+///
+/// Suits states that include the time left, where every step gains its cost and a path may stop
+/// anywhere. The best value of each node is memoised, so states reached in several ways are only
+/// explored once. The frontier is every successor generated on the way down that hasn't been
+/// visited yet.
+pub fn dfs_longest<N, C, FN, IN>(start: N, mut successors: FN) -> Outcome<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+{
+    /// The best value from `node`, and the child to go to for it.
+    fn visit<N, C, FN, IN>(
+        node: &N,
+        successors: &mut FN,
+        memo: &mut HashMap<N, (C, Option<N>)>,
+        stats: &mut Stats,
+        waiting: &mut usize,
+    ) -> C
+    where
+        N: Clone + Eq + Hash,
+        C: Copy + Ord + Default + Add<Output = C>,
+        FN: FnMut(&N) -> IN,
+        IN: IntoIterator<Item = (N, C)>,
+    {
+        if let Some(&(value, _)) = memo.get(node) {
+            return value;
+        }
+
+        stats.expanded += 1;
+        let children: Vec<(N, C)> = successors(node).into_iter().collect();
+        *waiting += children.len();
+        stats.frontier(*waiting);
+
+        let mut best = (C::default(), None);
+        for (next, gain) in children {
+            *waiting -= 1;
+            let value = gain + visit(&next, successors, memo, stats, waiting);
+            if value > best.0 {
+                best = (value, Some(next));
+            }
+        }
+
+        let value = best.0;
+        memo.insert(node.clone(), best);
+        value
+    }
+
+    let mut stats = Stats::default();
+    let mut memo = HashMap::new();
+    let cost = visit(&start, &mut successors, &mut memo, &mut stats, &mut 0);
+
+    let mut nodes = vec![start];
+    while let Some((_, Some(next))) = nodes.last().and_then(|node| memo.get(node)) {
+        nodes.push(next.clone());
+    }
+
+    Outcome {
+        path: Some(Path { nodes, cost }),
+        stats,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A small weighted graph where the direct edge to the goal isn't the cheapest way.
+    fn edges(node: &char) -> Vec<(char, u32)> {
+        match node {
+            'a' => vec![('b', 1), ('c', 4), ('e', 10)],
+            'b' => vec![('c', 1), ('d', 5)],
+            'c' => vec![('d', 1)],
+            'd' => vec![('e', 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_bfs() {
+        let outcome = bfs('a', |n| edges(n).into_iter().map(|(m, _)| m), |&n| n == 'd');
+
+        assert_eq!(
+            Some(Path {
+                nodes: vec!['a', 'b', 'd'],
+                cost: 2
+            }),
+            outcome.path
+        );
+        assert!(
+            bfs('a', |n| edges(n).into_iter().map(|(m, _)| m), |&n| n == 'z')
+                .path
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let outcome = dijkstra('a', edges, |&n| n == 'e');
+
+        assert_eq!(Some(4), outcome.cost());
+        assert_eq!(vec!['a', 'b', 'c', 'd', 'e'], outcome.path.unwrap().nodes);
+        assert_eq!(Some(0), dijkstra('a', edges, |&n| n == 'a').cost());
+    }
+
+    #[test]
+    fn test_astar_expands_less_with_a_heuristic() {
+        // A line of numbers where each one leads to its neighbours.
+        let successors = |n: &i32| vec![(n - 1, 1), (n + 1, 1)];
+
+        let blind = dijkstra(0, successors, |&n| n == 20);
+        let guided = astar(0, successors, |n: &i32| n.abs_diff(20), |&n| n == 20);
+
+        assert_eq!(Some(20), blind.cost());
+        assert_eq!(Some(20), guided.cost());
+        assert_eq!(21, guided.path.unwrap().nodes.len());
+        assert!(guided.stats.expanded < blind.stats.expanded);
+        assert!(guided.stats.max_frontier <= blind.stats.max_frontier);
+    }
+
+    #[test]
+    fn test_entry_order() {
+        let entry = |estimate, cost, node| Entry {
+            estimate,
+            cost,
+            node,
+        };
+        let mut frontier = BinaryHeap::from([
+            entry(5, 1, 'a'),
+            entry(4, 0, 'b'),
+            entry(5, 3, 'c'),
+            entry(4, 2, 'd'),
+        ]);
+
+        let order: Vec<char> = core::iter::from_fn(|| frontier.pop().map(|e| e.node)).collect();
+        assert_eq!(vec!['d', 'b', 'c', 'a'], order);
+    }
+
+    #[test]
+    fn test_dfs_longest() {
+        // Counting down from `n`, a step gains its size; steps of 2 pay more than two steps of 1.
+        let outcome = dfs_longest(4, |&n: &u32| {
+            let mut next = vec![];
+            if n >= 1 {
+                next.push((n - 1, 1));
+            }
+            if n >= 2 {
+                next.push((n - 2, 3));
+            }
+            next
+        });
+
+        assert_eq!(Some(6), outcome.cost());
+        assert_eq!(vec![4, 2, 0], outcome.path.unwrap().nodes);
+        assert_eq!(5, outcome.stats.expanded);
+        // Going down through 4, 3 and 2 leaves 2 from 4, 1 from 3, and 1 and 0 from 2 waiting.
+        assert_eq!(4, outcome.stats.max_frontier);
+    }
+}
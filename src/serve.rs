@@ -0,0 +1,584 @@
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+use crate::error::Error;
+use crate::solutions::{self, PuzzleSolution, Solution};
+
+/// The longest request line and headers accepted, so a client can't make a worker buffer forever.
+const MAX_HEADER: usize = 8 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// The largest input accepted, in bytes.
+    pub max_body: usize,
+    /// How long a solve may take before giving up on it.
+    pub timeout: Duration,
+    /// How long to wait for a client to send its request.
+    pub read_timeout: Duration,
+    /// The most connections handled at once. Any more are turned away.
+    pub max_connections: usize,
+    /// The most solvers running at once, counting ones that ran over their timeout and are still
+    /// going. Any more requests to solve are turned away.
+    pub max_solvers: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_body: 1024 * 1024,
+            timeout: Duration::from_secs(10),
+            read_timeout: Duration::from_secs(5),
+            max_connections: 64,
+            max_solvers: 8,
+        }
+    }
+}
+
+/// A place in a pool of limited size, given back when dropped.
+struct Slot(Arc<AtomicUsize>);
+
+impl Slot {
+    /// Takes a place if fewer than `limit` of them are taken.
+    fn take(taken: &Arc<AtomicUsize>, limit: usize) -> Option<Self> {
+        taken
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < limit).then_some(n + 1)
+            })
+            .ok()?;
+        Some(Self(Arc::clone(taken)))
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// What a connection's thread needs from the server.
+#[derive(Clone)]
+struct Context {
+    config: Config,
+    started: Instant,
+    solvers: Arc<AtomicUsize>,
+    /// Where the solvers for a day are found, which tests swap for ones they control.
+    lookup: fn(u8) -> (PuzzleSolution, PuzzleSolution),
+}
+
+/// A small HTTP server answering puzzles over JSON:
+///
+/// - `GET /health` reports that the server is up, its version and uptime.
+/// - `GET /days` lists the days and the parts each one can solve.
+/// - `POST /solve/<day>` solves both parts of the input in the body, with their timings.
+///
+/// Every connection gets its own thread and is closed after one response. Connections and
+/// solvers beyond the configured limits get a 503.
+pub struct Server {
+    listener: TcpListener,
+    connections: Arc<AtomicUsize>,
+    context: Context,
+}
+
+impl Server {
+    /// # Errors
+    ///
+    /// If the address can't be bound.
+    pub fn bind(addr: &str, config: Config) -> Result<Self, Error> {
+        Ok(Self {
+            listener: TcpListener::bind(addr)?,
+            connections: Arc::default(),
+            context: Context {
+                config,
+                started: Instant::now(),
+                solvers: Arc::default(),
+                lookup: solutions::get_solution,
+            },
+        })
+    }
+
+    /// # Errors
+    ///
+    /// If the listener's address can't be read.
+    pub fn local_addr(&self) -> Result<SocketAddr, Error> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Serves connections until accepting one fails.
+    ///
+    /// # Errors
+    ///
+    /// The error accepting a connection.
+    pub fn run(&self) -> Result<(), Error> {
+        for stream in self.listener.incoming() {
+            let stream = stream?;
+            let limit = self.context.config.max_connections;
+            let Some(slot) = Slot::take(&self.connections, limit) else {
+                let _ = Response::error(503, "Too many connections").write(&stream);
+                continue;
+            };
+            let context = self.context.clone();
+
+            std::thread::spawn(move || {
+                handle(&stream, &context);
+                drop(slot);
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Request {
+    method: String,
+    path: String,
+    body: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    const fn json(status: u16, body: String) -> Self {
+        Self { status, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self::json(status, format!("{{\"error\":{}}}", json_string(message)))
+    }
+
+    const fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            413 => "Payload Too Large",
+            431 => "Request Header Fields Too Large",
+            503 => "Service Unavailable",
+            504 => "Gateway Timeout",
+            _ => "Internal Server Error",
+        }
+    }
+
+    fn write<W: Write>(&self, mut out: W) -> std::io::Result<()> {
+        write!(
+            out,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.reason(),
+            self.body.len(),
+            self.body
+        )?;
+        out.flush()
+    }
+}
+
+fn handle(stream: &TcpStream, context: &Context) {
+    let config = &context.config;
+    let _ = stream.set_read_timeout(Some(config.read_timeout));
+
+    let response = match read_request(stream, config.max_body) {
+        Ok(request) => route(&request, context),
+        Err(response) => response,
+    };
+
+    let _ = response.write(stream);
+
+    // Closing with unread input makes the client see a reset instead of the response, as happens
+    // when a request is turned away before its body is read. Let the client hang up first.
+    let _ = stream.shutdown(Shutdown::Write);
+    let _ = std::io::copy(
+        &mut stream.take((config.max_body + MAX_HEADER) as u64),
+        &mut std::io::sink(),
+    );
+}
+
+fn read_request<S: Read + Write>(stream: S, max_body: usize) -> Result<Request, Response> {
+    let read_error = |e: std::io::Error| match e.kind() {
+        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => {
+            Response::error(408, "Timed out reading the request")
+        }
+        _ => Response::error(400, "Can't read the request"),
+    };
+    let mut reader = BufReader::new(stream.take(MAX_HEADER as u64));
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).map_err(read_error)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(path), Some(_)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(Response::error(400, "Malformed request line"));
+    };
+
+    let mut length = 0;
+    let mut expect_continue = false;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).map_err(read_error)? == 0 {
+            return Err(Response::error(431, "Headers are too large or incomplete"));
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| Response::error(400, "Bad Content-Length"))?;
+            }
+            if name.eq_ignore_ascii_case("expect") && value.trim() == "100-continue" {
+                expect_continue = true;
+            }
+        }
+    }
+
+    if length > max_body {
+        return Err(Response::error(
+            413,
+            &format!("Inputs are limited to {max_body} bytes"),
+        ));
+    }
+
+    // Clients such as curl wait for a go ahead before sending larger bodies.
+    if expect_continue {
+        let stream = reader.get_mut().get_mut();
+        stream
+            .write_all(b"HTTP/1.1 100 Continue\r\n\r\n")
+            .map_err(read_error)?;
+    }
+
+    // The body gets its own allowance on top of whatever of it came in with the headers.
+    reader.get_mut().set_limit(length as u64);
+    let mut body = Vec::with_capacity(length);
+    reader
+        .take(length as u64)
+        .read_to_end(&mut body)
+        .map_err(read_error)?;
+    if body.len() < length {
+        return Err(Response::error(
+            400,
+            "Body is shorter than its Content-Length",
+        ));
+    }
+
+    Ok(Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        body: String::from_utf8(body).map_err(|_| Response::error(400, "Input isn't UTF-8"))?,
+    })
+}
+
+fn route(request: &Request, context: &Context) -> Response {
+    let path = request.path.split('?').next().unwrap_or_default();
+    let segments = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["health"]) => Response::json(
+            200,
+            format!(
+                "{{\"status\":\"ok\",\"version\":{},\"uptime_ms\":{}}}",
+                json_string(env!("CARGO_PKG_VERSION")),
+                context.started.elapsed().as_millis()
+            ),
+        ),
+        ("GET", ["days"]) => Response::json(200, days()),
+        ("POST", ["solve", day]) => day.parse().map_or_else(
+            |_| Response::error(404, "Unknown day"),
+            |day| solve(day, &request.body, context),
+        ),
+        (_, ["health" | "days"] | ["solve", _]) => Response::error(405, "Method not allowed"),
+        _ => Response::error(404, "Not found"),
+    }
+}
+
+fn days() -> String {
+    let days = (1..=solutions::MAX_SOLVED_DAY)
+        .map(|day| {
+            let (part_a, part_b) = solutions::get_solution(day);
+            let parts = [('a', part_a.is_some()), ('b', part_b.is_some())]
+                .iter()
+                .filter(|(_, solved)| *solved)
+                .map(|(part, _)| format!("\"{part}\""))
+                .collect::<Vec<_>>();
+
+            format!("{{\"day\":{},\"parts\":[{}]}}", day, parts.join(","))
+        })
+        .collect::<Vec<_>>();
+
+    format!("{{\"days\":[{}]}}", days.join(","))
+}
+
+/// Solves both parts on a worker thread, giving up on it after the timeout. A solver that runs
+/// over is left to finish in the background, as there's no way to stop a thread from outside, and
+/// keeps its place among the running solvers until it does.
+fn solve(day: u8, input: &str, context: &Context) -> Response {
+    let (part_a, part_b) = (context.lookup)(day);
+    if part_a.is_none() && part_b.is_none() {
+        return Response::error(404, "Unknown day");
+    }
+    let Some(slot) = Slot::take(&context.solvers, context.config.max_solvers) else {
+        return Response::error(503, "Too many solvers running");
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let input = input.to_string();
+    std::thread::spawn(move || {
+        let _slot = slot;
+        let parts = [part_a, part_b].map(|part| {
+            part.map(|part| {
+                let start = Instant::now();
+                let answer = part(&input);
+                (answer, start.elapsed())
+            })
+        });
+        let _ = sender.send(parts);
+    });
+
+    match receiver.recv_timeout(context.config.timeout) {
+        Ok([a, b]) => Response::json(
+            200,
+            format!(
+                "{{\"day\":{},\"a\":{},\"b\":{}}}",
+                day,
+                part_json(a),
+                part_json(b)
+            ),
+        ),
+        Err(mpsc::RecvTimeoutError::Timeout) => Response::error(504, "Solving took too long"),
+        Err(mpsc::RecvTimeoutError::Disconnected) => Response::error(500, "Solver crashed"),
+    }
+}
+
+fn part_json(part: Option<(Result<Solution, Error>, Duration)>) -> String {
+    match part {
+        None => "null".to_string(),
+        Some((answer, time)) => {
+            let answer = match answer {
+                Ok(Solution::Integer(i)) => format!("\"answer\":{i}"),
+                Ok(Solution::String(s)) => format!("\"answer\":{}", json_string(&s)),
+                Err(e) => format!("\"error\":{}", json_string(&format!("{e:?}"))),
+            };
+            format!("{{{},\"micros\":{}}}", answer, time.as_micros())
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out += "\\\"",
+            '\\' => out += "\\\\",
+            '\n' => out += "\\n",
+            '\r' => out += "\\r",
+            '\t' => out += "\\t",
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Starts a server on a free local port, returning its address.
+    fn start(config: Config) -> SocketAddr {
+        start_with(config, solutions::get_solution)
+    }
+
+    /// Starts a server that finds the solvers for a day with `lookup`.
+    fn start_with(
+        config: Config,
+        lookup: fn(u8) -> (PuzzleSolution, PuzzleSolution),
+    ) -> SocketAddr {
+        let mut server = Server::bind("127.0.0.1:0", config).unwrap();
+        server.context.lookup = lookup;
+        let addr = server.local_addr().unwrap();
+        std::thread::spawn(move || server.run());
+        addr
+    }
+
+    /// Solves every day's part a by never finishing.
+    fn blocking(_day: u8) -> (PuzzleSolution, PuzzleSolution) {
+        fn block(_file: &str) -> Result<Solution, Error> {
+            loop {
+                std::thread::park();
+            }
+        }
+
+        (Some(block), None)
+    }
+
+    /// Sends `request` as is and returns the status and body of the response.
+    fn send(addr: SocketAddr, request: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        receive(stream)
+    }
+
+    fn receive(mut stream: TcpStream) -> (u16, String) {
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+
+        (status, body.to_string())
+    }
+
+    fn post(addr: SocketAddr, path: &str, body: &str) -> (u16, String) {
+        send(
+            addr,
+            &format!(
+                "POST {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+                path,
+                body.len(),
+                body
+            ),
+        )
+    }
+
+    #[test]
+    fn test_health_and_days() {
+        let addr = start(Config::default());
+
+        let (status, body) = send(addr, "GET /health HTTP/1.1\r\n\r\n");
+        assert_eq!(200, status);
+        assert!(body.starts_with("{\"status\":\"ok\",\"version\":\"0.0.1\",\"uptime_ms\":"));
+
+        let (status, body) = send(addr, "GET /days HTTP/1.1\r\n\r\n");
+        assert_eq!(200, status);
+        assert!(body.starts_with("{\"days\":[{\"day\":1,\"parts\":[\"a\",\"b\"]},"));
+
+        assert_eq!(404, send(addr, "GET /nowhere HTTP/1.1\r\n\r\n").0);
+        assert_eq!(405, send(addr, "DELETE /days HTTP/1.1\r\n\r\n").0);
+        assert_eq!(400, send(addr, "nonsense\r\n\r\n").0);
+    }
+
+    #[test]
+    fn test_solve() {
+        let addr = start(Config::default());
+
+        let (status, body) = post(addr, "/solve/1", "1000\n2000\n\n4000\n\n5000\n6000");
+        assert_eq!(200, status);
+        assert!(body.starts_with("{\"day\":1,\"a\":{\"answer\":11000,\"micros\":"));
+        assert!(body.contains(",\"b\":{\"answer\":18000,\"micros\":"));
+
+        let (status, body) = post(addr, "/solve/10", "noop\n");
+        assert_eq!(200, status);
+        assert!(
+            body.contains("\"a\":{\"error\":\"InputError(\\\"Program ends before cycle 220\\\")\"")
+        );
+
+        assert_eq!(404, post(addr, "/solve/30", "").0);
+        assert_eq!(404, post(addr, "/solve/x", "").0);
+    }
+
+    #[test]
+    fn test_limits() {
+        let addr = start(Config {
+            max_body: 16,
+            read_timeout: Duration::from_millis(200),
+            ..Config::default()
+        });
+
+        let (status, body) = post(addr, "/solve/1", &"1\n".repeat(10));
+        assert_eq!(413, status);
+        assert_eq!("{\"error\":\"Inputs are limited to 16 bytes\"}", body);
+
+        // A client that stops halfway through its body is cut off.
+        let request = "POST /solve/1 HTTP/1.1\r\nContent-Length: 10\r\n\r\n1\n";
+        assert_eq!(408, send(addr, request).0);
+
+        let headers = format!(
+            "GET /days HTTP/1.1\r\nX-Padding: {}\r\n\r\n",
+            "x".repeat(9000)
+        );
+        assert_eq!(431, send(addr, &headers).0);
+    }
+
+    #[test]
+    fn test_timeout() {
+        let addr = start_with(
+            Config {
+                timeout: Duration::from_millis(50),
+                ..Config::default()
+            },
+            blocking,
+        );
+
+        assert_eq!(504, post(addr, "/solve/1", "1\n").0);
+    }
+
+    #[test]
+    fn test_solver_limit() {
+        let addr = start_with(
+            Config {
+                timeout: Duration::from_millis(50),
+                max_solvers: 2,
+                ..Config::default()
+            },
+            blocking,
+        );
+
+        // Solvers that ran over still hold their places.
+        assert_eq!(504, post(addr, "/solve/1", "1\n").0);
+        assert_eq!(504, post(addr, "/solve/1", "1\n").0);
+        let (status, body) = post(addr, "/solve/1", "1\n");
+        assert_eq!(503, status);
+        assert_eq!("{\"error\":\"Too many solvers running\"}", body);
+
+        assert_eq!(200, send(addr, "GET /health HTTP/1.1\r\n\r\n").0);
+    }
+
+    #[test]
+    fn test_connection_limit() {
+        let addr = start(Config {
+            max_connections: 1,
+            ..Config::default()
+        });
+
+        // A client holding its connection open without a request keeps the only place.
+        let idle = TcpStream::connect(addr).unwrap();
+        let (status, body) = receive(TcpStream::connect(addr).unwrap());
+        assert_eq!(503, status);
+        assert_eq!("{\"error\":\"Too many connections\"}", body);
+
+        // Once it hangs up, its place is free again. Being turned away before the request is read
+        // can reset the connection, so attempts in the meantime may fail either way.
+        idle.shutdown(Shutdown::Write).unwrap();
+        assert_eq!(400, receive(idle).0);
+        let healthy = (0..100).any(|_| {
+            std::thread::sleep(Duration::from_millis(10));
+            let mut stream = TcpStream::connect(addr).unwrap();
+            let mut response = String::new();
+            stream.write_all(b"GET /health HTTP/1.1\r\n\r\n").is_ok()
+                && stream.read_to_string(&mut response).is_ok()
+                && response.starts_with("HTTP/1.1 200")
+        });
+        assert!(healthy);
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!("\"a\\\"b\\\\c\\nd\\u0001\"", json_string("a\"b\\c\nd\u{1}"));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::error::Error;
 use crate::solutions::Solution;
 
 const SCORE_WIN: i64 = 6;
@@ -8,13 +9,15 @@ const SCORE_ROCK: i64 = 1;
 const SCORE_PAPER: i64 = 2;
 const SCORE_SCISSOR: i64 = 3;
 
-pub fn part_a(file: &str) -> Solution {
-    Solution::Integer(file.lines().map(score_a).sum())
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(
+        file.lines().map(score_a).sum::<Result<_, _>>()?,
+    ))
 }
 
 //noinspection DuplicatedCode
-fn score_a(line: &str) -> i64 {
-    match line {
+fn score_a(line: &str) -> Result<i64, Error> {
+    Ok(match line {
         "A X" => SCORE_ROCK + SCORE_DRAW,
         "A Y" => SCORE_PAPER + SCORE_WIN,
         "A Z" => SCORE_SCISSOR + SCORE_LOSE,
@@ -24,17 +27,19 @@ fn score_a(line: &str) -> i64 {
         "C X" => SCORE_ROCK + SCORE_WIN,
         "C Y" => SCORE_PAPER + SCORE_LOSE,
         "C Z" => SCORE_SCISSOR + SCORE_DRAW,
-        _ => panic!("Unknown line"),
-    }
+        _ => return Err(Error::input("Unknown line")),
+    })
 }
 
-pub fn part_b(file: &str) -> Solution {
-    Solution::Integer(file.lines().map(score_b).sum())
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(
+        file.lines().map(score_b).sum::<Result<_, _>>()?,
+    ))
 }
 
 //noinspection DuplicatedCode
-fn score_b(line: &str) -> i64 {
-    match line {
+fn score_b(line: &str) -> Result<i64, Error> {
+    Ok(match line {
         "A X" => SCORE_SCISSOR + SCORE_LOSE,
         "A Y" => SCORE_ROCK + SCORE_DRAW,
         "A Z" => SCORE_PAPER + SCORE_WIN,
@@ -44,8 +49,8 @@ fn score_b(line: &str) -> i64 {
         "C X" => SCORE_PAPER + SCORE_LOSE,
         "C Y" => SCORE_SCISSOR + SCORE_DRAW,
         "C Z" => SCORE_ROCK + SCORE_WIN,
-        _ => panic!("Unknown line"),
-    }
+        _ => return Err(Error::input("Unknown line")),
+    })
 }
 
 #[cfg(test)]
@@ -54,19 +59,25 @@ mod tests {
 
     #[test]
     fn test_a() {
-        assert_eq!(8, score_a("A Y"));
-        assert_eq!(1, score_a("B X"));
-        assert_eq!(6, score_a("C Z"));
+        assert_eq!(8, score_a("A Y").unwrap());
+        assert_eq!(1, score_a("B X").unwrap());
+        assert_eq!(6, score_a("C Z").unwrap());
 
-        assert_eq!(Solution::Integer(15), part_a("A Y\nB X\nC Z"));
+        assert_eq!(Solution::Integer(15), part_a("A Y\nB X\nC Z").unwrap());
     }
 
     #[test]
     fn test_b() {
-        assert_eq!(4, score_b("A Y"));
-        assert_eq!(1, score_b("B X"));
-        assert_eq!(7, score_b("C Z"));
+        assert_eq!(4, score_b("A Y").unwrap());
+        assert_eq!(1, score_b("B X").unwrap());
+        assert_eq!(7, score_b("C Z").unwrap());
 
-        assert_eq!(Solution::Integer(12), part_b("A Y\nB X\nC Z"));
+        assert_eq!(Solution::Integer(12), part_b("A Y\nB X\nC Z").unwrap());
+    }
+
+    #[test]
+    fn test_unknown_line() {
+        assert!(part_a("A Y\nD X").is_err());
+        assert!(part_b("A Y\n").is_ok());
     }
 }
@@ -1,48 +1,40 @@
+use crate::error::Error;
+use crate::prelude::*;
 use crate::solutions::Solution;
 
-pub fn part_a(file: &str) -> Solution {
-    Solution::Integer(file.lines().map(score_backpack).sum())
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(
+        file.lines().map(score_backpack).sum::<Result<_, _>>()?,
+    ))
 }
 
-pub fn part_b(file: &str) -> Solution {
-    let mut lines = file.lines().peekable();
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    let lines = file.lines().collect::<Vec<_>>();
 
-    let mut total: i64 = 0;
-
-    loop {
-        let first = lines.next().unwrap();
-        let second = lines.next().unwrap();
-        let third = lines.next().unwrap();
-
-        total += score_item(in_three(first, second, third));
-
-        if lines.peek().is_none() {
-            break;
-        }
+    if lines.len() % 3 != 0 {
+        return Err(Error::input("Backpacks don't form groups of three"));
     }
 
-    Solution::Integer(total)
-}
+    let mut total: i64 = 0;
 
-fn in_both(backpack: &str) -> char {
-    let (first, second) = backpack.split_at(backpack.len() / 2);
-    for c in first.chars() {
-        if second.chars().any(|d| d == c) {
-            return c;
-        }
+    for group in lines.chunks(3) {
+        let badge = in_three(group[0], group[1], group[2])
+            .ok_or_else(|| Error::input("No common characters"))?;
+        total += score_item(badge);
     }
 
-    panic!("No common characters");
+    Ok(Solution::Integer(total))
 }
 
-fn in_three(first: &str, second: &str, third: &str) -> char {
-    for c in first.chars() {
-        if second.chars().any(|d| d == c) && third.chars().any(|d| d == c) {
-            return c;
-        }
-    }
+fn in_both(backpack: &str) -> Option<char> {
+    let (first, second) = backpack.split_at_checked(backpack.len() / 2)?;
+    first.chars().find(|&c| second.chars().any(|d| d == c))
+}
 
-    panic!("No common characters");
+fn in_three(first: &str, second: &str, third: &str) -> Option<char> {
+    first
+        .chars()
+        .find(|&c| second.chars().any(|d| d == c) && third.chars().any(|d| d == c))
 }
 
 fn score_item(i: char) -> i64 {
@@ -53,8 +45,10 @@ fn score_item(i: char) -> i64 {
     }
 }
 
-fn score_backpack(backpack: &str) -> i64 {
-    score_item(in_both(backpack))
+fn score_backpack(backpack: &str) -> Result<i64, Error> {
+    in_both(backpack)
+        .map(score_item)
+        .ok_or_else(|| Error::input("No common characters"))
 }
 
 #[cfg(test)]
@@ -63,12 +57,12 @@ mod tests {
 
     #[test]
     fn test_a() {
-        assert_eq!('p', in_both("vJrwpWtwJgWrhcsFMMfFFhFp"));
-        assert_eq!('L', in_both("jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL"));
-        assert_eq!('P', in_both("PmmdzqPrVvPwwTWBwg"));
-        assert_eq!('v', in_both("wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn"));
-        assert_eq!('t', in_both("ttgJtRGJQctTZtZT"));
-        assert_eq!('s', in_both("CrZsJsPPZsGzwwsLwLmpwMDw"));
+        assert_eq!(Some('p'), in_both("vJrwpWtwJgWrhcsFMMfFFhFp"));
+        assert_eq!(Some('L'), in_both("jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL"));
+        assert_eq!(Some('P'), in_both("PmmdzqPrVvPwwTWBwg"));
+        assert_eq!(Some('v'), in_both("wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn"));
+        assert_eq!(Some('t'), in_both("ttgJtRGJQctTZtZT"));
+        assert_eq!(Some('s'), in_both("CrZsJsPPZsGzwwsLwLmpwMDw"));
 
         assert_eq!(16, score_item('p'));
         assert_eq!(38, score_item('L'));
@@ -77,13 +71,13 @@ mod tests {
         assert_eq!(20, score_item('t'));
         assert_eq!(19, score_item('s'));
 
-        assert_eq!(Solution::Integer(157), part_a("vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\nPmmdzqPrVvPwwTWBwg\nwMqvLMZHhHMvwLHjbvcjnnSBnvTQFn\nttgJtRGJQctTZtZT\nCrZsJsPPZsGzwwsLwLmpwMDw"));
+        assert_eq!(Solution::Integer(157), part_a("vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\nPmmdzqPrVvPwwTWBwg\nwMqvLMZHhHMvwLHjbvcjnnSBnvTQFn\nttgJtRGJQctTZtZT\nCrZsJsPPZsGzwwsLwLmpwMDw").unwrap());
     }
 
     #[test]
     fn test_b() {
         assert_eq!(
-            'r',
+            Some('r'),
             in_three(
                 "vJrwpWtwJgWrhcsFMMfFFhFp",
                 "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
@@ -91,13 +85,14 @@ mod tests {
             )
         );
         assert_eq!(
-            'Z',
+            Some('Z'),
             in_three(
                 "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn",
                 "ttgJtRGJQctTZtZT",
                 "CrZsJsPPZsGzwwsLwLmpwMDw",
             )
         );
-        assert_eq!(Solution::Integer(70), part_b("vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\nPmmdzqPrVvPwwTWBwg\nwMqvLMZHhHMvwLHjbvcjnnSBnvTQFn\nttgJtRGJQctTZtZT\nCrZsJsPPZsGzwwsLwLmpwMDw"));
+        assert_eq!(Solution::Integer(70), part_b("vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\nPmmdzqPrVvPwwTWBwg\nwMqvLMZHhHMvwLHjbvcjnnSBnvTQFn\nttgJtRGJQctTZtZT\nCrZsJsPPZsGzwwsLwLmpwMDw").unwrap());
+        assert!(part_b("vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL").is_err());
     }
 }
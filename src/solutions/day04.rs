@@ -1,31 +1,56 @@
-use std::ops::RangeInclusive;
+use core::ops::RangeInclusive;
 
+use crate::error::Error;
+use crate::intervals::IntervalSet;
+use crate::prelude::*;
 use crate::solutions::Solution;
 
-fn contains(ranges: &(RangeInclusive<u32>, RangeInclusive<u32>)) -> bool {
-    (ranges.0.contains(ranges.1.start()) && ranges.0.contains(ranges.1.end()))
-        || (ranges.1.contains(ranges.0.start()) && ranges.1.contains(ranges.0.end()))
+type Pair = (RangeInclusive<i64>, RangeInclusive<i64>);
+
+fn contains(ranges: &Pair) -> bool {
+    let first = IntervalSet::from_iter([ranges.0.clone()]);
+    let second = IntervalSet::from_iter([ranges.1.clone()]);
+    let both = first.intersection(&second);
+
+    both == first || both == second
 }
 
-fn overlaps(ranges: &(RangeInclusive<u32>, RangeInclusive<u32>)) -> bool {
-    ranges.0.contains(ranges.1.start()) || ranges.1.contains(ranges.0.start())
+fn overlaps(ranges: &Pair) -> bool {
+    let first = IntervalSet::from_iter([ranges.0.clone()]);
+    let second = IntervalSet::from_iter([ranges.1.clone()]);
+
+    !first.intersection(&second).is_empty()
 }
 
-fn parse_line(line: &str) -> (RangeInclusive<u32>, RangeInclusive<u32>) {
-    let mut parts = line.split([',', '-']).map(|s| s.parse().unwrap());
+fn parse_line(line: &str) -> Result<Pair, Error> {
+    let parts = line
+        .split([',', '-'])
+        .map(|part| Ok(i64::from(part.parse::<u32>()?)))
+        .collect::<Result<Vec<i64>, Error>>()?;
+
+    match parts[..] {
+        [a, b, c, d] if a <= b && c <= d => Ok((a..=b, c..=d)),
+        [_, _, _, _] => Err(Error::input("Ranges can't end before they start")),
+        _ => Err(Error::input("Expected two ranges")),
+    }
+}
 
-    (
-        parts.next().unwrap()..=parts.next().unwrap(),
-        parts.next().unwrap()..=parts.next().unwrap(),
-    )
+fn parse_file(file: &str) -> Result<Vec<Pair>, Error> {
+    file.lines().map(parse_line).collect()
 }
 
-pub fn part_a(file: &str) -> Solution {
-    Solution::Integer(file.lines().map(parse_line).filter(contains).count() as i64)
+#[allow(clippy::cast_possible_wrap)]
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(
+        parse_file(file)?.iter().filter(|r| contains(r)).count() as i64,
+    ))
 }
 
-pub fn part_b(file: &str) -> Solution {
-    Solution::Integer(file.lines().map(parse_line).filter(overlaps).count() as i64)
+#[allow(clippy::cast_possible_wrap)]
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(
+        parse_file(file)?.iter().filter(|r| overlaps(r)).count() as i64,
+    ))
 }
 
 #[cfg(test)]
@@ -54,15 +79,17 @@ mod tests {
 
     #[test]
     fn test_parse_line() {
-        assert_eq!((2..=4, 6..=8), parse_line("2-4,6-8"));
-        assert_eq!((54..=54, 55..=68), parse_line("54-54,55-68"));
+        assert_eq!((2..=4, 6..=8), parse_line("2-4,6-8").unwrap());
+        assert_eq!((54..=54, 55..=68), parse_line("54-54,55-68").unwrap());
+        assert!(parse_line("2-4,6").is_err());
+        assert!(parse_line("4-2,6-8").is_err());
     }
 
     #[test]
     fn test_a() {
         assert_eq!(
             Solution::Integer(2),
-            part_a("2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8\n")
+            part_a("2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8\n").unwrap()
         );
     }
 
@@ -70,7 +97,7 @@ mod tests {
     fn test_b() {
         assert_eq!(
             Solution::Integer(4),
-            part_b("2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8\n")
+            part_b("2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8\n").unwrap()
         );
     }
 }
@@ -1,3 +1,8 @@
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::parse;
+use crate::prelude::*;
+use crate::render::{Frame, Recorder, Scene};
 use crate::solutions::Solution;
 
 #[derive(Eq, PartialEq, Debug)]
@@ -34,59 +39,122 @@ fn parse_stack_line(line: &str) -> impl Iterator<Item = Option<char>> + '_ {
     })
 }
 
-fn parse_move(line: &str) -> Move {
-    let mut nums = line
-        .split(' ')
-        .skip(1)
-        .step_by(2)
-        .map(|s| s.parse().unwrap());
+fn parse_move(line: &str) -> Result<Move, Error> {
+    let (count, from, to) = parse::capture("move {} from {} to {}", line)?.parse()?;
 
-    let count = nums.next().unwrap();
-    let from = nums.next().unwrap();
-    let to = nums.next().unwrap();
+    Ok(Move { count, from, to })
+}
 
-    Move { count, from, to }
+fn parse_moves(lines: &str) -> impl Iterator<Item = Result<Move, Error>> + '_ {
+    lines.lines().map(parse_move)
 }
 
-fn parse_moves(lines: &str) -> impl Iterator<Item = Move> + '_ {
-    lines.lines().skip(2).map(parse_move)
+fn parse_input(
+    file: &str,
+) -> Result<(Stacks, impl Iterator<Item = Result<Move, Error>> + '_), Error> {
+    let mut blocks = parse::blocks(file);
+
+    match (blocks.next(), blocks.next(), blocks.next()) {
+        (Some(stacks), Some(moves), None) => Ok((parse_stacks(stacks), parse_moves(moves))),
+        _ => Err(Error::input(
+            "Expected the stacks and the moves separated by a blank line",
+        )),
+    }
 }
 
-fn parse_input(file: &str) -> (Stacks, impl Iterator<Item = Move> + '_) {
-    let parts = file.split_at(file.find("\n\n").unwrap());
+/// Checks that a move only refers to existing stacks and doesn't take more crates than there are.
+fn validate_move(stack: &Stacks, m: &Move) -> Result<(usize, usize), Error> {
+    let from = (m.from as usize).wrapping_sub(1);
+    let to = (m.to as usize).wrapping_sub(1);
+
+    if from >= stack.len() || to >= stack.len() {
+        return Err(Error::input("Move refers to an unknown stack"));
+    }
+    if stack[from].len() < m.count as usize {
+        return Err(Error::input("Not enough crates to move"));
+    }
 
-    (parse_stacks(parts.0), parse_moves(parts.1))
+    Ok((from, to))
 }
 
-pub fn part_a(file: &str) -> Solution {
-    let (mut stack, moves) = parse_input(file);
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    let (mut stack, moves) = parse_input(file)?;
 
     for m in moves {
+        let m = m?;
+        let (from, to) = validate_move(&stack, &m)?;
+
         for _ in 0..m.count {
-            let val = stack[m.from as usize - 1].pop().unwrap();
-            stack[m.to as usize - 1].push(val);
+            if let Some(val) = stack[from].pop() {
+                stack[to].push(val);
+            }
         }
     }
 
     let result = stack.iter().filter_map(|k| k.last()).collect();
 
-    Solution::String(result)
+    Ok(Solution::String(result))
 }
 
-pub fn part_b(file: &str) -> Solution {
-    let (mut stack, moves) = parse_input(file);
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    let (mut stack, moves) = parse_input(file)?;
 
     for m in moves {
-        let original_stack = &mut stack[m.from as usize - 1];
+        let m = m?;
+        let (from, to) = validate_move(&stack, &m)?;
+
+        let original_stack = &mut stack[from];
         let index = original_stack.len() - (m.count as usize);
 
         let mut taken = original_stack.split_off(index);
-        stack[m.to as usize - 1].append(&mut taken);
+        stack[to].append(&mut taken);
     }
 
     let result = stack.iter().filter_map(|k| k.last()).collect();
 
-    Solution::String(result)
+    Ok(Solution::String(result))
+}
+
+/// Draws the stacks bottom up with a gap between them, each crate shaded by its letter.
+fn draw_stacks(stacks: &Stacks, height: usize) -> Frame {
+    let mut frame = Grid::filled((stacks.len() * 2).saturating_sub(1), height, 0);
+
+    for (i, stack) in stacks.iter().enumerate() {
+        for (j, &c) in stack.iter().enumerate() {
+            let level = (u32::from(c).saturating_sub(u32::from('A')) % 26) as u8 + 1;
+            frame[(i * 2, height - 1 - j)] = level;
+        }
+    }
+
+    frame
+}
+
+/// The stacks after every move of the `CrateMover` 9000.
+#[allow(clippy::cast_possible_truncation)]
+pub fn scene(file: &str, frames: usize) -> Result<Scene, Error> {
+    let (mut stack, moves) = parse_input(file)?;
+    let moves = moves.collect::<Result<Vec<_>, _>>()?;
+    let height = stack.iter().map(Vec::len).sum::<usize>().max(1);
+
+    let mut recorder = Recorder::new(moves.len(), frames);
+    recorder.frames.push(draw_stacks(&stack, height));
+
+    for m in &moves {
+        let (from, to) = validate_move(&stack, m)?;
+
+        for _ in 0..m.count {
+            if let Some(val) = stack[from].pop() {
+                stack[to].push(val);
+            }
+        }
+        recorder.step(|| draw_stacks(&stack, height));
+    }
+
+    Ok(Scene {
+        frames: recorder.frames,
+        levels: 27,
+        accent: false,
+    })
 }
 
 #[cfg(test)]
@@ -116,8 +184,9 @@ mod tests {
                 from: 2,
                 to: 1,
             },
-            parse_move("move 1 from 2 to 1")
+            parse_move("move 1 from 2 to 1").unwrap()
         );
+        assert!(parse_move("move 1 from 2").is_err());
     }
 
     #[test]
@@ -145,17 +214,55 @@ mod tests {
                     to: 2,
                 },
             ],
-            parse_input(TEST_INPUT).1.collect::<Vec<_>>()
+            parse_input(TEST_INPUT)
+                .unwrap()
+                .1
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
         );
     }
 
     #[test]
     fn test_part_a() {
-        assert_eq!(Solution::String("CMZ".to_string()), part_a(TEST_INPUT));
+        assert_eq!(
+            Solution::String("CMZ".to_string()),
+            part_a(TEST_INPUT).unwrap()
+        );
     }
 
     #[test]
     fn test_part_b() {
-        assert_eq!(Solution::String("MCD".to_string()), part_b(TEST_INPUT));
+        assert_eq!(
+            Solution::String("MCD".to_string()),
+            part_b(TEST_INPUT).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_invalid_moves() {
+        let input = TEST_INPUT.replace("move 1 from 2 to 1", "move 1 from 4 to 1");
+        assert!(part_a(&input).is_err());
+
+        let input = TEST_INPUT.replace("move 1 from 2 to 1", "move 9 from 2 to 1");
+        assert!(part_b(&input).is_err());
+    }
+
+    #[test]
+    fn test_scene() {
+        let scene = scene(TEST_INPUT, 10).unwrap();
+
+        let last = &scene.frames[4];
+
+        // The stacks end as C, M and PDNZ.
+        assert_eq!(5, scene.frames.len());
+        assert_eq!((5, 6), (last.width(), last.height()));
+        assert_eq!(
+            vec![3, 0, 13, 0, 16],
+            last.row(5).copied().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![0, 0, 26, 14, 4, 16],
+            last.column(4).copied().collect::<Vec<_>>()
+        );
     }
 }
@@ -1,3 +1,5 @@
+use crate::error::Error;
+use crate::prelude::*;
 use crate::solutions::Solution;
 
 fn parse_start_of_packet<const N: usize>(string: &str) -> i64 {
@@ -16,7 +18,8 @@ fn parse_start_of_packet<const N: usize>(string: &str) -> i64 {
     0
 }
 
-/// Use bit masking to detect if all chars are distinct. Only works for \[a-z\], as that fits inside `u32`
+/// Use bit masking to detect if all chars are distinct. Only works for \[a-z\], as that fits inside `u32`,
+/// anything else falls back to comparing every pair.
 fn all_distinct(chars: &[char]) -> bool {
     let mut acc = 0;
 
@@ -25,6 +28,10 @@ fn all_distinct(chars: &[char]) -> bool {
             return false;
         }
 
+        if !c.is_ascii_lowercase() {
+            return !chars.contains(&'\0') && all_distinct_naive(chars);
+        }
+
         let mask: u32 = 1 << (*c as i32 - 97);
 
         if acc & mask == mask {
@@ -37,16 +44,48 @@ fn all_distinct(chars: &[char]) -> bool {
     true
 }
 
-pub fn part_a(file: &str) -> Solution {
-    Solution::Integer(parse_start_of_packet::<4>(file))
+fn all_distinct_naive(chars: &[char]) -> bool {
+    chars
+        .iter()
+        .enumerate()
+        .all(|(i, c)| !chars[..i].contains(c))
+}
+
+/// Naive reference for `parse_start_of_packet`, checking every window from scratch.
+#[allow(clippy::cast_possible_wrap)]
+fn start_of_packet_naive(string: &str, length: usize) -> i64 {
+    let chars = string.chars().collect::<Vec<_>>();
+
+    chars
+        .windows(length)
+        .position(all_distinct_naive)
+        .map_or(0, |i| (i + length) as i64)
+}
+
+#[allow(clippy::unnecessary_wraps)]
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(parse_start_of_packet::<4>(file)))
+}
+
+#[allow(clippy::unnecessary_wraps)]
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(parse_start_of_packet::<14>(file)))
+}
+
+#[allow(clippy::unnecessary_wraps)]
+pub fn reference_part_a(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(start_of_packet_naive(file, 4)))
 }
 
-pub fn part_b(file: &str) -> Solution {
-    Solution::Integer(parse_start_of_packet::<14>(file))
+#[allow(clippy::unnecessary_wraps)]
+pub fn reference_part_b(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(start_of_packet_naive(file, 14)))
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
@@ -89,4 +128,34 @@ mod tests {
             parse_start_of_packet::<14>("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw")
         );
     }
+
+    #[test]
+    fn test_all_distinct() {
+        assert!(all_distinct(&['a', 'b', 'c', 'd']));
+        assert!(!all_distinct(&['a', 'b', 'c', 'a']));
+        assert!(all_distinct(&['A', 'b', 'C', 'd']));
+        assert!(!all_distinct(&['A', 'b', 'A', 'd']));
+        assert!(!all_distinct(&['\0', 'b', 'c', 'd']));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_all_distinct_matches_naive(
+            chars in prop::collection::vec(prop::char::range('a', 'z'), 1..16)
+        ) {
+            prop_assert_eq!(all_distinct_naive(&chars), all_distinct(&chars));
+        }
+
+        #[test]
+        fn prop_start_of_packet_matches_naive(stream in "[a-z]{0,100}|[a-zA-Z]{0,100}") {
+            prop_assert_eq!(
+                start_of_packet_naive(&stream, 4),
+                parse_start_of_packet::<4>(&stream)
+            );
+            prop_assert_eq!(
+                start_of_packet_naive(&stream, 14),
+                parse_start_of_packet::<14>(&stream)
+            );
+        }
+    }
 }
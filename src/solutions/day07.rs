@@ -1,5 +1,7 @@
-use std::collections::HashMap;
+use hashbrown::HashMap;
 
+use crate::error::Error;
+use crate::prelude::*;
 use crate::solutions::Solution;
 
 #[derive(Eq, PartialEq, Debug)]
@@ -10,14 +12,17 @@ enum Command {
     Ls { size: i64 },
 }
 
-fn parse_commands(file: &str) -> impl Iterator<Item = Command> + '_ {
+fn parse_commands(file: &str) -> impl Iterator<Item = Result<Command, Error>> + '_ {
     file.split('$').skip(1).map(|c| {
         let cmd = c.trim();
 
-        return if cmd.starts_with('c') {
-            let p = cmd.split_once(' ').unwrap().1;
+        if cmd.starts_with('c') {
+            let p = cmd
+                .split_once(' ')
+                .ok_or_else(|| Error::input("cd without a directory"))?
+                .1;
 
-            if p.starts_with('/') {
+            Ok(if p.starts_with('/') {
                 Command::CdRoot
             } else if p.starts_with('.') {
                 Command::CdUp
@@ -25,36 +30,45 @@ fn parse_commands(file: &str) -> impl Iterator<Item = Command> + '_ {
                 Command::CdDir {
                     directory: p.to_string(),
                 }
-            }
+            })
         } else {
+            // Sizes are read as `u32` so the sums can't overflow.
             let size = cmd
                 .lines()
                 .skip(1)
                 .filter(|l| !l.starts_with('d'))
-                .map(|l| l.split_once(' ').unwrap().0.parse::<i64>().unwrap())
-                .sum();
+                .map(|l| {
+                    let (size, _) = l
+                        .split_once(' ')
+                        .ok_or_else(|| Error::input("File without a name"))?;
+                    Ok(i64::from(size.parse::<u32>()?))
+                })
+                .sum::<Result<_, Error>>()?;
 
-            Command::Ls { size }
-        };
+            Ok(Command::Ls { size })
+        }
     })
 }
 
-fn execute_commands(commands: impl Iterator<Item = Command>) -> HashMap<String, i64> {
+fn execute_commands(
+    commands: impl Iterator<Item = Result<Command, Error>>,
+) -> Result<HashMap<String, i64>, Error> {
     let mut path = "/".to_string();
 
     let mut tree: HashMap<String, i64> = HashMap::new();
 
     for command in commands {
-        match command {
+        match command? {
             Command::CdRoot => {
-                path.drain(..1);
+                path.clear();
             }
             Command::CdDir { directory } => {
                 path.push_str(&directory);
                 path.push('/');
             }
             Command::CdUp => {
-                path.drain(..path.rfind('/').unwrap());
+                path.pop();
+                path.truncate(path.rfind('/').map_or(0, |i| i + 1));
             }
             Command::Ls { size } => {
                 if tree.contains_key(&path) {
@@ -65,7 +79,7 @@ fn execute_commands(commands: impl Iterator<Item = Command>) -> HashMap<String,
         }
     }
 
-    tree
+    Ok(tree)
 }
 
 fn tree_collapse(tree: &HashMap<String, i64>) -> (i64, impl Iterator<Item = i64> + '_) {
@@ -88,29 +102,124 @@ fn tree_collapse(tree: &HashMap<String, i64>) -> (i64, impl Iterator<Item = i64>
     (root, iter)
 }
 
-pub fn part_a(file: &str) -> Solution {
-    let tree = execute_commands(parse_commands(file));
+/// Naive reference for `execute_commands` and `tree_collapse`: tracks the current directory as a
+/// list of names and adds every listing to each directory containing it.
+fn directory_sizes_naive(
+    commands: impl Iterator<Item = Result<Command, Error>>,
+) -> Result<Vec<i64>, Error> {
+    let mut cwd: Vec<String> = vec![];
+    let mut listed: HashMap<Vec<String>, i64> = HashMap::new();
+
+    for command in commands {
+        match command? {
+            Command::CdRoot => cwd.clear(),
+            Command::CdUp => {
+                cwd.pop();
+            }
+            Command::CdDir { directory } => cwd.push(directory),
+            Command::Ls { size } => {
+                listed.entry(cwd.clone()).or_insert(size);
+            }
+        }
+    }
+
+    Ok(listed
+        .keys()
+        .map(|dir| {
+            listed
+                .iter()
+                .filter(|(sub, _)| sub.starts_with(dir))
+                .map(|(_, size)| size)
+                .sum()
+        })
+        .collect())
+}
+
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    let tree = execute_commands(parse_commands(file))?;
 
     let (_, iter) = tree_collapse(&tree);
 
-    Solution::Integer(iter.filter(|v| *v <= 100_000).sum())
+    Ok(Solution::Integer(iter.filter(|v| *v <= 100_000).sum()))
 }
 
-pub fn part_b(file: &str) -> Solution {
-    let tree = execute_commands(parse_commands(file));
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    let tree = execute_commands(parse_commands(file))?;
     let (used, iter) = tree_collapse(&tree);
 
     let total: i64 = 70_000_000;
     let required: i64 = 30_000_000;
     let to_free = required - (total - used);
 
-    Solution::Integer(iter.filter(|v| v >= &to_free).min().unwrap())
+    iter.filter(|v| v >= &to_free)
+        .min()
+        .map(Solution::Integer)
+        .ok_or_else(|| Error::input("No directory is large enough"))
+}
+
+pub fn reference_part_a(file: &str) -> Result<Solution, Error> {
+    let sizes = directory_sizes_naive(parse_commands(file))?;
+
+    Ok(Solution::Integer(
+        sizes.iter().filter(|v| **v <= 100_000).sum(),
+    ))
+}
+
+pub fn reference_part_b(file: &str) -> Result<Solution, Error> {
+    let sizes = directory_sizes_naive(parse_commands(file))?;
+    let used = sizes.iter().max().copied().unwrap_or(0);
+    let to_free = 30_000_000 - (70_000_000 - used);
+
+    sizes
+        .into_iter()
+        .filter(|v| *v >= to_free)
+        .min()
+        .map(Solution::Integer)
+        .ok_or_else(|| Error::input("No directory is large enough"))
 }
 
 #[cfg(test)]
 mod tests {
+    use std::fmt::Write;
+
+    use proptest::prelude::*;
+
     use super::*;
 
+    #[derive(Clone, Debug)]
+    struct Tree {
+        files: Vec<u32>,
+        children: Vec<Tree>,
+    }
+
+    fn tree_strategy() -> impl Strategy<Value = Tree> {
+        let files = || prop::collection::vec(1..200_000_u32, 0..4);
+        let leaf = files().prop_map(|files| Tree {
+            files,
+            children: vec![],
+        });
+
+        leaf.prop_recursive(4, 32, 4, move |inner| {
+            (files(), prop::collection::vec(inner, 0..4))
+                .prop_map(|(files, children)| Tree { files, children })
+        })
+    }
+
+    fn write_session(tree: &Tree, session: &mut String) {
+        session.push_str("$ ls\n");
+        for i in 0..tree.children.len() {
+            writeln!(session, "dir d{}", i).unwrap();
+        }
+        for (i, size) in tree.files.iter().enumerate() {
+            writeln!(session, "{} f{}", size, i).unwrap();
+        }
+        for (i, child) in tree.children.iter().enumerate() {
+            writeln!(session, "$ cd d{}", i).unwrap();
+            write_session(child, session);
+            session.push_str("$ cd ..\n");
+        }
+    }
+
     fn read_test_data() -> String {
         let path = std::env::current_dir()
             .unwrap()
@@ -124,7 +233,9 @@ mod tests {
         let test_data = read_test_data();
 
         assert_eq!(
-            parse_commands(test_data.as_str()).collect::<Vec<_>>(),
+            parse_commands(test_data.as_str())
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
             vec![
                 Command::CdRoot,
                 Command::Ls { size: 23_352_670 },
@@ -146,15 +257,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cd_up_from_nested_directory() {
+        // `cd ..` used to go back to the root from any depth, which put `c` beside `a` rather
+        // than in it and gave 700 here.
+        let session = "\
+$ cd /
+$ ls
+dir a
+$ cd a
+$ ls
+dir b
+dir c
+$ cd b
+$ ls
+100 x
+$ cd ..
+$ cd c
+$ ls
+200 y
+";
+        assert_eq!(Solution::Integer(900), part_a(session).unwrap());
+    }
+
     #[test]
     fn test_part_a() {
         let test_data = read_test_data();
-        assert_eq!(Solution::Integer(95437), part_a(&test_data));
+        assert_eq!(Solution::Integer(95437), part_a(&test_data).unwrap());
     }
 
     #[test]
     fn test_part_b() {
         let test_data = read_test_data();
-        assert_eq!(Solution::Integer(24_933_642), part_b(&test_data));
+        assert_eq!(Solution::Integer(24_933_642), part_b(&test_data).unwrap());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_tree_collapse_matches_naive(tree in tree_strategy()) {
+            let mut session = "$ cd /\n".to_string();
+            write_session(&tree, &mut session);
+
+            let tree = execute_commands(parse_commands(&session)).unwrap();
+            let mut sizes = tree_collapse(&tree).1.collect::<Vec<_>>();
+            let mut expected = directory_sizes_naive(parse_commands(&session)).unwrap();
+            sizes.sort_unstable();
+            expected.sort_unstable();
+
+            prop_assert_eq!(expected, sizes);
+        }
     }
 }
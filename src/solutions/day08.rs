@@ -1,172 +1,136 @@
-use std::collections::HashSet;
+use hashbrown::HashSet;
 
+use crate::error::Error;
+use crate::grid::{Grid, Pos};
+use crate::prelude::*;
+use crate::render::{Frame, Scene};
 use crate::solutions::Solution;
 
 #[allow(clippy::cast_possible_truncation)]
-fn parse_square(file: &str) -> Vec<i8> {
-    file.chars()
-        .filter_map(|c| c.to_digit(10).map(|d| d as i8))
-        .collect()
+fn parse_forest(file: &str) -> Result<Grid<i8>, Error> {
+    Grid::parse(file, |c| {
+        c.to_digit(10)
+            .map(|d| d as i8)
+            .ok_or_else(|| Error::InputError(format!("Unknown tree {c}")))
+    })
 }
 
-fn count_left(forest: &[i8], width: usize, seen: &mut HashSet<(usize, usize)>) {
-    for y in 0..width {
-        let mut talest: i8 = -1;
-        for x in 0..width {
-            let tree = forest[x + y * width];
+/// Marks the trees along `line` that are taller than every tree before them.
+fn count_line<'a>(line: impl Iterator<Item = (Pos, &'a i8)>, seen: &mut HashSet<Pos>) {
+    let mut talest: i8 = -1;
 
-            if tree > talest {
-                talest = tree;
-                seen.insert((x, y));
-            }
+    for (pos, &tree) in line {
+        if tree > talest {
+            talest = tree;
+            seen.insert(pos);
         }
     }
 }
 
-fn count_right(forest: &[i8], width: usize, seen: &mut HashSet<(usize, usize)>) {
-    for y in 0..width {
-        let mut talest: i8 = -1;
-        for x in (0..width).rev() {
-            let tree = forest[x + y * width];
-
-            if tree > talest {
-                talest = tree;
-                seen.insert((x, y));
-            }
-        }
+fn count_left(forest: &Grid<i8>, seen: &mut HashSet<Pos>) {
+    for y in 0..forest.height() {
+        count_line(forest.row(y).enumerate().map(|(x, t)| ((x, y), t)), seen);
     }
 }
 
-fn count_top(forest: &[i8], width: usize, seen: &mut HashSet<(usize, usize)>) {
-    for x in 0..width {
-        let mut talest: i8 = -1;
-        for y in 0..width {
-            let tree = forest[x + y * width];
-
-            if tree > talest {
-                talest = tree;
-                seen.insert((x, y));
-            }
-        }
+fn count_right(forest: &Grid<i8>, seen: &mut HashSet<Pos>) {
+    for y in 0..forest.height() {
+        count_line(
+            forest.row(y).enumerate().rev().map(|(x, t)| ((x, y), t)),
+            seen,
+        );
     }
 }
 
-fn count_bottom(forest: &[i8], width: usize, seen: &mut HashSet<(usize, usize)>) {
-    for x in 0..width {
-        let mut talest: i8 = -1;
-        for y in (0..width).rev() {
-            let tree = forest[x + y * width];
+fn count_top(forest: &Grid<i8>, seen: &mut HashSet<Pos>) {
+    for x in 0..forest.width() {
+        count_line(forest.column(x).enumerate().map(|(y, t)| ((x, y), t)), seen);
+    }
+}
 
-            if tree > talest {
-                talest = tree;
-                seen.insert((x, y));
-            }
-        }
+fn count_bottom(forest: &Grid<i8>, seen: &mut HashSet<Pos>) {
+    for x in 0..forest.width() {
+        count_line(
+            forest.column(x).enumerate().rev().map(|(y, t)| ((x, y), t)),
+            seen,
+        );
     }
 }
 
-#[allow(clippy::cast_sign_loss)]
-#[allow(clippy::cast_possible_truncation)]
-#[allow(clippy::cast_precision_loss)]
-pub fn part_a(file: &str) -> Solution {
-    let forest = parse_square(file);
-    let width = f64::sqrt(forest.len() as f64) as usize;
+#[allow(clippy::cast_possible_wrap)]
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    let forest = parse_forest(file)?;
     let mut seen = HashSet::new();
-    count_left(&forest, width, &mut seen);
-    count_right(&forest, width, &mut seen);
-    count_top(&forest, width, &mut seen);
-    count_bottom(&forest, width, &mut seen);
+    count_left(&forest, &mut seen);
+    count_right(&forest, &mut seen);
+    count_top(&forest, &mut seen);
+    count_bottom(&forest, &mut seen);
 
-    Solution::Integer(seen.len() as i64)
+    Ok(Solution::Integer(seen.len() as i64))
 }
 
-fn scenic_right(forest: &[i8], width: usize, start: &(usize, usize)) -> usize {
-    let start_height = forest[start.0 + start.1 * width];
-    let y = start.1;
+/// How many trees can be seen from `start` looking in the direction of `step`.
+fn scenic(forest: &Grid<i8>, start: Pos, step: (isize, isize)) -> usize {
+    let start_height = forest[start];
+    let mut distance = 0;
 
-    for x in (start.0 + 1)..width {
-        let tree = forest[x + y * width];
+    for pos in forest.ray(start, step) {
+        distance += 1;
 
-        if tree >= start_height {
-            return x - start.0;
+        if forest[pos] >= start_height {
+            break;
         }
     }
-    width - start.0 - 1
-}
-
-fn scenic_left(forest: &[i8], width: usize, start: &(usize, usize)) -> usize {
-    let start_height = forest[start.0 + start.1 * width];
-    let y = start.1;
 
-    for x in (0..start.0).rev() {
-        let tree = forest[x + y * width];
-
-        if tree >= start_height {
-            return start.0 - x;
-        }
-    }
-    start.0
+    distance
 }
 
-fn scenic_down(forest: &[i8], width: usize, start: &(usize, usize)) -> usize {
-    let start_height = forest[start.0 + start.1 * width];
-    let x = start.0;
-
-    for y in (start.1 + 1)..width {
-        let tree = forest[x + y * width];
-
-        if tree >= start_height {
-            return y - start.1;
-        }
-    }
-    width - start.1 - 1
+fn calc_scene(forest: &Grid<i8>, tree: Pos) -> usize {
+    scenic(forest, tree, (-1, 0))
+        * scenic(forest, tree, (1, 0))
+        * scenic(forest, tree, (0, 1))
+        * scenic(forest, tree, (0, -1))
 }
 
-fn scenic_up(forest: &[i8], width: usize, start: &(usize, usize)) -> usize {
-    let start_height = forest[start.0 + start.1 * width];
-    let x = start.0;
+#[allow(clippy::cast_possible_wrap)]
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    let forest = parse_forest(file)?;
 
-    for y in (0..start.1).rev() {
-        let tree = forest[x + y * width];
+    let max = forest
+        .positions()
+        .map(|tree| calc_scene(&forest, tree))
+        .max()
+        .unwrap_or(0);
 
-        if tree >= start_height {
-            return start.1 - y;
-        }
-    }
-    start.1
+    Ok(Solution::Integer(max as i64))
 }
 
-fn calc_scene(forest: &[i8], width: usize, tree: &(usize, usize)) -> usize {
-    if tree.0 == 0 || tree.1 == 0 || tree.0 == width - 1 || tree.1 == width - 1 {
-        return 0;
+/// Trees shaded by height, with the ones in `seen` marked.
+#[allow(clippy::cast_sign_loss)]
+fn draw_forest(forest: &Grid<i8>, seen: &HashSet<Pos>) -> Frame {
+    let mut frame = Grid::filled(forest.width(), forest.height(), 0);
+    for (pos, &tree) in forest.iter() {
+        frame[pos] = if seen.contains(&pos) { 10 } else { tree as u8 };
     }
-
-    scenic_left(forest, width, tree)
-        * scenic_right(forest, width, tree)
-        * scenic_down(forest, width, tree)
-        * scenic_up(forest, width, tree)
+    frame
 }
 
-#[allow(clippy::cast_sign_loss)]
-#[allow(clippy::cast_possible_truncation)]
-#[allow(clippy::cast_precision_loss)]
-pub fn part_b(file: &str) -> Solution {
-    let forest = parse_square(file);
-    let width = f64::sqrt(forest.len() as f64) as usize;
-
-    let mut max = 0;
-
-    for x in 1..(width - 1) {
-        for y in 1..(width - 1) {
-            let scene = calc_scene(&forest, width, &(x, y));
+/// The forest, then the trees seen from outside as each side is looked at in turn.
+pub fn scene(file: &str, _frames: usize) -> Result<Scene, Error> {
+    let forest = parse_forest(file)?;
+    let mut seen = HashSet::new();
+    let mut frames = vec![draw_forest(&forest, &seen)];
 
-            if scene > max {
-                max = scene;
-            }
-        }
+    for count in [count_left, count_right, count_top, count_bottom] {
+        count(&forest, &mut seen);
+        frames.push(draw_forest(&forest, &seen));
     }
 
-    Solution::Integer(max as i64)
+    Ok(Scene {
+        frames,
+        levels: 11,
+        accent: true,
+    })
 }
 
 #[cfg(test)]
@@ -183,65 +147,78 @@ mod tests {
 
     #[test]
     fn test_parse() {
-        assert_eq!(25, parse_square(&read_test_data()).len());
+        let forest = parse_forest(&read_test_data()).unwrap();
+
+        assert_eq!((5, 5), (forest.width(), forest.height()));
+        assert!(parse_forest("123\n4x6").is_err());
     }
 
     #[test]
     fn test_left() {
-        let forest = parse_square(&read_test_data());
+        let forest = parse_forest(&read_test_data()).unwrap();
         let mut seen = HashSet::new();
-        count_left(&forest, 5, &mut seen);
+        count_left(&forest, &mut seen);
         assert_eq!(11, seen.len());
     }
 
     #[test]
     fn test_right() {
-        let forest = parse_square(&read_test_data());
+        let forest = parse_forest(&read_test_data()).unwrap();
         let mut seen = HashSet::new();
-        count_right(&forest, 5, &mut seen);
+        count_right(&forest, &mut seen);
         assert_eq!(11, seen.len());
     }
 
     #[test]
     fn test_top() {
-        let forest = parse_square(&read_test_data());
+        let forest = parse_forest(&read_test_data()).unwrap();
         let mut seen = HashSet::new();
-        count_top(&forest, 5, &mut seen);
+        count_top(&forest, &mut seen);
         assert_eq!(10, seen.len());
     }
 
     #[test]
     fn test_bottom() {
-        let forest = parse_square(&read_test_data());
+        let forest = parse_forest(&read_test_data()).unwrap();
         let mut seen = HashSet::new();
-        count_bottom(&forest, 5, &mut seen);
+        count_bottom(&forest, &mut seen);
         assert_eq!(8, seen.len());
     }
 
     #[test]
     fn test_part_a() {
-        assert_eq!(Solution::Integer(21), part_a(&read_test_data()));
+        assert_eq!(Solution::Integer(21), part_a(&read_test_data()).unwrap());
     }
 
     #[test]
     fn test_scenic() {
-        let forest = parse_square(&read_test_data());
+        let forest = parse_forest(&read_test_data()).unwrap();
 
-        assert_eq!(1, scenic_up(&forest, 5, &(2, 1)));
-        assert_eq!(1, scenic_left(&forest, 5, &(2, 1)));
-        assert_eq!(2, scenic_right(&forest, 5, &(2, 1)));
-        assert_eq!(2, scenic_down(&forest, 5, &(2, 1)));
-        assert_eq!(4, calc_scene(&forest, 5, &(2, 1)));
+        assert_eq!(1, scenic(&forest, (2, 1), (0, -1)));
+        assert_eq!(1, scenic(&forest, (2, 1), (-1, 0)));
+        assert_eq!(2, scenic(&forest, (2, 1), (1, 0)));
+        assert_eq!(2, scenic(&forest, (2, 1), (0, 1)));
+        assert_eq!(4, calc_scene(&forest, (2, 1)));
 
-        assert_eq!(2, scenic_up(&forest, 5, &(2, 3)));
-        assert_eq!(2, scenic_left(&forest, 5, &(2, 3)));
-        assert_eq!(2, scenic_right(&forest, 5, &(2, 3)));
-        assert_eq!(1, scenic_down(&forest, 5, &(2, 3)));
-        assert_eq!(8, calc_scene(&forest, 5, &(2, 3)));
+        assert_eq!(2, scenic(&forest, (2, 3), (0, -1)));
+        assert_eq!(2, scenic(&forest, (2, 3), (-1, 0)));
+        assert_eq!(2, scenic(&forest, (2, 3), (1, 0)));
+        assert_eq!(1, scenic(&forest, (2, 3), (0, 1)));
+        assert_eq!(8, calc_scene(&forest, (2, 3)));
     }
 
     #[test]
     fn test_part_b() {
-        assert_eq!(Solution::Integer(8), part_b(&read_test_data()));
+        assert_eq!(Solution::Integer(8), part_b(&read_test_data()).unwrap());
+    }
+
+    #[test]
+    fn test_scene() {
+        let scene = scene(&read_test_data(), 10).unwrap();
+        let hidden = |frame: &Frame| frame.iter().filter(|(_, &level)| level < 10).count();
+
+        assert_eq!(5, scene.frames.len());
+        assert_eq!(25, hidden(&scene.frames[0]));
+        assert_eq!(4, hidden(&scene.frames[4]));
     }
 }
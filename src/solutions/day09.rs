@@ -1,64 +1,73 @@
+use crate::error::Error;
+use crate::geometry::{BoundingBox, Direction, Point};
+use crate::grid::Grid;
+use crate::prelude::*;
+use crate::render::{Frame, Recorder, Scene};
 use crate::solutions::Solution;
-use std::collections::HashSet;
+use hashbrown::HashSet;
 
-type Move = (char, i32);
-type Pos = (i32, i32);
+type Move = (Direction, u32);
 
-fn parse_lines(file: &str) -> impl Iterator<Item = Move> + '_ {
-    file.lines().map(|line| {
-        let dir = line.chars().next().unwrap();
-        let distance = line[2..].parse().unwrap();
+/// Moves adding up to more steps than this are rejected, as the rope is moved one step at a time.
+const MAX_STEPS: u64 = 1 << 24;
 
-        (dir, distance)
+fn parse_lines(file: &str) -> impl Iterator<Item = Result<Move, Error>> + '_ {
+    let mut steps = 0;
+    file.lines().map(move |line| {
+        let (dir, distance) = line
+            .split_once(' ')
+            .ok_or_else(|| Error::input("Expected a direction and a distance"))?;
+
+        let mut chars = dir.chars();
+        let (Some(d), None) = (chars.next(), chars.next()) else {
+            return Err(Error::input("Unknown move"));
+        };
+        let distance: u32 = distance.parse()?;
+
+        steps += u64::from(distance);
+        if steps > MAX_STEPS {
+            return Err(Error::input("Too many steps"));
+        }
+        Ok((Direction::try_from(d)?, distance))
     })
 }
 
-fn move_rope<I>(moves: I) -> usize
+fn move_rope<I>(moves: I) -> Result<usize, Error>
 where
-    I: Iterator<Item = Move>,
+    I: Iterator<Item = Result<Move, Error>>,
 {
-    let mut head = (0, 0);
-    let mut tail = (0, 0);
+    let mut head = Point::ORIGIN;
+    let mut tail = Point::ORIGIN;
 
-    let mut positions: HashSet<Pos> = HashSet::new();
+    let mut positions: HashSet<Point> = HashSet::new();
     positions.insert(tail);
 
-    for (d, l) in moves {
+    for m in moves {
+        let (d, l) = m?;
         for _ in 0..l {
-            match d {
-                'L' => head.0 -= 1,
-                'R' => head.0 += 1,
-                'U' => head.1 -= 1,
-                'D' => head.1 += 1,
-                _ => panic!("Unknown move"),
-            }
+            head += d.vec();
 
             tail = follow_move(tail, head);
             positions.insert(tail);
         }
     }
 
-    positions.len()
+    Ok(positions.len())
 }
 
-fn move_long_rope<I, const N: usize>(moves: I) -> usize
+fn move_long_rope<I, const N: usize>(moves: I) -> Result<usize, Error>
 where
-    I: Iterator<Item = Move>,
+    I: Iterator<Item = Result<Move, Error>>,
 {
-    let mut snake: [Pos; N] = [(0, 0); N];
+    let mut snake: [Point; N] = [Point::ORIGIN; N];
 
-    let mut positions: HashSet<Pos> = HashSet::new();
-    positions.insert((0, 0));
+    let mut positions: HashSet<Point> = HashSet::new();
+    positions.insert(Point::ORIGIN);
 
-    for (d, l) in moves {
+    for m in moves {
+        let (d, l) = m?;
         for _ in 0..l {
-            match d {
-                'L' => snake[0].0 -= 1,
-                'R' => snake[0].0 += 1,
-                'U' => snake[0].1 -= 1,
-                'D' => snake[0].1 += 1,
-                _ => panic!("Unknown move"),
-            }
+            snake[0] += d.vec();
 
             for i in 1..N {
                 snake[i] = follow_move(snake[i], snake[i - 1]);
@@ -68,26 +77,90 @@ where
         }
     }
 
-    positions.len()
+    Ok(positions.len())
 }
 
-const fn follow_move(current: Pos, ahead: Pos) -> Pos {
-    let x_diff = current.0 - ahead.0;
-    let y_diff = current.1 - ahead.1;
+fn follow_move(current: Point, ahead: Point) -> Point {
+    let diff = ahead - current;
 
-    if x_diff.abs() <= 1 && y_diff.abs() <= 1 {
+    if diff.chebyshev() <= 1 {
         return current;
     }
 
-    (current.0 - x_diff.signum(), current.1 - y_diff.signum())
+    current + diff.signum()
+}
+
+/// Draws the long rope within `bounds`: the squares its tail has been to, the knots, and its
+/// head on top.
+#[allow(clippy::cast_possible_truncation)]
+fn draw_rope(snake: &[Point], visited: &HashSet<Point>, bounds: &BoundingBox) -> Frame {
+    let to_pos = |p: Point| {
+        (Point::ORIGIN + (p - bounds.min))
+            .to_pos()
+            .expect("Knots stay within the bounds")
+    };
+    let mut frame = Grid::filled(bounds.width() as usize, bounds.height() as usize, 0);
+
+    for &p in visited {
+        frame[to_pos(p)] = 1;
+    }
+    for &p in snake.iter().rev() {
+        frame[to_pos(p)] = 2;
+    }
+    frame[to_pos(snake[0])] = 3;
+
+    frame
+}
+
+/// The ten knot rope after every step of its head.
+pub fn scene(file: &str, frames: usize) -> Result<Scene, Error> {
+    let steps = parse_lines(file)
+        .map(|m| m.map(|(d, l)| (0..l).map(move |_| d)))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+    // Knots never leave the area the head has been through.
+    let mut head = Point::ORIGIN;
+    let bounds = BoundingBox::of(core::iter::once(head).chain(steps.iter().map(|d| {
+        head += d.vec();
+        head
+    })))
+    .ok_or_else(|| Error::input("No moves"))?;
+
+    let mut snake = [Point::ORIGIN; 10];
+    let mut visited: HashSet<_> = HashSet::from([Point::ORIGIN]);
+    let mut recorder = Recorder::new(steps.len(), frames);
+    recorder.frames.push(draw_rope(&snake, &visited, &bounds));
+
+    for d in steps {
+        snake[0] += d.vec();
+        for i in 1..snake.len() {
+            snake[i] = follow_move(snake[i], snake[i - 1]);
+        }
+        visited.insert(snake[9]);
+
+        recorder.step(|| draw_rope(&snake, &visited, &bounds));
+    }
+
+    Ok(Scene {
+        frames: recorder.frames,
+        levels: 4,
+        accent: true,
+    })
 }
 
-pub fn part_a(file: &str) -> Solution {
-    Solution::Integer(move_rope(parse_lines(file)) as i64)
+#[allow(clippy::cast_possible_wrap)]
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(move_rope(parse_lines(file))? as i64))
 }
 
-pub fn part_b(file: &str) -> Solution {
-    Solution::Integer(move_long_rope::<_, 10>(parse_lines(file)) as i64)
+#[allow(clippy::cast_possible_wrap)]
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(
+        move_long_rope::<_, 10>(parse_lines(file))? as i64,
+    ))
 }
 
 #[cfg(test)]
@@ -97,23 +170,55 @@ mod tests {
     #[test]
     fn test_parse() {
         assert_eq!(
-            vec![('D', 10), ('U', 5), ('L', 3)],
-            parse_lines("D 10\nU 5\nL 3").collect::<Vec<_>>()
+            vec![
+                (Direction::Down, 10),
+                (Direction::Up, 5),
+                (Direction::Left, 3)
+            ],
+            parse_lines("D 10\nU 5\nL 3")
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
         );
+        assert!(parse_lines("X 1").next().unwrap().is_err());
+        assert!(parse_lines("U").next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_long_moves() {
+        assert_eq!(1000, move_rope(parse_lines("R 1000\nL 1000")).unwrap());
+        assert!(move_rope(parse_lines("R 4294967295")).is_err());
+        assert!(move_rope(parse_lines("R 10000000\nL 10000000")).is_err());
     }
 
     #[test]
     fn test_part_a() {
         let moves = parse_lines("R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2");
-        assert_eq!(13, move_rope(moves));
+        assert_eq!(13, move_rope(moves).unwrap());
     }
 
     #[test]
     fn test_part_b() {
         let moves_short = parse_lines("R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2");
-        assert_eq!(1, move_long_rope::<_, 10>(moves_short));
+        assert_eq!(1, move_long_rope::<_, 10>(moves_short).unwrap());
 
         let moves_long = parse_lines("R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20");
-        assert_eq!(36, move_long_rope::<_, 10>(moves_long));
+        assert_eq!(36, move_long_rope::<_, 10>(moves_long).unwrap());
+    }
+
+    #[test]
+    fn test_scene() {
+        let scene = scene("R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20", 1000).unwrap();
+        let count = |frame: &Frame, level| frame.iter().filter(|(_, &l)| l == level).count();
+        let (first, last) = (&scene.frames[0], &scene.frames[96]);
+
+        assert_eq!(97, scene.frames.len());
+        assert_eq!((26, 21), (last.width(), last.height()));
+        assert_eq!(
+            (1, 0, 0),
+            (count(first, 3), count(first, 2), count(first, 1))
+        );
+        assert_eq!(1, count(last, 3));
+        // The 36 squares the tail has been to, some of them under knots.
+        assert!(count(last, 1) + count(last, 2) + count(last, 3) >= 36);
     }
 }
@@ -1,3 +1,7 @@
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::prelude::*;
+use crate::render::{Recorder, Scene};
 use crate::solutions::Solution;
 
 #[derive(PartialEq, Eq, Debug)]
@@ -6,12 +10,16 @@ enum Instruction {
     AddX(i64),
 }
 
-fn parse_instruction(file: &str) -> impl Iterator<Item = Instruction> + '_ {
+/// `addx` arguments are read as `i32` so the register can't overflow.
+fn parse_instruction(file: &str) -> impl Iterator<Item = Result<Instruction, Error>> + '_ {
     file.lines().map(|l| {
         if l.starts_with('n') {
-            Instruction::Noop
+            Ok(Instruction::Noop)
         } else {
-            Instruction::AddX(l.split_once(' ').unwrap().1.parse().unwrap())
+            let (_, value) = l
+                .split_once(' ')
+                .ok_or_else(|| Error::input("addx without a value"))?;
+            Ok(Instruction::AddX(i64::from(value.parse::<i32>()?)))
         }
     })
 }
@@ -32,21 +40,27 @@ where
         .enumerate()
 }
 
-pub fn part_a(file: &str) -> Solution {
-    let mut instructions = parse_instruction(file);
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    let mut instructions = parse_instruction(file)
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter();
     let mut states = to_state(&mut instructions);
 
     let mut sum = 0;
     for i in [18, 39, 39, 39, 39, 39] {
-        let (a, b) = states.nth(i).unwrap();
+        let (a, b) = states
+            .nth(i)
+            .ok_or_else(|| Error::input("Program ends before cycle 220"))?;
         sum += (a as i64 + 2) * b;
     }
 
-    Solution::Integer(sum)
+    Ok(Solution::Integer(sum))
 }
 
-pub fn part_b(file: &str) -> Solution {
-    let mut instructions = parse_instruction(file);
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    let mut instructions = parse_instruction(file)
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter();
     let states = to_state(&mut instructions);
 
     let mut output = "\n".to_string();
@@ -66,7 +80,41 @@ pub fn part_b(file: &str) -> Solution {
 
         prev = s.1;
     }
-    Solution::String(output)
+    Ok(Solution::String(output))
+}
+
+/// The CRT being drawn a pixel per cycle, with its beam marked.
+#[allow(clippy::cast_possible_wrap)]
+pub fn scene(file: &str, frames: usize) -> Result<Scene, Error> {
+    let mut instructions = parse_instruction(file)
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter();
+    let states = to_state(&mut instructions).take(240).collect::<Vec<_>>();
+
+    let mut screen = Grid::filled(40, 6, 0);
+    let mut recorder = Recorder::new(states.len(), frames);
+    let mut prev: i64 = 1;
+
+    for (cycle, x) in states {
+        let pixel = (cycle % 40, cycle / 40);
+        screen[pixel] = u8::from((pixel.0 as i64 - prev).abs() <= 1);
+
+        recorder.step(|| {
+            let mut frame = screen.clone();
+            frame[pixel] = 2;
+            frame
+        });
+        prev = x;
+    }
+
+    let mut frames = recorder.frames;
+    frames.push(screen);
+
+    Ok(Scene {
+        frames,
+        levels: 3,
+        accent: true,
+    })
 }
 
 #[cfg(test)]
@@ -89,7 +137,9 @@ mod tests {
                 Instruction::AddX(3),
                 Instruction::AddX(-5)
             ],
-            parse_instruction(&read_test_data(1)).collect::<Vec<_>>()
+            parse_instruction(&read_test_data(1))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
         );
     }
 
@@ -97,13 +147,18 @@ mod tests {
     fn test_acc() {
         assert_eq!(
             vec![(0, 1), (1, 1), (2, 4), (3, 4), (4, -1)],
-            to_state(&mut parse_instruction(&read_test_data(1))).collect::<Vec<_>>()
+            to_state(&mut parse_instruction(&read_test_data(1)).map(Result::unwrap))
+                .collect::<Vec<_>>()
         );
     }
 
     #[test]
     fn test_part_a() {
-        assert_eq!(Solution::Integer(13140), part_a(&read_test_data(2)));
+        assert_eq!(
+            Solution::Integer(13140),
+            part_a(&read_test_data(2)).unwrap()
+        );
+        assert!(part_a(&read_test_data(1)).is_err());
     }
 
     #[test]
@@ -111,7 +166,23 @@ mod tests {
         let expected = "\n##..##..##..##..##..##..##..##..##..##..\n###...###...###...###...###...###...###.\n####....####....####....####....####....\n#####.....#####.....#####.....#####.....\n######......######......######......####\n#######.......#######.......#######.....\n";
         assert_eq!(
             Solution::String(expected.to_string()),
-            part_b(&read_test_data(2))
+            part_b(&read_test_data(2)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_scene() {
+        let scene = scene(&read_test_data(2), 24).unwrap();
+        let image = part_b(&read_test_data(2)).unwrap();
+
+        assert_eq!(25, scene.frames.len());
+        assert_eq!(
+            Solution::String(
+                format!("\n{}", scene.frames[24])
+                    .replace('0', ".")
+                    .replace('1', "#")
+            ),
+            image
         );
     }
 }
@@ -1,6 +1,12 @@
+use crate::cycle;
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::parse::{self, Captures};
+use crate::prelude::*;
+use crate::render::{Frame, Recorder, Scene};
 use crate::solutions::Solution;
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 enum MonkeyOperation {
     Add(i64),
     AddOld,
@@ -8,7 +14,7 @@ enum MonkeyOperation {
     MultiplyOld,
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 struct Monkey {
     items: Vec<i64>,
     operation: MonkeyOperation,
@@ -17,64 +23,85 @@ struct Monkey {
     false_monkey: usize,
 }
 
-fn parse_monkeys(file: &str) -> impl Iterator<Item = Monkey> + '_ {
-    file.split("\n\n").map(parse_monkey)
+fn parse_monkeys(file: &str) -> Result<Vec<Monkey>, Error> {
+    let monkeys = parse::blocks(file)
+        .map(parse_monkey)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if monkeys
+        .iter()
+        .any(|m| m.true_monkey >= monkeys.len() || m.false_monkey >= monkeys.len())
+    {
+        return Err(Error::input("Throw to an unknown monkey"));
+    }
+    if monkeys
+        .iter()
+        .enumerate()
+        .any(|(i, m)| m.true_monkey == i || m.false_monkey == i)
+    {
+        return Err(Error::input("Monkeys can't throw to themselves"));
+    }
+
+    Ok(monkeys)
 }
 
-fn parse_monkey(spec: &str) -> Monkey {
+fn field<'a>(line: Option<&'a str>, pattern: &str) -> Result<Captures<'a>, Error> {
+    line.ok_or_else(|| Error::input("Malformed monkey"))
+        .and_then(|l| parse::capture(pattern, l))
+}
+
+fn parse_monkey(spec: &str) -> Result<Monkey, Error> {
     let mut lines = spec.lines();
     lines.next();
 
-    let items = lines
-        .next()
-        .unwrap()
-        .split_at(18)
-        .1
-        .split(", ")
-        .map(str::parse)
-        .collect::<Result<Vec<_>, _>>()
-        .unwrap();
+    let items = parse::integers(
+        field(lines.next(), "  Starting items: {}")?
+            .text(0)
+            .unwrap_or_default(),
+    )?;
 
-    let operation = parse_op(lines.next().unwrap().split_at(19).1);
+    let (op, value): (char, String) =
+        field(lines.next(), "  Operation: new = old {} {}")?.parse()?;
+    let operation = parse_op(op, &value)?;
 
-    let test = lines.next().unwrap().split_at(21).1.parse().unwrap();
+    let test = field(lines.next(), "  Test: divisible by {}")?.get(0)?;
+    if test <= 0 {
+        return Err(Error::input("Monkeys must test a positive divisor"));
+    }
 
-    let true_monkey = lines.next().unwrap().split_at(29).1.parse().unwrap();
-    let false_monkey = lines.next().unwrap().split_at(30).1.parse().unwrap();
+    let true_monkey = field(lines.next(), "    If true: throw to monkey {}")?.get(0)?;
+    let false_monkey = field(lines.next(), "    If false: throw to monkey {}")?.get(0)?;
 
-    Monkey {
+    Ok(Monkey {
         items,
         operation,
         test,
         true_monkey,
         false_monkey,
-    }
+    })
 }
 
-fn parse_op(spec: &str) -> MonkeyOperation {
-    let mut parts = spec.split(' ');
-    let op = parts.nth(1).unwrap().chars().next().unwrap();
-    let value = parts.next().unwrap();
-
-    match (op, value) {
+fn parse_op(op: char, value: &str) -> Result<MonkeyOperation, Error> {
+    Ok(match (op, value) {
         ('*', "old") => MonkeyOperation::MultiplyOld,
         ('+', "old") => MonkeyOperation::AddOld,
-        ('*', v) => MonkeyOperation::Multiply(v.parse().unwrap()),
-        ('+', v) => MonkeyOperation::Add(v.parse().unwrap()),
-        _ => panic!("Unknown monkey operation"),
-    }
+        ('*', v) => MonkeyOperation::Multiply(v.parse()?),
+        ('+', v) => MonkeyOperation::Add(v.parse()?),
+        _ => return Err(Error::input("Unknown monkey operation")),
+    })
 }
 
-fn update_worry(worry: i64, op: &MonkeyOperation) -> i64 {
+fn update_worry(worry: i64, op: &MonkeyOperation) -> Result<i64, Error> {
     match op {
-        MonkeyOperation::AddOld => worry + worry,
-        MonkeyOperation::Add(v) => worry + v,
-        MonkeyOperation::MultiplyOld => worry * worry,
-        MonkeyOperation::Multiply(v) => worry * v,
+        MonkeyOperation::AddOld => worry.checked_add(worry),
+        MonkeyOperation::Add(v) => worry.checked_add(*v),
+        MonkeyOperation::MultiplyOld => worry.checked_mul(worry),
+        MonkeyOperation::Multiply(v) => worry.checked_mul(*v),
     }
+    .ok_or_else(|| Error::input("Worry level overflows"))
 }
 
-fn monkey_business<W>(monkeys: &mut [Monkey], rounds: i32, manage_worry: W) -> i64
+fn monkey_business<W>(monkeys: &mut [Monkey], rounds: i32, manage_worry: W) -> Result<i64, Error>
 where
     W: Fn(i64) -> i64,
 {
@@ -83,7 +110,7 @@ where
     for _ in 0..rounds {
         for m in 0..monkeys.len() {
             for worry in monkeys[m].items.clone() {
-                let new_worry = manage_worry(update_worry(worry, &monkeys[m].operation));
+                let new_worry = manage_worry(update_worry(worry, &monkeys[m].operation)?);
 
                 let next_monkey = if new_worry % monkeys[m].test == 0 {
                     monkeys[m].true_monkey
@@ -99,22 +126,195 @@ where
     }
 
     inspection.sort_by(|a, b| b.cmp(a));
-    inspection.iter().take(2).product()
+    Ok(inspection.iter().take(2).product())
+}
+
+/// Naive reference for the worry management in `part_b`: instead of reducing modulo the product of
+/// all tests, every item remembers its remainder for each monkey's test separately.
+fn monkey_business_naive(monkeys: &[Monkey], rounds: i32) -> Result<i64, Error> {
+    let tests = monkeys.iter().map(|m| m.test).collect::<Vec<_>>();
+    let mut items: Vec<Vec<Vec<i64>>> = monkeys
+        .iter()
+        .map(|m| {
+            m.items
+                .iter()
+                .map(|&w| tests.iter().map(|t| w.rem_euclid(*t)).collect())
+                .collect()
+        })
+        .collect();
+    let mut inspection: Vec<i64> = vec![0; monkeys.len()];
+
+    for _ in 0..rounds {
+        for m in 0..monkeys.len() {
+            for remainders in core::mem::take(&mut items[m]) {
+                let remainders = remainders
+                    .iter()
+                    .zip(&tests)
+                    .map(|(r, t)| Ok(update_worry(*r, &monkeys[m].operation)?.rem_euclid(*t)))
+                    .collect::<Result<Vec<_>, Error>>()?;
+
+                let next_monkey = if remainders[m] == 0 {
+                    monkeys[m].true_monkey
+                } else {
+                    monkeys[m].false_monkey
+                };
+
+                items[next_monkey].push(remainders);
+                inspection[m] += 1;
+            }
+        }
+    }
+
+    inspection.sort_by(|a, b| b.cmp(a));
+    Ok(inspection.iter().take(2).product())
+}
+
+/// One round for a single item, starting with `monkey`. Items never affect each other, so each one
+/// can be followed on its own; an item thrown to a later monkey is inspected again the same round.
+fn item_round(
+    monkeys: &[Monkey],
+    test_product: i64,
+    (mut monkey, mut worry, mut inspection): (usize, i64, Vec<i64>),
+) -> Result<(usize, i64, Vec<i64>), Error> {
+    loop {
+        let m = &monkeys[monkey];
+        inspection[monkey] += 1;
+        worry = update_worry(worry, &m.operation)? % test_product;
+
+        let next_monkey = if worry % m.test == 0 {
+            m.true_monkey
+        } else {
+            m.false_monkey
+        };
+
+        if next_monkey < monkey {
+            return Ok((next_monkey, worry, inspection));
+        }
+        monkey = next_monkey;
+    }
 }
 
-pub fn part_a(file: &str) -> Solution {
-    let mut monkeys = parse_monkeys(file).collect::<Vec<_>>();
-    Solution::Integer(monkey_business(&mut monkeys, 20, |w| w / 3))
+/// The inspections of every monkey after `rounds` rounds of part b. Worry levels only take
+/// `test_product` values, so each item soon goes round in a cycle that is fast forwarded.
+fn inspections_after(monkeys: &[Monkey], rounds: u64) -> Result<Vec<i64>, Error> {
+    let test_product = test_product(monkeys)?;
+    let mut inspection = vec![0; monkeys.len()];
+
+    for (m, monkey) in monkeys.iter().enumerate() {
+        for &worry in &monkey.items {
+            let item = (m, worry.rem_euclid(test_product), vec![0; monkeys.len()]);
+            let counts = cycle::fast_forward(
+                item,
+                rounds,
+                |item| item_round(monkeys, test_product, item),
+                |&(monkey, worry, _)| (monkey, worry),
+                |(_, _, inspection)| inspection.clone(),
+            )?;
+
+            for (total, count) in inspection.iter_mut().zip(counts) {
+                *total += count;
+            }
+        }
+    }
+
+    Ok(inspection)
 }
 
-pub fn part_b(file: &str) -> Solution {
-    let mut monkeys = parse_monkeys(file).collect::<Vec<_>>();
-    let test_product: i64 = monkeys.iter().map(|m| m.test).product::<i64>();
-    Solution::Integer(monkey_business(&mut monkeys, 10000, |w| w % test_product))
+fn test_product(monkeys: &[Monkey]) -> Result<i64, Error> {
+    monkeys
+        .iter()
+        .try_fold(1_i64, |acc, m| acc.checked_mul(m.test))
+        .ok_or_else(|| Error::input("Monkey tests are too large"))
+}
+
+/// The twenty rounds of part a an item at a time, telling `on_throw` which monkey just threw.
+fn throw_items<F>(monkeys: &mut [Monkey], mut on_throw: F) -> Result<(), Error>
+where
+    F: FnMut(&[Monkey], usize),
+{
+    for _ in 0..20 {
+        for m in 0..monkeys.len() {
+            while !monkeys[m].items.is_empty() {
+                let worry = monkeys[m].items.remove(0);
+                let new_worry = update_worry(worry, &monkeys[m].operation)? / 3;
+
+                let next_monkey = if new_worry % monkeys[m].test == 0 {
+                    monkeys[m].true_monkey
+                } else {
+                    monkeys[m].false_monkey
+                };
+
+                monkeys[next_monkey].items.push(new_worry);
+                on_throw(monkeys, m);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// A row per monkey: a mark for the one throwing, then the items it holds shaded by how worrying
+/// they are.
+#[allow(clippy::cast_possible_truncation)]
+fn draw_monkeys(monkeys: &[Monkey], throwing: usize, width: usize) -> Frame {
+    let mut frame = Grid::filled(width, monkeys.len(), 0);
+
+    for (y, monkey) in monkeys.iter().enumerate() {
+        if y == throwing {
+            frame[(0, y)] = 9;
+        }
+        for (x, worry) in monkey.items.iter().enumerate() {
+            frame[(x + 1, y)] = (worry.max(&1).ilog2().min(23) / 3) as u8 + 1;
+        }
+    }
+
+    frame
+}
+
+/// Items passing between the monkeys during part a, a throw at a time.
+pub fn scene(file: &str, frames: usize) -> Result<Scene, Error> {
+    let mut monkeys = parse_monkeys(file)?;
+    let width = monkeys.iter().map(|m| m.items.len()).sum::<usize>() + 1;
+
+    let mut throws = 0;
+    throw_items(&mut monkeys.clone(), |_, _| throws += 1)?;
+
+    let mut recorder = Recorder::new(throws, frames);
+    throw_items(&mut monkeys, |monkeys, m| {
+        recorder.step(|| draw_monkeys(monkeys, m, width));
+    })?;
+
+    Ok(Scene {
+        frames: recorder.frames,
+        levels: 10,
+        accent: true,
+    })
+}
+
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    let mut monkeys = parse_monkeys(file)?;
+    Ok(Solution::Integer(monkey_business(&mut monkeys, 20, |w| {
+        w / 3
+    })?))
+}
+
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    let monkeys = parse_monkeys(file)?;
+    let mut inspection = inspections_after(&monkeys, 10000)?;
+
+    inspection.sort_by(|a, b| b.cmp(a));
+    Ok(Solution::Integer(inspection.iter().take(2).product()))
+}
+
+pub fn reference_part_b(file: &str) -> Result<Solution, Error> {
+    let monkeys = parse_monkeys(file)?;
+    Ok(Solution::Integer(monkey_business_naive(&monkeys, 10000)?))
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     fn read_test_data() -> String {
@@ -127,7 +327,7 @@ mod tests {
 
     #[test]
     fn test_parse() {
-        let monkeys = parse_monkeys(&read_test_data()).collect::<Vec<_>>();
+        let monkeys = parse_monkeys(&read_test_data()).unwrap();
 
         assert_eq!(4, monkeys.len());
 
@@ -146,13 +346,110 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_invalid() {
+        let test_data = read_test_data();
+
+        assert!(parse_monkeys(&test_data.replace("monkey 3", "monkey 4")).is_err());
+        assert!(parse_monkeys(&test_data.replace("monkey 2", "monkey 0")).is_err());
+        assert!(parse_monkeys(&test_data.replace("by 23", "by 0")).is_err());
+        assert!(parse_monkeys(&test_data.replace("  Test:", "Test:")).is_err());
+    }
+
+    #[test]
+    fn test_scene() {
+        let scene = scene(&read_test_data(), 1000).unwrap();
+        let last = scene.frames.last().unwrap();
+        let items = |y| last.row(y).skip(1).filter(|&&level| level > 0).count();
+
+        // 101 + 95 + 7 + 105 inspections, and the ten items end with the first two monkeys.
+        assert_eq!(308, scene.frames.len());
+        assert_eq!((11, 4), (last.width(), last.height()));
+        assert_eq!(vec![5, 5, 0, 0], (0..4).map(items).collect::<Vec<_>>());
+        assert_eq!(Some(&9), last.get((0, 3)));
+    }
+
     #[test]
     fn test_part_a() {
-        assert_eq!(Solution::Integer(10605), part_a(&read_test_data()));
+        assert_eq!(Solution::Integer(10605), part_a(&read_test_data()).unwrap());
     }
 
     #[test]
     fn test_monkey_business_part2() {
-        assert_eq!(Solution::Integer(2_713_310_158), part_b(&read_test_data()));
+        assert_eq!(
+            Solution::Integer(2_713_310_158),
+            part_b(&read_test_data()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_inspections_fast_forward() {
+        let mut monkeys = parse_monkeys(&read_test_data()).unwrap();
+        assert_eq!(
+            vec![52166, 47830, 1938, 52013],
+            inspections_after(&monkeys, 10000).unwrap()
+        );
+
+        // Each of the ten items is inspected between once and four times a round.
+        let rounds = 5_000_000_000;
+        let far = inspections_after(&monkeys, rounds as u64).unwrap();
+        assert!((10 * rounds..=40 * rounds).contains(&far.iter().sum::<i64>()));
+
+        let test_product = test_product(&monkeys).unwrap();
+        let mut counted = inspections_after(&monkeys, 777).unwrap();
+        counted.sort_by(|a, b| b.cmp(a));
+        assert_eq!(
+            counted[0] * counted[1],
+            monkey_business(&mut monkeys, 777, |w| w % test_product).unwrap()
+        );
+    }
+
+    /// Monkey `index` of a troop of `troop` monkeys, which never throws to itself.
+    fn monkey_strategy(troop: usize, index: usize) -> impl Strategy<Value = Monkey> {
+        let target = move |t: usize| if t >= index { t + 1 } else { t };
+
+        let operation = prop_oneof![
+            (1..10_i64).prop_map(MonkeyOperation::Add),
+            Just(MonkeyOperation::AddOld),
+            (2..20_i64).prop_map(MonkeyOperation::Multiply),
+            Just(MonkeyOperation::MultiplyOld),
+        ];
+
+        (
+            prop::collection::vec(1..100_i64, 0..4),
+            operation,
+            prop::sample::select(vec![2, 3, 5, 7, 11, 13, 17, 19, 23]),
+            0..troop - 1,
+            0..troop - 1,
+        )
+            .prop_map(
+                move |(items, operation, test, true_monkey, false_monkey)| Monkey {
+                    items,
+                    operation,
+                    test,
+                    true_monkey: target(true_monkey),
+                    false_monkey: target(false_monkey),
+                },
+            )
+    }
+
+    proptest! {
+        #[test]
+        fn prop_worry_management_matches_naive(
+            monkeys in (2..6_usize)
+                .prop_flat_map(|n| (0..n).map(|i| monkey_strategy(n, i)).collect::<Vec<_>>())
+        ) {
+            let expected = monkey_business_naive(&monkeys, 500).unwrap();
+
+            let mut fast = inspections_after(&monkeys, 500).unwrap();
+            fast.sort_by(|a, b| b.cmp(a));
+            prop_assert_eq!(expected, fast[0] * fast[1]);
+
+            let mut monkeys = monkeys;
+            let test_product: i64 = monkeys.iter().map(|m| m.test).product();
+            let actual = monkey_business(&mut monkeys, 500, |w| w % test_product).unwrap();
+
+            prop_assert_eq!(expected, actual);
+        }
     }
 }
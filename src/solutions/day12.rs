@@ -1,14 +1,17 @@
-use pathfinding::prelude::astar;
-
+use alloc::collections::VecDeque;
+use hashbrown::HashSet;
+
+use crate::error::Error;
+use crate::geometry::{Direction, Point, Vec2};
+use crate::grid::{Grid, Pos};
+use crate::prelude::*;
+use crate::render::{Recorder, Scene};
+use crate::search::{self, Outcome};
 use crate::solutions::Solution;
 
-#[derive(Clone, Eq, PartialEq, Hash, Debug)]
-struct Pos(i32, i32);
-
-impl Pos {
-    const fn distance(&self, other: &Self) -> u32 {
-        (self.0.abs_diff(other.0) + self.1.abs_diff(other.1)) as u32
-    }
+#[allow(clippy::cast_possible_truncation)]
+fn distance(a: Pos, b: Pos) -> u32 {
+    Point::from(a).manhattan(Point::from(b)) as u32
 }
 
 #[derive(Eq, PartialEq, Debug)]
@@ -19,46 +22,32 @@ enum Square {
 }
 
 struct Mountain {
-    width: i32,
-    height: i32,
-    squares: Vec<Vec<Square>>,
+    squares: Grid<Square>,
 }
 
 impl Mountain {
     #[allow(clippy::cast_possible_truncation)]
     #[allow(clippy::cast_possible_wrap)]
-    fn parse(file: &str) -> Self {
-        let squares: Vec<Vec<Square>> = file
-            .lines()
-            .map(|l| {
-                l.chars()
-                    .map(|c| match c {
-                        'S' => Square::Start,
-                        'E' => Square::End,
-                        'a'..='z' => Square::Height((c as i32) - ('a' as i32)),
-                        _ => panic!("Unknown char {}", c),
-                    })
-                    .collect()
-            })
-            .collect();
-
-        let width = squares[0].len() as i32;
-        let height = squares.len() as i32;
-
-        Self {
-            width,
-            height,
-            squares,
+    fn parse(file: &str) -> Result<Self, Error> {
+        let squares = Grid::parse(file, |c| match c {
+            'S' => Ok(Square::Start),
+            'E' => Ok(Square::End),
+            'a'..='z' => Ok(Square::Height((c as i32) - ('a' as i32))),
+            _ => Err(Error::InputError(format!("Unknown char {c}"))),
+        })?;
+
+        if squares.width() == 0 {
+            return Err(Error::input("Mountain must be a non-empty rectangle"));
         }
+
+        Ok(Self { squares })
     }
 
-    #[allow(clippy::cast_sign_loss)]
-    fn height(&self, pos: &Pos) -> i32 {
-        match self.squares[pos.1 as usize].get(pos.0 as usize) {
-            Some(Square::End) => 25,
-            Some(Square::Start) => 0,
-            Some(Square::Height(h)) => *h,
-            _ => panic!("Invalid index for height"),
+    fn height(&self, pos: Pos) -> i32 {
+        match self.squares[pos] {
+            Square::End => 25,
+            Square::Start => 0,
+            Square::Height(h) => h,
         }
     }
 
@@ -70,63 +59,64 @@ impl Mountain {
         to > from || from.abs_diff(to) <= 1
     }
 
-    fn path(&self) -> u32 {
-        let start = self.find(&Square::Start);
-        let end = self.find(&Square::End);
-        astar(
-            &start,
-            |p| self.successors(p, Self::can_go),
-            |p| p.distance(&end),
-            |p| p == &end,
-        )
-        .expect("No path found")
-        .1
+    fn path(&self) -> Option<u32> {
+        self.climb().and_then(|outcome| outcome.cost())
     }
 
-    const fn valid_pos(&self, pos: &Pos) -> bool {
-        pos.0 >= 0 && pos.0 < self.width && pos.1 >= 0 && pos.1 < self.height
+    fn climb(&self) -> Option<Outcome<Pos, u32>> {
+        let start = self.find(&Square::Start)?;
+        let end = self.find(&Square::End)?;
+
+        Some(search::astar(
+            start,
+            |&p| self.successors(p, Self::can_go),
+            |&p| distance(p, end),
+            |&p| p == end,
+        ))
     }
 
-    fn distance_to_zero(&self, pos: &Pos) -> u32 {
+    fn distance_to_zero(&self, pos: Pos) -> u32 {
+        if self.height(pos) == 0 {
+            return 0;
+        }
+
         let mut min_dist = u32::MAX;
 
-        let mut dir = 0;
+        let mut dir = Direction::Right;
+        let mut turns = 0;
         let mut layer = 1;
         let mut step = 0;
-        let mut test_pos = Pos(pos.0 - 1, pos.1 - 1);
+        let mut test_point = Point::from(pos) + Vec2::new(-1, -1);
 
         loop {
             if layer >= 100 {
                 break;
             }
 
-            if self.valid_pos(&test_pos) && self.height(&test_pos) == 0 {
-                min_dist = min_dist.min(pos.distance(&test_pos));
-            }
-
-            match dir {
-                0 => test_pos.0 += 1,
-                1 => test_pos.1 += 1,
-                2 => test_pos.0 -= 1,
-                3 => test_pos.1 -= 1,
-                _ => panic!("Wah"),
+            if let Some(test_pos) = test_point.to_pos().filter(|&p| self.squares.contains(p)) {
+                if self.height(test_pos) == 0 {
+                    min_dist = min_dist.min(distance(pos, test_pos));
+                }
             }
 
+            test_point += dir.vec();
             step += 1;
 
             if step >= 2 * layer {
-                dir += 1;
+                dir = dir.turn_right();
+                turns += 1;
                 step = 0;
             }
 
-            if dir >= 4 {
-                if min_dist < u32::MAX {
+            if turns >= 4 {
+                // Squares in later layers are at least `layer + 1` away, so only stop once none of
+                // them can be closer than what was found. Otherwise the heuristic overestimates.
+                if min_dist <= layer + 1 {
                     return min_dist;
                 }
-                dir = 0;
+                turns = 0;
                 layer += 1;
-                test_pos.0 -= 1;
-                test_pos.1 -= 1;
+                test_point += Vec2::new(-1, -1);
                 step = 0;
             }
         }
@@ -134,79 +124,133 @@ impl Mountain {
         0
     }
 
-    fn path_down(&self) -> u32 {
-        let start = self.find(&Square::End);
-        astar(
-            &start,
-            |p| self.successors(p, Self::can_go_back),
-            |p| self.distance_to_zero(p),
-            |p| self.height(p) == 0,
-        )
-        .expect("No path found")
-        .1
+    fn path_down(&self) -> Option<u32> {
+        self.descend().and_then(|outcome| outcome.cost())
     }
 
-    fn successors<P>(&self, pos: &Pos, can_go: P) -> Vec<(Pos, u32)>
+    fn descend(&self) -> Option<Outcome<Pos, u32>> {
+        let start = self.find(&Square::End)?;
+
+        Some(search::astar(
+            start,
+            |&p| self.successors(p, Self::can_go_back),
+            |&p| self.distance_to_zero(p),
+            |&p| self.height(p) == 0,
+        ))
+    }
+
+    /// Naive reference for the A* searches: a plain breadth first search without a heuristic.
+    fn path_naive<P, G>(&self, start: Pos, can_go: P, is_goal: G) -> Option<u32>
     where
-        P: Fn(i32, i32) -> bool,
+        P: Fn(i32, i32) -> bool + Copy,
+        G: Fn(Pos) -> bool,
     {
-        let mut succ: Vec<(Pos, u32)> = vec![];
-        let height = self.height(pos);
+        let mut seen: HashSet<_> = HashSet::from([start]);
+        let mut queue = VecDeque::from([(start, 0)]);
 
-        if pos.0 > 0 {
-            let new_pos = Pos(pos.0 - 1, pos.1);
-            if can_go(height, self.height(&new_pos)) {
-                succ.push((new_pos, 1));
+        while let Some((pos, steps)) = queue.pop_front() {
+            if is_goal(pos) {
+                return Some(steps);
             }
-        }
-        if pos.1 > 0 {
-            let new_pos = Pos(pos.0, pos.1 - 1);
-            if can_go(height, self.height(&new_pos)) {
-                succ.push((new_pos, 1));
-            }
-        }
-        if pos.0 < self.width - 1 {
-            let new_pos = Pos(pos.0 + 1, pos.1);
-            if can_go(height, self.height(&new_pos)) {
-                succ.push((new_pos, 1));
-            }
-        }
-        if pos.1 < self.height - 1 {
-            let new_pos = Pos(pos.0, pos.1 + 1);
-            if can_go(height, self.height(&new_pos)) {
-                succ.push((new_pos, 1));
+
+            for (next, _) in self.successors(pos, can_go) {
+                if seen.insert(next) {
+                    queue.push_back((next, steps + 1));
+                }
             }
         }
 
-        succ
+        None
     }
 
-    #[allow(clippy::cast_sign_loss)]
-    fn find(&self, square: &Square) -> Pos {
-        for x in 0..self.width {
-            for y in 0..self.height {
-                if &self.squares[y as usize][x as usize] == square {
-                    return Pos(x, y);
-                }
-            }
-        }
+    fn successors<P>(&self, pos: Pos, can_go: P) -> Vec<(Pos, u32)>
+    where
+        P: Fn(i32, i32) -> bool,
+    {
+        let height = self.height(pos);
+
+        self.squares
+            .neighbours4(pos)
+            .filter(|&next| can_go(height, self.height(next)))
+            .map(|next| (next, 1))
+            .collect()
+    }
 
-        panic!("Oh no");
+    fn find(&self, square: &Square) -> Option<Pos> {
+        self.squares.find(|s| s == square)
     }
 }
 
-#[allow(clippy::cast_lossless)]
-pub fn part_a(file: &str) -> Solution {
-    Solution::Integer(Mountain::parse(file).path() as i64)
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    Mountain::parse(file)?
+        .path()
+        .map(|steps| Solution::Integer(i64::from(steps)))
+        .ok_or_else(|| Error::input("No path found"))
+}
+
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    Mountain::parse(file)?
+        .path_down()
+        .map(|steps| Solution::Integer(i64::from(steps)))
+        .ok_or_else(|| Error::input("No path found"))
 }
 
-#[allow(clippy::cast_lossless)]
-pub fn part_b(file: &str) -> Solution {
-    Solution::Integer(Mountain::parse(file).path_down() as i64)
+/// The heightmap with the route from the start to the top traced across it.
+#[allow(clippy::cast_sign_loss)]
+#[allow(clippy::cast_possible_truncation)]
+pub fn scene(file: &str, frames: usize) -> Result<Scene, Error> {
+    let mountain = Mountain::parse(file)?;
+    let path = mountain
+        .climb()
+        .and_then(|outcome| outcome.path)
+        .ok_or_else(|| Error::input("No path found"))?;
+
+    let mut map = Grid::filled(mountain.squares.width(), mountain.squares.height(), 0);
+    for pos in mountain.squares.positions() {
+        map[pos] = mountain.height(pos) as u8;
+    }
+
+    let mut recorder = Recorder::new(path.nodes.len(), frames);
+    for pos in path.nodes {
+        map[pos] = 26;
+        recorder.step(|| map.clone());
+    }
+
+    Ok(Scene {
+        frames: recorder.frames,
+        levels: 27,
+        accent: true,
+    })
+}
+
+pub fn reference_part_a(file: &str) -> Result<Solution, Error> {
+    let mountain = Mountain::parse(file)?;
+    let start = mountain.find(&Square::Start);
+    let end = mountain.find(&Square::End);
+
+    start
+        .zip(end)
+        .and_then(|(start, end)| mountain.path_naive(start, Mountain::can_go, |p| p == end))
+        .map(|steps| Solution::Integer(i64::from(steps)))
+        .ok_or_else(|| Error::input("No path found"))
+}
+
+pub fn reference_part_b(file: &str) -> Result<Solution, Error> {
+    let mountain = Mountain::parse(file)?;
+
+    mountain
+        .find(&Square::End)
+        .and_then(|end| {
+            mountain.path_naive(end, Mountain::can_go_back, |p| mountain.height(p) == 0)
+        })
+        .map(|steps| Solution::Integer(i64::from(steps)))
+        .ok_or_else(|| Error::input("No path found"))
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     fn read_test_data() -> String {
@@ -219,25 +263,130 @@ mod tests {
 
     #[test]
     fn test_parse() {
-        let mountain = Mountain::parse(&read_test_data());
+        let mountain = Mountain::parse(&read_test_data()).unwrap();
+
+        assert_eq!(40, mountain.squares.width() * mountain.squares.height());
 
-        assert_eq!(40, mountain.width * mountain.height);
+        assert_eq!(0, mountain.height((0, 0)));
+        assert_eq!(8, mountain.height((7, 4)));
 
-        assert_eq!(0, mountain.height(&Pos(0, 0)));
-        assert_eq!(8, mountain.height(&Pos(7, 4)));
+        assert!(Mountain::parse("SabcE\nabc").is_err());
+        assert!(Mountain::parse("Sab1E").is_err());
     }
 
     #[test]
     fn test_path() {
-        let mountain = Mountain::parse(&read_test_data());
+        let mountain = Mountain::parse(&read_test_data()).unwrap();
 
-        assert_eq!(31, mountain.path());
+        assert_eq!(Some(31), mountain.path());
+    }
+
+    #[test]
+    fn test_scene() {
+        let scene = scene(&read_test_data(), 8).unwrap();
+        let last = scene.frames.last().unwrap();
+
+        assert_eq!(8, scene.frames.len());
+        assert_eq!(32, last.iter().filter(|(_, &level)| level == 26).count());
+        assert_eq!(Some(&26), last.get((5, 2)));
     }
 
     #[test]
     fn test_path_down() {
-        let mountain = Mountain::parse(&read_test_data());
+        let mountain = Mountain::parse(&read_test_data()).unwrap();
 
-        assert_eq!(29, mountain.path_down());
+        assert_eq!(Some(29), mountain.path_down());
+    }
+
+    #[test]
+    fn test_heuristics_save_work() {
+        let mountain = Mountain::parse(&read_test_data()).unwrap();
+        let start = mountain.find(&Square::Start).unwrap();
+        let end = mountain.find(&Square::End).unwrap();
+
+        let climb = mountain.climb().unwrap();
+        let blind = search::dijkstra(
+            start,
+            |&p| mountain.successors(p, Mountain::can_go),
+            |&p| p == end,
+        );
+        assert_eq!(blind.cost(), climb.cost());
+        assert!(climb.stats.expanded <= blind.stats.expanded);
+
+        let path = climb.path.unwrap().nodes;
+        assert_eq!((start, end), (path[0], path[31]));
+
+        let descend = mountain.descend().unwrap();
+        let blind = search::dijkstra(
+            end,
+            |&p| mountain.successors(p, Mountain::can_go_back),
+            |&p| mountain.height(p) == 0,
+        );
+        assert_eq!(blind.cost(), descend.cost());
+        assert!(descend.stats.expanded <= blind.stats.expanded);
+    }
+
+    /// Heightmaps that climb from `a` on the left to `z` on the right, with random dips in them.
+    fn mountain_strategy() -> impl Strategy<Value = String> {
+        (26..34_usize, 1..5_usize).prop_flat_map(|(width, height)| {
+            (
+                prop::collection::vec(prop_oneof![3 => Just(0), 1 => 1..4_usize], width * height),
+                0..height,
+                0..height,
+            )
+                .prop_map(move |(dips, start, end)| {
+                    let mut map = String::new();
+                    for y in 0..height {
+                        for x in 0..width {
+                            map.push(match (x, y) {
+                                (0, y) if y == start => 'S',
+                                (x, y) if x == width - 1 && y == end => 'E',
+                                _ => {
+                                    let climb =
+                                        (x * 25 / (width - 1)).saturating_sub(dips[x + y * width]);
+                                    char::from(b'a' + u8::try_from(climb).unwrap())
+                                }
+                            });
+                        }
+                        map.push('\n');
+                    }
+                    map
+                })
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_distance_to_zero_is_exact(map in mountain_strategy()) {
+            let mountain = Mountain::parse(&map).unwrap();
+
+            for pos in mountain.squares.positions() {
+                let closest = mountain
+                    .squares
+                    .positions()
+                    .filter(|&p| mountain.height(p) == 0)
+                    .map(|p| distance(pos, p))
+                    .min()
+                    .unwrap();
+
+                prop_assert_eq!(closest, mountain.distance_to_zero(pos));
+            }
+        }
+
+        #[test]
+        fn prop_paths_match_naive(map in mountain_strategy()) {
+            let mountain = Mountain::parse(&map).unwrap();
+            let start = mountain.find(&Square::Start).unwrap();
+            let end = mountain.find(&Square::End).unwrap();
+
+            prop_assert_eq!(
+                mountain.path_naive(start, Mountain::can_go, |p| p == end),
+                mountain.path()
+            );
+            prop_assert_eq!(
+                mountain.path_naive(end, Mountain::can_go_back, |p| mountain.height(p) == 0),
+                mountain.path_down()
+            );
+        }
     }
 }
@@ -1,6 +1,8 @@
-use std::cmp::Ordering;
-use std::iter::Peekable;
+use core::cmp::Ordering;
 
+use crate::error::Error;
+use crate::parse::{self, Cursor};
+use crate::prelude::*;
 use crate::solutions::Solution;
 
 #[derive(Eq, PartialEq)]
@@ -35,70 +37,51 @@ impl Ord for Value {
     }
 }
 
-fn parse_file(file: &str) -> impl Iterator<Item = (Value, Value)> + '_ {
-    file.split("\n\n").map(|p| {
-        let (a, b) = p.split_once('\n').unwrap();
+fn parse_file(file: &str) -> impl Iterator<Item = Result<(Value, Value), Error>> + '_ {
+    parse::blocks(file).map(|p| {
+        let (a, b) = p
+            .split_once('\n')
+            .ok_or_else(|| Error::input("Expected a pair of packets"))?;
 
-        (parse_line(a), parse_line(b))
+        Ok((parse_line(a)?, parse_line(b)?))
     })
 }
 
-fn parse_line(line: &str) -> Value {
-    let mut it = line.chars().peekable();
+fn parse_line(line: &str) -> Result<Value, Error> {
+    let mut cursor = Cursor::new(line);
+    let packet = parse_list(&mut cursor)?;
+    cursor.finish()?;
 
-    parse_list(&mut it)
+    Ok(packet)
 }
 
-fn parse_list<T: Iterator<Item = char>>(it: &mut Peekable<T>) -> Value {
-    let mut list: Vec<Value> = vec![];
-
-    it.next(); // skip '['
-
-    while let Some(&c) = it.peek() {
-        match c {
-            '[' => list.push(parse_list(it)),
-            ']' => {
-                it.next(); // skip ']';
-                break;
-            }
-            '0'..='9' => list.push(parse_number(it)),
-            ',' => {
-                it.next(); // skip ','
-            }
-            c => panic!("Unknown! {}", c),
-        }
-    }
-
-    Value::List(list)
+fn parse_list(cursor: &mut Cursor) -> Result<Value, Error> {
+    Ok(Value::List(cursor.separated('[', ',', ']', parse_value)?))
 }
 
-fn parse_number<T: Iterator<Item = char>>(it: &mut Peekable<T>) -> Value {
-    let mut str = String::new();
-
-    while let Some(&c) = it.peek() {
-        if !c.is_ascii_digit() {
-            return Value::Integer(str.parse().unwrap());
-        }
-
-        str.push(c);
-        it.next();
+fn parse_value(cursor: &mut Cursor) -> Result<Value, Error> {
+    match cursor.peek() {
+        Some('[') => parse_list(cursor),
+        Some('0'..='9') => Ok(Value::Integer(cursor.integer()?)),
+        _ => Err(cursor.error("Expected a list or a number")),
     }
-
-    Value::Integer(str.parse().unwrap())
 }
 
-pub fn part_a(file: &str) -> Solution {
-    Solution::Integer(
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(
         parse_file(file)
-            .map(|(a, b)| a.cmp(&b))
+            .collect::<Result<Vec<_>, _>>()?
+            .iter()
+            .map(|(a, b)| a.cmp(b))
             .enumerate()
             .filter(|(_, v)| *v == Ordering::Less)
             .map(|(i, _)| i as i64 + 1)
             .sum(),
-    )
+    ))
 }
 
-pub fn part_b(file: &str) -> Solution {
+#[allow(clippy::cast_possible_wrap)]
+pub fn part_b(file: &str) -> Result<Solution, Error> {
     let mut lines = file
         .lines()
         .filter_map(|l| {
@@ -108,15 +91,21 @@ pub fn part_b(file: &str) -> Solution {
                 Some(parse_line(l))
             }
         })
-        .chain(vec![wrap(i_to_list(2)), wrap(i_to_list(6))])
-        .collect::<Vec<Value>>();
+        .chain(vec![Ok(wrap(i_to_list(2))), Ok(wrap(i_to_list(6)))])
+        .collect::<Result<Vec<Value>, _>>()?;
 
     lines.sort_unstable();
 
-    let first_index = lines.iter().position(|p| *p == wrap(i_to_list(2))).unwrap() + 1;
-    let second_index = lines.iter().position(|p| *p == wrap(i_to_list(6))).unwrap() + 1;
+    let index_of = |divider: Value| {
+        lines
+            .iter()
+            .position(|p| *p == divider)
+            .map_or(0, |i| i + 1)
+    };
+    let first_index = index_of(wrap(i_to_list(2)));
+    let second_index = index_of(wrap(i_to_list(6)));
 
-    Solution::Integer((first_index * second_index) as i64)
+    Ok(Solution::Integer((first_index * second_index) as i64))
 }
 
 #[cfg(test)]
@@ -143,27 +132,30 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_number() {
-        assert_eq!(Value::Integer(1), parse_number(&mut "1".chars().peekable()));
+    fn test_parse_value() {
+        assert_eq!(
+            Value::Integer(1),
+            parse_value(&mut Cursor::new("1")).unwrap()
+        );
 
         assert_eq!(
             Value::Integer(100),
-            parse_number(&mut "100".chars().peekable())
+            parse_value(&mut Cursor::new("100")).unwrap()
         );
 
         assert_eq!(
             Value::Integer(50),
-            parse_number(&mut "50abc".chars().peekable())
+            parse_value(&mut Cursor::new("50abc")).unwrap()
         );
     }
 
     #[test]
     fn test_parse_line() {
-        assert_eq!(Value::List(vec![]), parse_line("[]"));
+        assert_eq!(Value::List(vec![]), parse_line("[]").unwrap());
 
         assert_eq!(
             Value::List(vec![Value::Integer(1), Value::Integer(2)]),
-            parse_line("[1,2]")
+            parse_line("[1,2]").unwrap()
         );
 
         assert_eq!(
@@ -171,17 +163,22 @@ mod tests {
                 Value::Integer(1),
                 Value::List(vec![Value::Integer(2), Value::Integer(3)]),
             ]),
-            parse_line("[1,[2,3]]")
+            parse_line("[1,[2,3]]").unwrap()
         );
+
+        assert!(parse_line("[1,a]").is_err());
+        assert!(parse_line("[99999999999]").is_err());
+        assert!(parse_line("[1,2").is_err());
+        assert!(parse_line("[1]2").is_err());
     }
 
     #[test]
     fn test_part_a() {
-        assert_eq!(Solution::Integer(13), part_a(&read_test_data()));
+        assert_eq!(Solution::Integer(13), part_a(&read_test_data()).unwrap());
     }
 
     #[test]
     fn test_part_b() {
-        assert_eq!(Solution::Integer(140), part_b(&read_test_data()));
+        assert_eq!(Solution::Integer(140), part_b(&read_test_data()).unwrap());
     }
 }
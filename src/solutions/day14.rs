@@ -0,0 +1,224 @@
+use hashbrown::HashSet;
+
+use crate::error::Error;
+use crate::geometry::Point;
+use crate::grid::{Grid, Pos};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+const SOURCE: Point = Point::new(500, 0);
+
+/// Rock further out than this is rejected, rather than making room for all the sand it could hold.
+const MAX_COORDINATE: i64 = 2000;
+
+/// The rock paths, one polyline of `x,y` corners per line.
+fn parse_paths(file: &str) -> Result<Vec<Vec<Point>>, Error> {
+    file.lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.split(" -> ").map(parse_point).collect())
+        .collect()
+}
+
+fn parse_point(point: &str) -> Result<Point, Error> {
+    let (x, y) = point
+        .split_once(',')
+        .ok_or_else(|| Error::input("Expected a point like 498,4"))?;
+    let point = Point::new(x.trim().parse()?, y.trim().parse()?);
+
+    if !(0..=MAX_COORDINATE).contains(&point.x) || !(0..=MAX_COORDINATE).contains(&point.y) {
+        return Err(Error::input("Rock is outside the cave"));
+    }
+    Ok(point)
+}
+
+/// Every square of rock along the paths, which only run straight across or down.
+fn rocks(paths: &[Vec<Point>]) -> Result<HashSet<Point>, Error> {
+    let mut rocks = HashSet::new();
+
+    for path in paths {
+        rocks.extend(path.first());
+
+        for corners in path.windows(2) {
+            let step = corners[1] - corners[0];
+            if step.x != 0 && step.y != 0 {
+                return Err(Error::input("Rock paths must be horizontal or vertical"));
+            }
+
+            let mut point = corners[0];
+            while point != corners[1] {
+                point += step.signum();
+                rocks.insert(point);
+            }
+        }
+    }
+
+    Ok(rocks)
+}
+
+/// The part of the cave sand can reach: everything within a diagonal of the source, down to the
+/// floor two below the lowest rock.
+struct Cave {
+    blocked: Grid<bool>,
+    /// The x coordinate of the leftmost column.
+    left: i64,
+    floor: usize,
+}
+
+impl Cave {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn parse(file: &str) -> Result<Self, Error> {
+        let rocks = rocks(&parse_paths(file)?)?;
+        let floor = rocks.iter().map(|rock| rock.y).max().unwrap_or(0) + 2;
+        let left = SOURCE.x - floor;
+
+        let width = 2 * floor as usize + 1;
+        let mut blocked = Grid::filled(width, floor as usize, false);
+        for rock in &rocks {
+            // Rock further out than the floor is wide can never be reached.
+            if let Some(pos) = Point::new(rock.x - left, rock.y).to_pos() {
+                if let Some(cell) = blocked.get_mut(pos) {
+                    *cell = true;
+                }
+            }
+        }
+
+        Ok(Self {
+            blocked,
+            left,
+            floor: floor as usize,
+        })
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    const fn source(&self) -> Pos {
+        ((SOURCE.x - self.left) as usize, SOURCE.y as usize)
+    }
+
+    /// The squares a grain at `(x, y)` tries to fall into, in order, or none once it is lying on
+    /// the floor.
+    fn below(&self, (x, y): Pos) -> Option<[Pos; 3]> {
+        (y + 1 < self.floor).then_some([(x, y + 1), (x - 1, y + 1), (x + 1, y + 1)])
+    }
+
+    /// Pours sand until a grain falls past the lowest rock, or with `floor` until the source is
+    /// buried, and counts the grains that came to rest.
+    ///
+    /// Every grain falls along the path of the one before it until the square where that one came
+    /// to rest, so the path is kept and each grain carries on from its end.
+    fn pour(&mut self, floor: bool) -> usize {
+        if self.blocked[self.source()] {
+            return 0;
+        }
+
+        let mut path = vec![self.source()];
+        let mut resting = 0;
+
+        while let Some(&pos) = path.last() {
+            match self.below(pos) {
+                None if !floor => break,
+                Some(next) => {
+                    if let Some(&next) = next.iter().find(|&&p| !self.blocked[p]) {
+                        path.push(next);
+                        continue;
+                    }
+                }
+                None => {}
+            }
+
+            self.blocked[pos] = true;
+            resting += 1;
+            path.pop();
+        }
+
+        resting
+    }
+
+    /// Naive reference for `pour`: drops every grain from the source on its own.
+    fn pour_naive(&mut self, floor: bool) -> usize {
+        let mut resting = 0;
+
+        while !self.blocked[self.source()] {
+            let mut pos = self.source();
+            while let Some(next) = self.below(pos) {
+                match next.iter().find(|&&p| !self.blocked[p]) {
+                    Some(&next) => pos = next,
+                    None => break,
+                }
+            }
+
+            if self.below(pos).is_none() && !floor {
+                break;
+            }
+            self.blocked[pos] = true;
+            resting += 1;
+        }
+
+        resting
+    }
+}
+
+#[allow(clippy::cast_possible_wrap)]
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(Cave::parse(file)?.pour(false) as i64))
+}
+
+#[allow(clippy::cast_possible_wrap)]
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(Cave::parse(file)?.pour(true) as i64))
+}
+
+#[allow(clippy::cast_possible_wrap)]
+pub fn reference_part_a(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(
+        Cave::parse(file)?.pour_naive(false) as i64
+    ))
+}
+
+#[allow(clippy::cast_possible_wrap)]
+pub fn reference_part_b(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(Cave::parse(file)?.pour_naive(true) as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "498,4 -> 498,6 -> 496,6\n503,4 -> 502,4 -> 502,9 -> 494,9\n";
+
+    #[test]
+    fn test_rocks() {
+        let rocks = rocks(&parse_paths(TEST_INPUT).unwrap()).unwrap();
+
+        assert_eq!(20, rocks.len());
+        assert!(rocks.contains(&Point::new(498, 5)));
+        assert!(rocks.contains(&Point::new(494, 9)));
+        assert!(!rocks.contains(&Point::new(499, 4)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Cave::parse("498,4 -> 500,6").is_err());
+        assert!(Cave::parse("498,4 -> 498").is_err());
+        assert!(Cave::parse("498,-4 -> 498,6").is_err());
+        assert!(Cave::parse("498,4 -> 498,1000000").is_err());
+        assert!(Cave::parse("0,4 -> 99999999999,4").is_err());
+    }
+
+    #[test]
+    fn test_part_a() {
+        assert_eq!(Solution::Integer(24), part_a(TEST_INPUT).unwrap());
+        assert_eq!(Solution::Integer(24), reference_part_a(TEST_INPUT).unwrap());
+    }
+
+    #[test]
+    fn test_part_b() {
+        assert_eq!(Solution::Integer(93), part_b(TEST_INPUT).unwrap());
+        assert_eq!(Solution::Integer(93), reference_part_b(TEST_INPUT).unwrap());
+    }
+
+    #[test]
+    fn test_buried_source() {
+        assert_eq!(Solution::Integer(0), part_a("500,0 -> 500,3").unwrap());
+        assert_eq!(Solution::Integer(0), part_b("500,0 -> 500,3").unwrap());
+    }
+}
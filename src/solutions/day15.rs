@@ -0,0 +1,213 @@
+use core::ops::RangeInclusive;
+
+use crate::error::Error;
+use crate::geometry::Point;
+use crate::intervals::IntervalSet;
+use crate::parse;
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+/// The row part a looks along in the real input.
+const ROW: i64 = 2_000_000;
+
+/// The largest coordinate the distress beacon can have in the real input.
+const BOUND: i64 = 4_000_000;
+
+/// Coordinates are kept well inside `i64` so sums of them can't overflow.
+const MAX_COORDINATE: i64 = 1 << 40;
+
+struct Sensor {
+    position: Point,
+    beacon: Point,
+    /// The distance to the closest beacon, within which there can't be another.
+    radius: i64,
+}
+
+impl Sensor {
+    fn parse(line: &str) -> Result<Self, Error> {
+        let (sx, sy, bx, by): (i64, i64, i64, i64) = parse::capture(
+            "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+            line,
+        )?
+        .parse()?;
+
+        if [sx, sy, bx, by].iter().any(|v| v.abs() > MAX_COORDINATE) {
+            return Err(Error::input("Coordinate out of range"));
+        }
+
+        let position = Point::new(sx, sy);
+        let beacon = Point::new(bx, by);
+        Ok(Self {
+            position,
+            beacon,
+            radius: (sx - bx).abs() + (sy - by).abs(),
+        })
+    }
+
+    /// The stretch of `row` inside the sensor's range.
+    const fn cover(&self, row: i64) -> Option<RangeInclusive<i64>> {
+        let reach = self.radius - (self.position.y - row).abs();
+        if reach < 0 {
+            None
+        } else {
+            Some(self.position.x - reach..=self.position.x + reach)
+        }
+    }
+
+    const fn covers(&self, point: Point) -> bool {
+        (self.position.x - point.x).abs() + (self.position.y - point.y).abs() <= self.radius
+    }
+}
+
+fn parse_sensors(file: &str) -> Result<Vec<Sensor>, Error> {
+    let sensors = file
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(Sensor::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if sensors.is_empty() {
+        return Err(Error::input("No sensors"));
+    }
+    Ok(sensors)
+}
+
+/// How many positions on `row` can't hold a beacon, worked out from the ranges the sensors cover
+/// rather than position by position.
+fn excluded_on_row(sensors: &[Sensor], row: i64) -> u64 {
+    let mut excluded: IntervalSet = sensors.iter().filter_map(|s| s.cover(row)).collect();
+
+    for sensor in sensors.iter().filter(|s| s.beacon.y == row) {
+        excluded.remove(sensor.beacon.x..=sensor.beacon.x);
+    }
+
+    excluded.len()
+}
+
+/// The one position with both coordinates in `0..=bound` that no sensor covers.
+///
+/// Such a lone gap sits right outside the edges of the sensor ranges, so it is where a rising
+/// edge crosses a falling one, or where an edge meets the side of the square. Those few crossings
+/// are tried instead of the whole square.
+fn find_beacon(sensors: &[Sensor], bound: i64) -> Option<Point> {
+    // Edges just outside each range, as `y - x` for the rising ones and `y + x` for the falling.
+    let mut rising = vec![];
+    let mut falling = vec![];
+    for sensor in sensors {
+        let Point { x, y } = sensor.position;
+        let r = sensor.radius + 1;
+        rising.extend([y - x - r, y - x + r]);
+        falling.extend([y + x - r, y + x + r]);
+    }
+
+    let crossings = rising.iter().flat_map(|&a| {
+        falling
+            .iter()
+            .filter(move |&&b| (a + b) % 2 == 0)
+            .map(move |&b| Point::new((b - a) / 2, i64::midpoint(a, b)))
+    });
+    let (rising, falling) = (&rising, &falling);
+    let sides = [0, bound].into_iter().flat_map(|side| {
+        let along_rising = rising
+            .iter()
+            .flat_map(move |&a| [Point::new(side, side + a), Point::new(side - a, side)]);
+        let along_falling = falling
+            .iter()
+            .flat_map(move |&b| [Point::new(side, b - side), Point::new(b - side, side)]);
+        along_rising.chain(along_falling)
+    });
+    let corners = [0, bound]
+        .into_iter()
+        .flat_map(|x| [0, bound].map(|y| Point::new(x, y)));
+
+    crossings
+        .chain(sides)
+        .chain(corners)
+        .filter(|p| (0..=bound).contains(&p.x) && (0..=bound).contains(&p.y))
+        .find(|&p| !sensors.iter().any(|s| s.covers(p)))
+}
+
+fn tuning_frequency(sensors: &[Sensor], bound: i64) -> Result<i64, Error> {
+    find_beacon(sensors, bound)
+        .map(|p| p.x * 4_000_000 + p.y)
+        .ok_or_else(|| Error::input("Every position is covered"))
+}
+
+#[allow(clippy::cast_possible_wrap)]
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(
+        excluded_on_row(&parse_sensors(file)?, ROW) as i64,
+    ))
+}
+
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(tuning_frequency(
+        &parse_sensors(file)?,
+        BOUND,
+    )?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_data() -> String {
+        let path = std::env::current_dir()
+            .unwrap()
+            .join("input")
+            .join("day15_test.txt");
+        std::fs::read_to_string(&path).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let sensor = Sensor::parse("Sensor at x=2, y=18: closest beacon is at x=-2, y=15").unwrap();
+
+        assert_eq!(Point::new(2, 18), sensor.position);
+        assert_eq!(Point::new(-2, 15), sensor.beacon);
+        assert_eq!(7, sensor.radius);
+        assert_eq!(Some(-3..=7), sensor.cover(16));
+        assert_eq!(None, sensor.cover(10));
+
+        assert!(Sensor::parse("Sensor at x=2, y=18").is_err());
+        assert!(Sensor::parse("Sensor at x=2, y=18: closest beacon is at x=a, y=15").is_err());
+        assert!(parse_sensors("\n").is_err());
+    }
+
+    #[test]
+    fn test_excluded_on_row() {
+        let sensors = parse_sensors(&read_test_data()).unwrap();
+
+        assert_eq!(26, excluded_on_row(&sensors, 10));
+    }
+
+    #[test]
+    fn test_tuning_frequency() {
+        let sensors = parse_sensors(&read_test_data()).unwrap();
+
+        assert_eq!(Some(Point::new(14, 11)), find_beacon(&sensors, 20));
+        assert_eq!(56_000_011, tuning_frequency(&sensors, 20).unwrap());
+    }
+
+    #[test]
+    fn test_beacon_in_a_corner() {
+        // Covers everything in 0..=4 except (4, 4).
+        let sensors = parse_sensors("Sensor at x=0, y=0: closest beacon is at x=4, y=3").unwrap();
+
+        assert_eq!(Some(Point::new(4, 4)), find_beacon(&sensors, 4));
+    }
+
+    #[test]
+    fn test_part_b() {
+        let path = std::env::current_dir()
+            .unwrap()
+            .join("input")
+            .join("day15.txt");
+        let file = std::fs::read_to_string(&path).unwrap();
+
+        assert_eq!(
+            Solution::Integer(3_167_842 * 4_000_000 + 2_681_359),
+            part_b(&file).unwrap()
+        );
+    }
+}
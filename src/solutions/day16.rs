@@ -0,0 +1,269 @@
+use hashbrown::HashMap;
+
+use crate::error::Error;
+use crate::parse;
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+/// The opened valves are kept in a bit mask, and part b needs a table entry for every set of them.
+const MAX_FLOWING: usize = 16;
+
+const UNREACHABLE: u32 = u32::MAX;
+
+struct Valve<'a> {
+    name: &'a str,
+    flow: u32,
+    tunnels: Vec<&'a str>,
+}
+
+fn parse_valve(line: &str) -> Result<Valve<'_>, Error> {
+    let captures = parse::capture("Valve {} has flow rate={}; {}", line)?;
+    let tunnels = captures.text(2).unwrap_or_default();
+    let tunnels = tunnels
+        .strip_prefix("tunnels lead to valves ")
+        .or_else(|| tunnels.strip_prefix("tunnel leads to valve "))
+        .ok_or_else(|| Error::input("Expected the tunnels from the valve"))?;
+
+    Ok(Valve {
+        name: captures.text(0).unwrap_or_default(),
+        flow: u32::from(captures.get::<u16>(1)?),
+        tunnels: tunnels.split(", ").collect(),
+    })
+}
+
+/// The valves worth opening, with the time it takes to walk between any two of them and from the
+/// start. Every other valve only matters as a way through.
+#[derive(Debug, PartialEq, Eq)]
+struct Network {
+    flows: Vec<u32>,
+    /// `distances[i][j]` is the walk from valve `i` to valve `j`, with the start as the last row.
+    distances: Vec<Vec<u32>>,
+}
+
+impl Network {
+    fn parse(file: &str) -> Result<Self, Error> {
+        let valves = file
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(parse_valve)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let index: HashMap<&str, usize> = valves
+            .iter()
+            .enumerate()
+            .map(|(i, valve)| (valve.name, i))
+            .collect();
+        if index.len() != valves.len() {
+            return Err(Error::input("Valve listed twice"));
+        }
+        let start = *index
+            .get("AA")
+            .ok_or_else(|| Error::input("No valve AA to start from"))?;
+
+        // Floyd-Warshall over every valve, tunnels taking a minute each.
+        let n = valves.len();
+        let mut all = vec![vec![UNREACHABLE; n]; n];
+        for (i, valve) in valves.iter().enumerate() {
+            all[i][i] = 0;
+            for tunnel in &valve.tunnels {
+                let j = *index
+                    .get(tunnel)
+                    .ok_or_else(|| Error::input("Tunnel to an unknown valve"))?;
+                all[i][j] = 1;
+            }
+        }
+        for k in 0..n {
+            for i in 0..n {
+                for j in 0..n {
+                    let through = all[i][k].saturating_add(all[k][j]);
+                    if through < all[i][j] {
+                        all[i][j] = through;
+                    }
+                }
+            }
+        }
+
+        let flowing: Vec<usize> = (0..n).filter(|&i| valves[i].flow > 0).collect();
+        if flowing.len() > MAX_FLOWING {
+            return Err(Error::input("Too many valves with a flow"));
+        }
+
+        let rows = flowing.iter().chain([&start]);
+        Ok(Self {
+            flows: flowing.iter().map(|&i| valves[i].flow).collect(),
+            distances: rows
+                .map(|&i| flowing.iter().map(|&j| all[i][j]).collect())
+                .collect(),
+        })
+    }
+
+    /// The most pressure released in `time` minutes for every set of opened valves, as a bit
+    /// mask. Sets that can't be opened in time are left at 0.
+    ///
+    /// States are the valve last opened, the opened set and the time left. Many orders of opening
+    /// lead to the same state, so they are merged keeping the best pressure, one minute at a time.
+    fn best_by_opened(&self, time: u32) -> Vec<u32> {
+        let n = self.flows.len();
+        let mut best = vec![0; 1 << n];
+
+        // `states[t]` holds the states with `t` minutes left.
+        let mut states: Vec<HashMap<(usize, u32), u32>> = vec![HashMap::new(); time as usize + 1];
+        states[time as usize].insert((n, 0), 0);
+
+        for left in (0..=time).rev() {
+            for ((at, opened), pressure) in core::mem::take(&mut states[left as usize]) {
+                best[opened as usize] = best[opened as usize].max(pressure);
+
+                for next in (0..n).filter(|next| opened & (1 << next) == 0) {
+                    let cost = self.distances[at][next].saturating_add(1);
+                    if cost >= left {
+                        continue;
+                    }
+                    let remaining = left - cost;
+                    let pressure = pressure + self.flows[next] * remaining;
+                    let entry = states[remaining as usize]
+                        .entry((next, opened | 1 << next))
+                        .or_default();
+                    *entry = (*entry).max(pressure);
+                }
+            }
+        }
+
+        best
+    }
+}
+
+/// The most pressure one agent releases in `time` minutes.
+fn release_alone(network: &Network, time: u32) -> u32 {
+    network
+        .best_by_opened(time)
+        .into_iter()
+        .max()
+        .unwrap_or_default()
+}
+
+/// The most pressure two agents working at once release in `time` minutes. They never gain from
+/// opening the same valve, so the valves are split between them: each split is worth the best one
+/// agent does with the valves on its side.
+fn release_together(network: &Network, time: u32) -> u32 {
+    let mut best = network.best_by_opened(time);
+    let all = best.len() - 1;
+
+    // Widen each entry to the best over all its subsets.
+    for bit in 0..network.flows.len() {
+        for opened in 0..=all {
+            if opened & (1 << bit) != 0 {
+                best[opened] = best[opened].max(best[opened ^ (1 << bit)]);
+            }
+        }
+    }
+
+    (0..=all)
+        .map(|mine| best[mine] + best[all ^ mine])
+        .max()
+        .unwrap_or_default()
+}
+
+/// Naive reference for the merged states: every order of opening the valves, tried one by one.
+fn release_naive(network: &Network, at: usize, allowed: u32, left: u32) -> u32 {
+    (0..network.flows.len())
+        .filter(|next| allowed & (1 << next) != 0)
+        .filter_map(|next| {
+            let cost = network.distances[at][next].saturating_add(1);
+            (cost < left).then(|| {
+                let remaining = left - cost;
+                network.flows[next] * remaining
+                    + release_naive(network, next, allowed & !(1 << next), remaining)
+            })
+        })
+        .max()
+        .unwrap_or_default()
+}
+
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(i64::from(release_alone(
+        &Network::parse(file)?,
+        30,
+    ))))
+}
+
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(i64::from(release_together(
+        &Network::parse(file)?,
+        26,
+    ))))
+}
+
+pub fn reference_part_a(file: &str) -> Result<Solution, Error> {
+    let network = Network::parse(file)?;
+    let all = (1 << network.flows.len()) - 1;
+
+    Ok(Solution::Integer(i64::from(release_naive(
+        &network,
+        network.flows.len(),
+        all,
+        30,
+    ))))
+}
+
+pub fn reference_part_b(file: &str) -> Result<Solution, Error> {
+    let network = Network::parse(file)?;
+    let start = network.flows.len();
+    let all = (1 << network.flows.len()) - 1;
+
+    Ok(Solution::Integer(i64::from(
+        (0..=all)
+            .map(|mine| {
+                release_naive(&network, start, mine, 26)
+                    + release_naive(&network, start, all ^ mine, 26)
+            })
+            .max()
+            .unwrap_or_default(),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_data() -> String {
+        let path = std::env::current_dir()
+            .unwrap()
+            .join("input")
+            .join("day16_test.txt");
+        std::fs::read_to_string(&path).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let network = Network::parse(&read_test_data()).unwrap();
+
+        assert_eq!(vec![13, 2, 20, 3, 22, 21], network.flows);
+        // From AA to JJ goes back through II.
+        assert_eq!(2, network.distances[6][5]);
+        assert_eq!(0, network.distances[0][0]);
+
+        assert!(Network::parse("Valve AA has flow rate=0; tunnel leads to valve BB").is_err());
+        assert!(Network::parse("Valve BB has flow rate=1; tunnel leads to valve BB").is_err());
+        assert!(Network::parse("Valve AA has flow rate=0; tunnels to BB").is_err());
+        assert!(Network::parse("Valve AA has flow rate=x; tunnel leads to valve AA").is_err());
+    }
+
+    #[test]
+    fn test_part_a() {
+        assert_eq!(Solution::Integer(1651), part_a(&read_test_data()).unwrap());
+        assert_eq!(
+            Solution::Integer(1651),
+            reference_part_a(&read_test_data()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_part_b() {
+        assert_eq!(Solution::Integer(1707), part_b(&read_test_data()).unwrap());
+        assert_eq!(
+            Solution::Integer(1707),
+            reference_part_b(&read_test_data()).unwrap()
+        );
+    }
+}
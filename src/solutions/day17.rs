@@ -0,0 +1,317 @@
+use core::convert::Infallible;
+use core::fmt;
+
+use crate::cycle;
+use crate::error::Error;
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+const WIDTH: usize = 7;
+
+/// The rocks in the order they fall, as rows from the bottom up with bit 6 for the leftmost
+/// column, already two columns in from the left wall where they appear.
+const ROCKS: [&[u8]; 5] = [
+    &[0b001_1110],
+    &[0b000_1000, 0b001_1100, 0b000_1000],
+    &[0b001_1100, 0b000_0100, 0b000_0100],
+    &[0b001_0000, 0b001_0000, 0b001_0000, 0b001_0000],
+    &[0b001_1000, 0b001_1000],
+];
+
+/// How far down the surface profile looks. Columns left empty deeper than this are treated alike,
+/// as no rock falls that far past the rest of the surface.
+const PROFILE_DEPTH: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Jet {
+    Left,
+    Right,
+}
+
+fn parse_jets(file: &str) -> Result<Vec<Jet>, Error> {
+    let jets = file
+        .trim_end()
+        .chars()
+        .map(|c| match c {
+            '<' => Ok(Jet::Left),
+            '>' => Ok(Jet::Right),
+            _ => Err(Error::input("Jets only push < or >")),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if jets.is_empty() {
+        return Err(Error::input("No jets"));
+    }
+    Ok(jets)
+}
+
+/// A rock on its way down, as its rows of the chamber from `bottom` up.
+#[derive(Clone, Debug)]
+struct Falling {
+    rows: Vec<u8>,
+    bottom: usize,
+}
+
+#[derive(Clone, Debug)]
+struct Chamber<'a> {
+    jets: &'a [Jet],
+    /// Settled rock, one bit per column, from the floor up. Empty rows are never kept.
+    rows: Vec<u8>,
+    next_jet: usize,
+    /// How many rocks have come to rest.
+    rocks: u64,
+}
+
+impl<'a> Chamber<'a> {
+    const fn new(jets: &'a [Jet]) -> Self {
+        Self {
+            jets,
+            rows: vec![],
+            next_jet: 0,
+            rocks: 0,
+        }
+    }
+
+    const fn height(&self) -> u64 {
+        self.rows.len() as u64
+    }
+
+    /// The next rock where it appears: two columns in and three rows above the tower.
+    fn appear(&self) -> Falling {
+        #[allow(clippy::cast_possible_truncation)]
+        let shape = ROCKS[(self.rocks % ROCKS.len() as u64) as usize];
+
+        Falling {
+            rows: shape.to_vec(),
+            bottom: self.rows.len() + 3,
+        }
+    }
+
+    fn fits(&self, rows: &[u8], bottom: usize) -> bool {
+        rows.iter().enumerate().all(|(i, row)| {
+            self.rows
+                .get(bottom + i)
+                .is_none_or(|settled| settled & row == 0)
+        })
+    }
+
+    /// Lets the next rock fall, pushed by a jet before every step down, until it comes to rest.
+    fn drop_rock(&mut self) {
+        let mut rock = self.appear();
+
+        loop {
+            let jet = self.jets[self.next_jet];
+            self.next_jet = (self.next_jet + 1) % self.jets.len();
+
+            let against_wall = match jet {
+                Jet::Left => rock.rows.iter().any(|row| row & 0b100_0000 != 0),
+                Jet::Right => rock.rows.iter().any(|row| row & 1 != 0),
+            };
+            if !against_wall {
+                let pushed: Vec<u8> = rock
+                    .rows
+                    .iter()
+                    .map(|&row| if jet == Jet::Left { row << 1 } else { row >> 1 })
+                    .collect();
+                if self.fits(&pushed, rock.bottom) {
+                    rock.rows = pushed;
+                }
+            }
+
+            if rock.bottom == 0 || !self.fits(&rock.rows, rock.bottom - 1) {
+                break;
+            }
+            rock.bottom -= 1;
+        }
+
+        for (i, row) in rock.rows.iter().enumerate() {
+            match self.rows.get_mut(rock.bottom + i) {
+                Some(settled) => *settled |= row,
+                None => self.rows.push(*row),
+            }
+        }
+        self.rocks += 1;
+    }
+
+    /// How far below the top of the tower the highest rock of each column is.
+    fn profile(&self) -> [usize; WIDTH] {
+        let mut profile = [PROFILE_DEPTH; WIDTH];
+
+        for (depth, row) in self.rows.iter().rev().take(PROFILE_DEPTH).enumerate() {
+            for (column, deepest) in profile.iter_mut().enumerate() {
+                if *deepest == PROFILE_DEPTH && row & (0b100_0000 >> column) != 0 {
+                    *deepest = depth;
+                }
+            }
+        }
+
+        profile
+    }
+
+    /// Draws the chamber as the puzzle does, with `falling` as `@` if there is one.
+    fn draw(&self, falling: Option<&Falling>) -> String {
+        let top = falling.map_or(self.rows.len(), |rock| {
+            self.rows.len().max(rock.bottom + rock.rows.len())
+        });
+        let mut out = String::new();
+
+        for y in (0..top).rev() {
+            let settled = self.rows.get(y).copied().unwrap_or_default();
+            let moving = falling
+                .and_then(|rock| rock.rows.get(y.checked_sub(rock.bottom)?))
+                .copied()
+                .unwrap_or_default();
+
+            out.push('|');
+            for column in 0..WIDTH {
+                let bit = 0b100_0000 >> column;
+                out.push(if moving & bit != 0 {
+                    '@'
+                } else if settled & bit != 0 {
+                    '#'
+                } else {
+                    '.'
+                });
+            }
+            out.push_str("|\n");
+        }
+
+        out.push_str("+-------+\n");
+        out
+    }
+}
+
+impl fmt::Display for Chamber<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.draw(None))
+    }
+}
+
+/// The height of the tower after `rocks` rocks. The rocks and jets go round, so once the rock,
+/// the jet and the shape of the top of the tower come back together, the tower grows the same
+/// way again and the rest of the climb is extrapolated.
+fn tower_height(jets: &[Jet], rocks: u64) -> u64 {
+    let result: Result<u64, Infallible> = cycle::fast_forward(
+        Chamber::new(jets),
+        rocks,
+        |mut chamber| {
+            chamber.drop_rock();
+            Ok(chamber)
+        },
+        |chamber| {
+            (
+                chamber.rocks % ROCKS.len() as u64,
+                chamber.next_jet,
+                chamber.profile(),
+            )
+        },
+        Chamber::height,
+    );
+
+    match result {
+        Ok(height) => height,
+        Err(never) => match never {},
+    }
+}
+
+#[allow(clippy::cast_possible_wrap)]
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    let jets = parse_jets(file)?;
+    let mut chamber = Chamber::new(&jets);
+
+    for _ in 0..2022 {
+        chamber.drop_rock();
+    }
+
+    Ok(Solution::Integer(chamber.height() as i64))
+}
+
+#[allow(clippy::cast_possible_wrap)]
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(
+        tower_height(&parse_jets(file)?, 1_000_000_000_000) as i64,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>\n";
+
+    #[test]
+    fn test_parse_jets() {
+        assert_eq!(vec![Jet::Right, Jet::Left], parse_jets("><\n").unwrap());
+        assert!(parse_jets("<x>").is_err());
+        assert!(parse_jets("\n").is_err());
+    }
+
+    #[test]
+    fn test_draw() {
+        let jets = parse_jets(TEST_INPUT).unwrap();
+        let mut chamber = Chamber::new(&jets);
+
+        assert_eq!(
+            "|..@@@@.|\n\
+             |.......|\n\
+             |.......|\n\
+             |.......|\n\
+             +-------+\n",
+            chamber.draw(Some(&chamber.appear()))
+        );
+
+        chamber.drop_rock();
+        assert_eq!("|..####.|\n+-------+\n", chamber.to_string());
+        assert_eq!(
+            "|...@...|\n\
+             |..@@@..|\n\
+             |...@...|\n\
+             |.......|\n\
+             |.......|\n\
+             |.......|\n\
+             |..####.|\n\
+             +-------+\n",
+            chamber.draw(Some(&chamber.appear()))
+        );
+
+        chamber.drop_rock();
+        assert_eq!(
+            "|...#...|\n\
+             |..###..|\n\
+             |...#...|\n\
+             |..####.|\n\
+             +-------+\n",
+            chamber.to_string()
+        );
+    }
+
+    #[test]
+    fn test_part_a() {
+        assert_eq!(Solution::Integer(3068), part_a(TEST_INPUT).unwrap());
+    }
+
+    #[test]
+    fn test_part_b() {
+        assert_eq!(
+            Solution::Integer(1_514_285_714_288),
+            part_b(TEST_INPUT).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_tower_height_agrees_with_simulation() {
+        let jets = parse_jets(TEST_INPUT).unwrap();
+        let mut chamber = Chamber::new(&jets);
+
+        for rocks in 0..=5000 {
+            if rocks % 97 == 0 {
+                assert_eq!(
+                    chamber.height(),
+                    tower_height(&jets, rocks),
+                    "{rocks} rocks"
+                );
+            }
+            chamber.drop_rock();
+        }
+    }
+}
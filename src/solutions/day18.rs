@@ -0,0 +1,126 @@
+use hashbrown::HashSet;
+
+use crate::error::Error;
+use crate::geometry::{BoundingBox3, Point3};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+/// Droplets whose bounding box holds more cubes than this are rejected, as part b fills the air
+/// around them one cube at a time.
+const MAX_VOLUME: u64 = 1 << 24;
+
+fn parse_cubes(file: &str) -> Result<HashSet<Point3>, Error> {
+    file.lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let mut coordinates = line.split(',').map(|c| c.trim().parse::<i32>());
+            match (
+                coordinates.next(),
+                coordinates.next(),
+                coordinates.next(),
+                coordinates.next(),
+            ) {
+                (Some(x), Some(y), Some(z), None) => {
+                    Ok(Point3::new(x?.into(), y?.into(), z?.into()))
+                }
+                _ => Err(Error::input("Expected a cube like 2,2,2")),
+            }
+        })
+        .collect()
+}
+
+/// Faces of the cubes that don't touch another cube.
+fn surface_area(cubes: &HashSet<Point3>) -> usize {
+    cubes
+        .iter()
+        .flat_map(|cube| cube.neighbours6())
+        .filter(|side| !cubes.contains(side))
+        .count()
+}
+
+/// Faces of the cubes that steam from outside can reach. The air around the droplet is flooded
+/// from a corner of its bounding box, padded so the flood can get all the way round, and every
+/// cube face the flood runs into is on the outside.
+fn exterior_surface_area(cubes: &HashSet<Point3>) -> Result<usize, Error> {
+    let Some(bounds) = BoundingBox3::of(cubes.iter().copied()) else {
+        return Ok(0);
+    };
+    let bounds = bounds.padded(1);
+    if bounds.volume() > MAX_VOLUME {
+        return Err(Error::input("Droplet is too large"));
+    }
+
+    let mut outside: HashSet<_> = HashSet::from([bounds.min]);
+    let mut stack = vec![bounds.min];
+    let mut faces = 0;
+
+    while let Some(air) = stack.pop() {
+        for next in air.neighbours6() {
+            if cubes.contains(&next) {
+                faces += 1;
+            } else if bounds.contains(next) && outside.insert(next) {
+                stack.push(next);
+            }
+        }
+    }
+
+    Ok(faces)
+}
+
+#[allow(clippy::cast_possible_wrap)]
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(surface_area(&parse_cubes(file)?) as i64))
+}
+
+#[allow(clippy::cast_possible_wrap)]
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(
+        exterior_surface_area(&parse_cubes(file)?)? as i64,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_data() -> String {
+        let path = std::env::current_dir()
+            .unwrap()
+            .join("input")
+            .join("day18_test.txt");
+        std::fs::read_to_string(&path).unwrap()
+    }
+
+    #[test]
+    fn test_parse_cubes() {
+        let cubes = parse_cubes("1,1,1\n2,1,-1\n").unwrap();
+
+        assert!(cubes.contains(&Point3::new(2, 1, -1)));
+        assert!(parse_cubes("1,1").is_err());
+        assert!(parse_cubes("1,1,1,1").is_err());
+        assert!(parse_cubes("1,a,1").is_err());
+    }
+
+    #[test]
+    fn test_two_cubes() {
+        let cubes = parse_cubes("1,1,1\n2,1,1\n").unwrap();
+
+        assert_eq!(10, surface_area(&cubes));
+        assert_eq!(10, exterior_surface_area(&cubes).unwrap());
+    }
+
+    #[test]
+    fn test_too_large() {
+        assert!(part_b("0,0,0\n1000,1000,1000\n").is_err());
+    }
+
+    #[test]
+    fn test_part_a() {
+        assert_eq!(Solution::Integer(64), part_a(&read_test_data()).unwrap());
+    }
+
+    #[test]
+    fn test_part_b() {
+        assert_eq!(Solution::Integer(58), part_b(&read_test_data()).unwrap());
+    }
+}
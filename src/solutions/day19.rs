@@ -0,0 +1,316 @@
+use crate::error::Error;
+use crate::parse;
+use crate::prelude::*;
+use crate::search::{self, Stats};
+use crate::solutions::Solution;
+
+const ORE: usize = 0;
+const CLAY: usize = 1;
+const OBSIDIAN: usize = 2;
+const GEODE: usize = 3;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Blueprint {
+    id: u32,
+    /// What a robot collecting each kind of resource costs, in each kind of resource.
+    costs: [[u32; 4]; 4],
+    /// No more robots of a kind are worth having than the most of its resource that can be spent
+    /// in a minute, as only one robot is built at a time.
+    useful: [u32; 4],
+}
+
+fn parse_blueprints(file: &str) -> Result<Vec<Blueprint>, Error> {
+    let blueprints = file
+        .split("Blueprint")
+        .filter(|text| !text.trim().is_empty())
+        .map(|text| {
+            // The example spreads each blueprint over several lines.
+            let line = format!(
+                "Blueprint {}",
+                text.split_whitespace().collect::<Vec<_>>().join(" ")
+            );
+            parse_blueprint(&line)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if blueprints.is_empty() {
+        return Err(Error::input("No blueprints"));
+    }
+    Ok(blueprints)
+}
+
+fn parse_blueprint(line: &str) -> Result<Blueprint, Error> {
+    let captures = parse::capture(
+        "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. \
+         Each obsidian robot costs {} ore and {} clay. \
+         Each geode robot costs {} ore and {} obsidian.",
+        line,
+    )?;
+    // Costs are read as `u16` so resources can't overflow over the minutes.
+    let cost = |i| captures.get::<u16>(i).map(u32::from);
+
+    let mut costs = [[0; 4]; 4];
+    costs[ORE][ORE] = cost(1)?;
+    costs[CLAY][ORE] = cost(2)?;
+    costs[OBSIDIAN][ORE] = cost(3)?;
+    costs[OBSIDIAN][CLAY] = cost(4)?;
+    costs[GEODE][ORE] = cost(5)?;
+    costs[GEODE][OBSIDIAN] = cost(6)?;
+    if costs.iter().any(|cost| cost.iter().all(|&c| c == 0)) {
+        return Err(Error::input("Robots can't be free"));
+    }
+
+    let mut useful = [u32::MAX; 4];
+    for (kind, most) in useful.iter_mut().enumerate().take(GEODE) {
+        *most = costs
+            .iter()
+            .map(|cost| cost[kind])
+            .max()
+            .unwrap_or_default();
+    }
+
+    Ok(Blueprint {
+        id: captures.get(0)?,
+        costs,
+        useful,
+    })
+}
+
+/// A point where the next robot is picked. Geodes are counted in full when their robot is built,
+/// so only the other robots and resources are kept.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct State {
+    left: u32,
+    robots: [u32; 4],
+    resources: [u32; 4],
+}
+
+impl Blueprint {
+    /// The most geodes that can be opened in `minutes`, and how much searching it took.
+    ///
+    /// Rather than go a minute at a time, every step picks the next robot to build and waits until
+    /// it can be afforded, and a geode robot gains the geodes it will open in the minutes left.
+    /// Robots whose resource can't all be spent are never built, and stock beyond what can be
+    /// spent is dropped, so that states differing only in waste are memoised as one.
+    fn max_geodes(&self, minutes: u32) -> (u32, Stats) {
+        let start = State {
+            left: minutes,
+            robots: [1, 0, 0, 0],
+            resources: [0; 4],
+        };
+
+        let outcome = search::dfs_longest(start, |state| self.successors(state));
+        (outcome.cost().unwrap_or_default(), outcome.stats)
+    }
+
+    /// The states after building each robot worth building next, with the geodes that gains.
+    fn successors(&self, state: &State) -> Vec<(State, u32)> {
+        let State {
+            left,
+            robots,
+            resources,
+        } = *state;
+        let mut next_states = vec![];
+
+        for kind in ORE..=GEODE {
+            // Enough robots, or enough stock with the robots there are, to spend the most that can
+            // be spent every minute left.
+            if kind != GEODE
+                && (robots[kind] >= self.useful[kind]
+                    || resources[kind] + robots[kind] * left >= self.useful[kind] * left)
+            {
+                continue;
+            }
+            let Some(wait) = Self::wait_for(&self.costs[kind], state) else {
+                continue;
+            };
+            // A robot finished in the last minute collects nothing.
+            if wait + 1 >= left {
+                continue;
+            }
+
+            let mut next = State {
+                left: left - wait - 1,
+                robots,
+                resources,
+            };
+            for ((stock, robots), cost) in
+                next.resources.iter_mut().zip(robots).zip(self.costs[kind])
+            {
+                *stock = *stock + robots * (wait + 1) - cost;
+            }
+            let gain = if kind == GEODE {
+                next.left
+            } else {
+                next.robots[kind] += 1;
+                0
+            };
+
+            // Only so much can be spent in the minutes left, on top of what the robots collect.
+            for resource in ORE..GEODE {
+                let spendable = self.useful[resource] * next.left;
+                let collected = next.robots[resource] * next.left.saturating_sub(1);
+                next.resources[resource] =
+                    next.resources[resource].min(spendable.saturating_sub(collected));
+            }
+            next_states.push((next, gain));
+        }
+
+        next_states
+    }
+
+    /// The minutes until `cost` can be paid, or `None` if no robot collects something it needs.
+    fn wait_for(cost: &[u32; 4], state: &State) -> Option<u32> {
+        (ORE..=GEODE).try_fold(0, |wait, resource| {
+            let missing = cost[resource].saturating_sub(state.resources[resource]);
+            if missing == 0 {
+                Some(wait)
+            } else if state.robots[resource] == 0 {
+                None
+            } else {
+                Some(wait.max(missing.div_ceil(state.robots[resource])))
+            }
+        })
+    }
+}
+
+/// Runs `evaluate` on every blueprint, spread over the available threads when there are threads.
+#[cfg(feature = "std")]
+fn evaluate_all<T, F>(blueprints: &[Blueprint], evaluate: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(&Blueprint) -> T + Sync,
+{
+    let threads = std::thread::available_parallelism().map_or(1, usize::from);
+    let chunk = blueprints.len().div_ceil(threads).max(1);
+
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = blueprints
+            .chunks(chunk)
+            .map(|chunk| scope.spawn(|| chunk.iter().map(&evaluate).collect::<Vec<_>>()))
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e))
+            })
+            .collect()
+    })
+}
+
+#[cfg(not(feature = "std"))]
+fn evaluate_all<T, F>(blueprints: &[Blueprint], evaluate: F) -> Vec<T>
+where
+    F: Fn(&Blueprint) -> T,
+{
+    blueprints.iter().map(evaluate).collect()
+}
+
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    let blueprints = parse_blueprints(file)?;
+    let quality = evaluate_all(&blueprints, |b| {
+        i64::from(b.id) * i64::from(b.max_geodes(24).0)
+    });
+
+    Ok(Solution::Integer(quality.iter().sum()))
+}
+
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    let blueprints = parse_blueprints(file)?;
+    let first = &blueprints[..blueprints.len().min(3)];
+    let geodes = evaluate_all(first, |b| i64::from(b.max_geodes(32).0));
+
+    Ok(Solution::Integer(geodes.iter().product()))
+}
+
+/// How much searching every blueprint took in both parts, to see how well the pruning works.
+pub fn search_stats(file: &str) -> Result<Vec<(String, Stats)>, Error> {
+    let blueprints = parse_blueprints(file)?;
+    let mut stats = vec![];
+
+    let first = &blueprints[..blueprints.len().min(3)];
+    for (searched, minutes) in [(&blueprints[..], 24), (first, 32)] {
+        let found = evaluate_all(searched, |b| b.max_geodes(minutes));
+        for (blueprint, (geodes, search)) in searched.iter().zip(found) {
+            stats.push((
+                format!(
+                    "blueprint {}, {} minutes ({} geodes)",
+                    blueprint.id, minutes, geodes
+                ),
+                search,
+            ));
+        }
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_data() -> String {
+        let path = std::env::current_dir()
+            .unwrap()
+            .join("input")
+            .join("day19_test.txt");
+        std::fs::read_to_string(&path).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let blueprints = parse_blueprints(&read_test_data()).unwrap();
+
+        assert_eq!(2, blueprints.len());
+        assert_eq!(2, blueprints[1].id);
+        assert_eq!([3, 0, 12, 0], blueprints[1].costs[GEODE]);
+        assert_eq!([3, 8, 12, u32::MAX], blueprints[1].useful);
+
+        assert!(parse_blueprints("Blueprint 1: Each ore robot costs 4 ore.").is_err());
+        assert!(parse_blueprints("\n").is_err());
+        assert!(parse_blueprints(
+            "Blueprint 1: Each ore robot costs 0 ore. Each clay robot costs 2 ore. \
+             Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian."
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_max_geodes() {
+        let blueprints = parse_blueprints(&read_test_data()).unwrap();
+        let (geodes, stats) = blueprints[0].max_geodes(24);
+
+        assert_eq!(9, geodes);
+        // Keeps an eye on the pruning, so a change that weakens it shows up here.
+        assert!(stats.expanded < 30_000, "{stats:?}");
+        assert_eq!(12, blueprints[1].max_geodes(24).0);
+    }
+
+    #[test]
+    fn test_search_stats() {
+        let stats = search_stats(&read_test_data()).unwrap();
+
+        assert_eq!(4, stats.len());
+        assert_eq!("blueprint 1, 24 minutes (9 geodes)", stats[0].0);
+        assert_eq!("blueprint 2, 32 minutes (62 geodes)", stats[3].0);
+        assert!(stats.iter().all(|(_, s)| s.expanded > 0));
+    }
+
+    #[test]
+    fn test_part_a() {
+        assert_eq!(Solution::Integer(33), part_a(&read_test_data()).unwrap());
+    }
+
+    #[test]
+    fn test_part_b() {
+        assert_eq!(
+            Solution::Integer(56 * 62),
+            part_b(&read_test_data()).unwrap()
+        );
+        // An empty product would be 1, which isn't an answer to anything.
+        assert!(part_b("").is_err());
+    }
+}
@@ -0,0 +1,250 @@
+use crate::error::Error;
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+const DECRYPTION_KEY: i64 = 811_589_153;
+
+fn parse_numbers(file: &str) -> Result<Vec<i64>, Error> {
+    let numbers = file
+        .lines()
+        .filter(|line| !line.is_empty())
+        // Read as `i32` so the decrypted numbers and their sums can't overflow.
+        .map(|line| Ok(i64::from(line.trim().parse::<i32>()?)))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    if !numbers.contains(&0) {
+        return Err(Error::input("No 0 to count from"));
+    }
+    Ok(numbers)
+}
+
+/// The order of the numbers, by their place in the input so equal numbers stay apart, kept as a
+/// list of short blocks. Moving a number only shifts the block it leaves and the one it joins,
+/// and finding it only takes a look at its block, rather than shifting half the list every time.
+struct Ring {
+    blocks: Vec<Vec<usize>>,
+    /// The block each number is in.
+    block_of: Vec<usize>,
+    block_size: usize,
+    /// Moves since the blocks were last evened out.
+    moves: usize,
+}
+
+impl Ring {
+    fn new(len: usize) -> Self {
+        let mut ring = Self {
+            blocks: vec![(0..len).collect()],
+            block_of: vec![0; len],
+            block_size: len.isqrt().max(1),
+            moves: 0,
+        };
+        ring.rebalance();
+        ring
+    }
+
+    const fn len(&self) -> usize {
+        self.block_of.len()
+    }
+
+    /// Splits the order back into blocks of the same size, as moves make some grow and others
+    /// shrink.
+    fn rebalance(&mut self) {
+        let order = self.order();
+        self.blocks = order
+            .chunks(self.block_size)
+            .map(<[usize]>::to_vec)
+            .collect();
+        for (b, block) in self.blocks.iter().enumerate() {
+            for &number in block {
+                self.block_of[number] = b;
+            }
+        }
+        self.moves = 0;
+    }
+
+    /// The numbers in their current order.
+    fn order(&self) -> Vec<usize> {
+        self.blocks.concat()
+    }
+
+    /// Moves `number` forwards by `shift` places, backwards if negative, round the ring.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_sign_loss
+    )]
+    fn shift(&mut self, number: usize, shift: i64) {
+        let others = self.len() - 1;
+        if others == 0 {
+            return;
+        }
+
+        let b = self.block_of[number];
+        let i = self.blocks[b]
+            .iter()
+            .position(|&n| n == number)
+            .unwrap_or_default();
+        let from = self.blocks[..b].iter().map(Vec::len).sum::<usize>() + i;
+        self.blocks[b].remove(i);
+
+        let to = (from as i64 + shift.rem_euclid(others as i64)) as usize % others;
+        let mut before = 0;
+        for (b, block) in self.blocks.iter_mut().enumerate() {
+            if to <= before + block.len() {
+                block.insert(to - before, number);
+                self.block_of[number] = b;
+                break;
+            }
+            before += block.len();
+        }
+
+        self.moves += 1;
+        if self.moves >= self.block_size {
+            self.rebalance();
+        }
+    }
+}
+
+/// The sum of the numbers 1000, 2000 and 3000 places after the 0, once the list is mixed `rounds`
+/// times.
+fn grove_coordinates(numbers: &[i64], rounds: usize) -> i64 {
+    let mut ring = Ring::new(numbers.len());
+    for _ in 0..rounds {
+        for (number, &shift) in numbers.iter().enumerate() {
+            ring.shift(number, shift);
+        }
+    }
+
+    coordinates(numbers, &ring.order())
+}
+
+fn coordinates(numbers: &[i64], order: &[usize]) -> i64 {
+    let zero = order
+        .iter()
+        .position(|&n| numbers[n] == 0)
+        .unwrap_or_default();
+
+    [1000, 2000, 3000]
+        .iter()
+        .map(|offset| numbers[order[(zero + offset) % order.len()]])
+        .sum()
+}
+
+/// Naive reference for `Ring`: a plain list, shifting everything between where a number leaves
+/// and where it goes.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::cast_sign_loss
+)]
+fn grove_coordinates_naive(numbers: &[i64], rounds: usize) -> i64 {
+    let mut order: Vec<usize> = (0..numbers.len()).collect();
+    let others = numbers.len() as i64 - 1;
+
+    for _ in 0..rounds {
+        for (number, &shift) in numbers.iter().enumerate() {
+            if others == 0 {
+                break;
+            }
+            let from = order.iter().position(|&n| n == number).unwrap_or_default();
+            order.remove(from);
+            let to = (from as i64 + shift).rem_euclid(others) as usize;
+            order.insert(to, number);
+        }
+    }
+
+    coordinates(numbers, &order)
+}
+
+fn decrypt(numbers: &[i64]) -> Vec<i64> {
+    numbers.iter().map(|n| n * DECRYPTION_KEY).collect()
+}
+
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(grove_coordinates(
+        &parse_numbers(file)?,
+        1,
+    )))
+}
+
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(grove_coordinates(
+        &decrypt(&parse_numbers(file)?),
+        10,
+    )))
+}
+
+pub fn reference_part_a(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(grove_coordinates_naive(
+        &parse_numbers(file)?,
+        1,
+    )))
+}
+
+pub fn reference_part_b(file: &str) -> Result<Solution, Error> {
+    Ok(Solution::Integer(grove_coordinates_naive(
+        &decrypt(&parse_numbers(file)?),
+        10,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "1\n2\n-3\n3\n-2\n0\n4\n";
+
+    #[test]
+    fn test_mix_once() {
+        let numbers = parse_numbers(TEST_INPUT).unwrap();
+        let mut ring = Ring::new(numbers.len());
+        for (number, &shift) in numbers.iter().enumerate() {
+            ring.shift(number, shift);
+        }
+
+        // The puzzle prints the mixed list starting from wherever, so compare from the 1.
+        let mixed: Vec<i64> = ring.order().iter().map(|&n| numbers[n]).collect();
+        let one = mixed.iter().position(|&n| n == 1).unwrap();
+        let mut from_one = mixed[one..].to_vec();
+        from_one.extend(&mixed[..one]);
+        assert_eq!(vec![1, 2, -3, 4, 0, 3, -2], from_one);
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let numbers = vec![0, 2, 2, -1, 2, 5, -7];
+
+        for rounds in 1..4 {
+            assert_eq!(
+                grove_coordinates_naive(&numbers, rounds),
+                grove_coordinates(&numbers, rounds)
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_numbers("1\n2\n").is_err());
+        assert!(parse_numbers("1\nx\n0\n").is_err());
+        assert!(part_b("0\n99999999999999\n").is_err());
+        assert_eq!(Solution::Integer(0), part_b("0\n").unwrap());
+    }
+
+    #[test]
+    fn test_part_a() {
+        assert_eq!(Solution::Integer(3), part_a(TEST_INPUT).unwrap());
+        assert_eq!(Solution::Integer(3), reference_part_a(TEST_INPUT).unwrap());
+    }
+
+    #[test]
+    fn test_part_b() {
+        assert_eq!(
+            Solution::Integer(1_623_178_306),
+            part_b(TEST_INPUT).unwrap()
+        );
+        assert_eq!(
+            Solution::Integer(1_623_178_306),
+            reference_part_b(TEST_INPUT).unwrap()
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use alloc::collections::VecDeque;
+
+use hashbrown::HashMap;
+
+use crate::error::Error;
+use crate::prelude::*;
+use crate::rational::Rational;
+use crate::solutions::Solution;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Operation {
+    fn apply(self, a: Rational, b: Rational) -> Result<Rational, Error> {
+        match self {
+            Self::Add => a.checked_add(b),
+            Self::Subtract => a.checked_sub(b),
+            Self::Multiply => a.checked_mul(b),
+            Self::Divide => a.checked_div(b),
+        }
+        .ok_or_else(|| Error::input("Overflow or division by zero"))
+    }
+
+    /// The left operand that makes `self` give `result` with `right` as the right operand.
+    fn solve_left(self, result: Rational, right: Rational) -> Result<Rational, Error> {
+        match self {
+            Self::Add => Self::Subtract.apply(result, right),
+            Self::Subtract => Self::Add.apply(result, right),
+            Self::Multiply => Self::Divide.apply(result, right),
+            Self::Divide => Self::Multiply.apply(result, right),
+        }
+    }
+
+    /// The right operand that makes `self` give `result` with `left` as the left operand.
+    fn solve_right(self, left: Rational, result: Rational) -> Result<Rational, Error> {
+        match self {
+            Self::Add => Self::Subtract.apply(result, left),
+            Self::Subtract => Self::Subtract.apply(left, result),
+            Self::Multiply => Self::Divide.apply(result, left),
+            Self::Divide => Self::Divide.apply(left, result),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Job {
+    Number(i64),
+    /// An operation on the monkeys at two indices.
+    Operation(usize, Operation, usize),
+}
+
+/// The monkeys' jobs, as a graph of expressions where a monkey may be shared by several others.
+#[derive(Debug)]
+struct Troop {
+    jobs: Vec<Job>,
+    root: usize,
+    human: usize,
+    /// Every monkey after the monkeys it listens to.
+    order: Vec<usize>,
+}
+
+impl Troop {
+    fn parse(file: &str) -> Result<Self, Error> {
+        let lines = file
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                line.split_once(": ")
+                    .ok_or_else(|| Error::input("Expected a monkey name and a job"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let index: HashMap<&str, usize> = lines
+            .iter()
+            .enumerate()
+            .map(|(i, &(name, _))| (name, i))
+            .collect();
+        if index.len() != lines.len() {
+            return Err(Error::input("Monkey listed twice"));
+        }
+        let monkey = |name: &str| {
+            index
+                .get(name)
+                .copied()
+                .ok_or_else(|| Error::input("Unknown monkey"))
+        };
+
+        let jobs = lines
+            .iter()
+            .map(|&(_, job)| {
+                let parts: Vec<&str> = job.split(' ').collect();
+                match parts[..] {
+                    [number] => Ok(Job::Number(number.parse()?)),
+                    [a, op, b] => {
+                        let op = match op {
+                            "+" => Operation::Add,
+                            "-" => Operation::Subtract,
+                            "*" => Operation::Multiply,
+                            "/" => Operation::Divide,
+                            _ => return Err(Error::input("Unknown operation")),
+                        };
+                        Ok(Job::Operation(monkey(a)?, op, monkey(b)?))
+                    }
+                    _ => Err(Error::input("Expected a number or an operation")),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let order = topological_order(&jobs)?;
+        Ok(Self {
+            jobs,
+            root: monkey("root")?,
+            human: monkey("humn")?,
+            order,
+        })
+    }
+
+    /// The number every monkey yells.
+    fn evaluate(&self) -> Result<Vec<Rational>, Error> {
+        self.evaluate_known(&vec![false; self.jobs.len()])
+    }
+
+    /// The number every monkey yells, except those marked `unknown`, which are left at zero and
+    /// never worked out.
+    fn evaluate_known(&self, unknown: &[bool]) -> Result<Vec<Rational>, Error> {
+        let mut values = vec![Rational::ZERO; self.jobs.len()];
+
+        for &monkey in self.order.iter().filter(|&&m| !unknown[m]) {
+            values[monkey] = match self.jobs[monkey] {
+                Job::Number(n) => Rational::from(n),
+                Job::Operation(a, op, b) => op.apply(values[a], values[b])?,
+            };
+        }
+
+        Ok(values)
+    }
+
+    /// Whether each monkey's number depends on the human's.
+    fn depends_on_human(&self) -> Vec<bool> {
+        let mut depends = vec![false; self.jobs.len()];
+        depends[self.human] = true;
+
+        for &monkey in &self.order {
+            if let Job::Operation(a, _, b) = self.jobs[monkey] {
+                depends[monkey] |= depends[a] || depends[b];
+            }
+        }
+
+        depends
+    }
+
+    /// The number the human must yell for both of root's monkeys to yell the same.
+    ///
+    /// Everything not depending on the human is worked out as it is, while the number the human is
+    /// listed with is never used. Then, from root down to the human, each operation is undone:
+    /// knowing what it must give and its other operand tells what the operand depending on the
+    /// human must be.
+    fn solve_for_human(&self) -> Result<Rational, Error> {
+        let depends = self.depends_on_human();
+        let values = self.evaluate_known(&depends)?;
+
+        let Job::Operation(a, _, b) = self.jobs[self.root] else {
+            return Err(Error::input("Root has no monkeys to compare"));
+        };
+        let (mut monkey, mut target) = match (depends[a], depends[b]) {
+            (true, false) => (a, values[b]),
+            (false, true) => (b, values[a]),
+            _ => {
+                return Err(Error::input(
+                    "The human must be on exactly one side of root",
+                ))
+            }
+        };
+
+        while monkey != self.human {
+            let Job::Operation(a, op, b) = self.jobs[monkey] else {
+                return Err(Error::input("The human's number doesn't reach root"));
+            };
+            (monkey, target) = match (depends[a], depends[b]) {
+                (true, false) => (a, op.solve_left(target, values[b])?),
+                (false, true) => (b, op.solve_right(values[a], target)?),
+                _ => return Err(Error::input("The human's number is used more than once")),
+            };
+        }
+
+        Ok(target)
+    }
+}
+
+/// The monkeys ordered so that every monkey comes after those it listens to, found without
+/// recursion so long chains can't overflow the stack.
+fn topological_order(jobs: &[Job]) -> Result<Vec<usize>, Error> {
+    let mut waiting_on = vec![0; jobs.len()];
+    let mut listeners = vec![vec![]; jobs.len()];
+    for (monkey, job) in jobs.iter().enumerate() {
+        if let &Job::Operation(a, _, b) = job {
+            waiting_on[monkey] = 2;
+            listeners[a].push(monkey);
+            listeners[b].push(monkey);
+        }
+    }
+
+    let mut ready: VecDeque<usize> = (0..jobs.len()).filter(|&m| waiting_on[m] == 0).collect();
+    let mut order = vec![];
+    while let Some(monkey) = ready.pop_front() {
+        order.push(monkey);
+        for &listener in &listeners[monkey] {
+            waiting_on[listener] -= 1;
+            if waiting_on[listener] == 0 {
+                ready.push_back(listener);
+            }
+        }
+    }
+
+    if order.len() == jobs.len() {
+        Ok(order)
+    } else {
+        Err(Error::input("Monkeys wait on each other in a loop"))
+    }
+}
+
+fn integer(value: Rational) -> Result<Solution, Error> {
+    value
+        .to_integer()
+        .and_then(|n| i64::try_from(n).ok())
+        .map(Solution::Integer)
+        .ok_or_else(|| Error::InputError(format!("{value} isn't a whole number")))
+}
+
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    let troop = Troop::parse(file)?;
+    integer(troop.evaluate()?[troop.root])
+}
+
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    integer(Troop::parse(file)?.solve_for_human()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_data() -> String {
+        let path = std::env::current_dir()
+            .unwrap()
+            .join("input")
+            .join("day21_test.txt");
+        std::fs::read_to_string(&path).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let troop = Troop::parse(&read_test_data()).unwrap();
+
+        assert_eq!(15, troop.jobs.len());
+        assert_eq!(Job::Number(5), troop.jobs[troop.human]);
+
+        assert!(Troop::parse("root: a + b\na: 1\n").is_err());
+        assert!(Troop::parse("root: a % b\na: 1\nb: 2\nhumn: 3\n").is_err());
+        assert!(Troop::parse("root: a + b\na: b * b\nb: a - a\nhumn: 3\n").is_err());
+        assert!(Troop::parse("root: 1\nroot: 2\nhumn: 3\n").is_err());
+    }
+
+    #[test]
+    fn test_exact_division() {
+        let file = "root: a + humn\na: b / c\nb: 7\nc: 2\nhumn: 1\n";
+
+        assert!(part_a(file).is_err());
+        assert_eq!(
+            Solution::Integer(3),
+            part_a("root: a * c\na: b / c\nb: 3\nc: 2\nhumn: 1\n").unwrap()
+        );
+        assert_eq!(
+            Solution::Integer(3),
+            part_b("root: a - humn\na: b / c\nb: 6\nc: 2\nhumn: 1\n").unwrap()
+        );
+        assert!(part_b(file).is_err());
+        assert!(part_a("root: a / b\na: 1\nb: 0\nhumn: 1\n").is_err());
+    }
+
+    #[test]
+    fn test_listed_human_number_is_ignored() {
+        // With the listed 3, `d` is 0 and `a` divides by it, but the human's number is
+        // what's being solved for.
+        let file = "root: a + b\na: c / d\nb: 2\nc: 8\nd: humn - e\ne: 3\nhumn: 3\n";
+
+        assert!(part_a(file).is_err());
+        assert_eq!(Solution::Integer(7), part_b(file).unwrap());
+    }
+
+    #[test]
+    fn test_human_on_both_sides() {
+        assert!(part_b("root: a + humn\na: humn * humn\nhumn: 1\n").is_err());
+    }
+
+    #[test]
+    fn test_part_a() {
+        assert_eq!(Solution::Integer(152), part_a(&read_test_data()).unwrap());
+    }
+
+    #[test]
+    fn test_part_b() {
+        assert_eq!(Solution::Integer(301), part_b(&read_test_data()).unwrap());
+    }
+}
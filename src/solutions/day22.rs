@@ -0,0 +1,497 @@
+use crate::error::Error;
+use crate::geometry::{Direction, Point};
+use crate::grid::{Grid, Pos};
+use crate::parse::{self, Cursor};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+/// The most cells the padded board may have, so a ragged input can't ask for a huge grid.
+const MAX_CELLS: usize = 4_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tile {
+    /// Off the map, where the lines are padded with spaces.
+    Void,
+    Open,
+    Wall,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Move {
+    Forward(u32),
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct State {
+    pos: Pos,
+    facing: Direction,
+}
+
+impl State {
+    const fn password(self) -> usize {
+        let facing = match self.facing {
+            Direction::Right => 0,
+            Direction::Down => 1,
+            Direction::Left => 2,
+            Direction::Up => 3,
+        };
+        1000 * (self.pos.1 + 1) + 4 * (self.pos.0 + 1) + facing
+    }
+}
+
+#[derive(Debug)]
+struct Notes {
+    board: Grid<Tile>,
+    path: Vec<Move>,
+}
+
+fn parse_notes(file: &str) -> Result<Notes, Error> {
+    let blocks: Vec<&str> = parse::blocks(file).collect();
+    let [board, path] = blocks[..] else {
+        return Err(Error::input("Expected a board and a path"));
+    };
+
+    let width = board.lines().map(str::len).max().unwrap_or_default();
+    let height = board.lines().count();
+    if width.saturating_mul(height) > MAX_CELLS {
+        return Err(Error::input("Board too large"));
+    }
+
+    let mut grid = Grid::filled(width, height, Tile::Void);
+    for (y, line) in board.lines().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            grid[(x, y)] = match c {
+                ' ' => Tile::Void,
+                '.' => Tile::Open,
+                '#' => Tile::Wall,
+                _ => return Err(Error::InputError(format!("Unknown tile {c:?}"))),
+            };
+        }
+    }
+
+    Ok(Notes {
+        board: grid,
+        path: parse_path(path.trim())?,
+    })
+}
+
+fn parse_path(line: &str) -> Result<Vec<Move>, Error> {
+    let mut cursor = Cursor::new(line);
+    let mut path = vec![];
+
+    while let Some(c) = cursor.peek() {
+        if c.is_ascii_digit() {
+            path.push(Move::Forward(cursor.integer()?));
+            continue;
+        }
+
+        path.push(match c {
+            'L' => Move::Left,
+            'R' => Move::Right,
+            _ => return Err(cursor.error("Expected a number of steps or a turn")),
+        });
+        cursor.advance();
+    }
+
+    Ok(path)
+}
+
+/// What happens when a step would leave the map: where it comes back on, and facing which way.
+trait Wrap {
+    fn wrap(&self, board: &Grid<Tile>, state: State) -> State;
+}
+
+/// Comes back on the far side of the same row or column, as if the board were flat and rolled.
+struct Flat;
+
+impl Wrap for Flat {
+    fn wrap(&self, board: &Grid<Tile>, state: State) -> State {
+        let back = state.facing.reverse().vec();
+        let mut pos = state.pos;
+
+        // Walk backwards to the last tile before the map runs out the other way.
+        while let Some(prev) = (Point::from(pos) + back)
+            .to_pos()
+            .filter(|&prev| board.get(prev).is_some_and(|&tile| tile != Tile::Void))
+        {
+            pos = prev;
+        }
+
+        State { pos, ..state }
+    }
+}
+
+type Vec3 = [i64; 3];
+
+const fn dot(a: Vec3, b: Vec3) -> i64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+const fn add(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+const fn scale(a: Vec3, k: i64) -> Vec3 {
+    [a[0] * k, a[1] * k, a[2] * k]
+}
+
+/// A face of the cube: where it is on the board, and how it sits in space, with `right` and
+/// `down` the directions its own right and down point in once the net is folded.
+#[derive(Clone, Copy, Debug)]
+struct Face {
+    corner: Pos,
+    normal: Vec3,
+    right: Vec3,
+    down: Vec3,
+}
+
+impl Face {
+    const fn direction(&self, facing: Direction) -> Vec3 {
+        match facing {
+            Direction::Right => self.right,
+            Direction::Down => self.down,
+            Direction::Left => scale(self.right, -1),
+            Direction::Up => scale(self.down, -1),
+        }
+    }
+
+    /// The face next to this one in the net in `facing`, folded along their shared edge.
+    const fn fold(&self, facing: Direction, corner: Pos) -> Self {
+        let direction = self.direction(facing);
+        let away = scale(self.normal, -1);
+        let (right, down) = match facing {
+            Direction::Right | Direction::Left => {
+                (scale(away, dot(direction, self.right)), self.down)
+            }
+            Direction::Down | Direction::Up => (self.right, scale(away, dot(direction, self.down))),
+        };
+
+        Self {
+            corner,
+            normal: direction,
+            right,
+            down,
+        }
+    }
+}
+
+/// Comes back on wherever the edge of the map meets once the net is folded into a cube.
+///
+/// Each face is given its place in space by folding outwards from the first one over the edges
+/// the net shows joined. Positions on the cube are then taken as points in space, at twice the
+/// scale so the middles of the cells are whole, with the cube centred on the origin.
+struct Cube {
+    side: usize,
+    faces: Vec<Face>,
+}
+
+impl Cube {
+    #[allow(clippy::cast_possible_wrap)]
+    fn fold(board: &Grid<Tile>) -> Result<Self, Error> {
+        let tiles = board.iter().filter(|&(_, &t)| t != Tile::Void).count();
+        let side = (tiles / 6).isqrt();
+        if side == 0 || 6 * side * side != tiles {
+            return Err(Error::input("The map doesn't have the area of a cube"));
+        }
+
+        let blocks = board
+            .positions()
+            .filter(|&(x, y)| x % side == 0 && y % side == 0);
+        let mut corners = vec![];
+        for corner in blocks {
+            let on_map = (0..side)
+                .flat_map(|dy| (0..side).map(move |dx| (corner.0 + dx, corner.1 + dy)))
+                .filter(|&pos| board.get(pos).is_some_and(|&t| t != Tile::Void))
+                .count();
+            if on_map == side * side {
+                corners.push(corner);
+            } else if on_map != 0 {
+                return Err(Error::input("The map doesn't split into square faces"));
+            }
+        }
+
+        let mut faces = vec![Face {
+            corner: corners[0],
+            normal: [0, 0, -1],
+            right: [1, 0, 0],
+            down: [0, 1, 0],
+        }];
+        let mut next = 0;
+        while let Some(&face) = faces.get(next) {
+            next += 1;
+            for facing in Direction::ALL {
+                let corner = (Point::from(face.corner) + facing.vec() * side as i64).to_pos();
+                if let Some(corner) = corner.filter(|c| corners.contains(c)) {
+                    if faces.iter().all(|f| f.corner != corner) {
+                        faces.push(face.fold(facing, corner));
+                    }
+                }
+            }
+        }
+
+        for (i, face) in faces.iter().enumerate() {
+            if faces[..i].iter().any(|f| f.normal == face.normal) {
+                return Err(Error::input("The net folds two faces onto one"));
+            }
+        }
+        if faces.len() != corners.len() {
+            return Err(Error::input("The faces aren't all joined"));
+        }
+
+        Ok(Self { side, faces })
+    }
+
+    /// The cell at `pos` on `face`, as a point in space.
+    #[allow(clippy::cast_possible_wrap)]
+    const fn to_space(&self, face: &Face, pos: Pos) -> Vec3 {
+        let side = self.side as i64;
+        let x = (pos.0 - face.corner.0) as i64;
+        let y = (pos.1 - face.corner.1) as i64;
+
+        add(
+            scale(face.normal, side),
+            add(
+                scale(face.right, 2 * x + 1 - side),
+                scale(face.down, 2 * y + 1 - side),
+            ),
+        )
+    }
+
+    /// The cell at `point` on `face`, back on the board.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_sign_loss
+    )]
+    const fn to_board(&self, face: &Face, point: Vec3) -> Pos {
+        let side = self.side as i64;
+        let x = (dot(point, face.right) + side - 1) / 2;
+        let y = (dot(point, face.down) + side - 1) / 2;
+
+        (face.corner.0 + x as usize, face.corner.1 + y as usize)
+    }
+}
+
+impl Wrap for Cube {
+    fn wrap(&self, _: &Grid<Tile>, state: State) -> State {
+        let (x, y) = state.pos;
+        let corner = (x - x % self.side, y - y % self.side);
+        let Some(from) = self.faces.iter().find(|f| f.corner == corner) else {
+            return state;
+        };
+
+        // Over the edge, the point is one step further along the way it was going and one step
+        // in from the face it left, and it goes on straight down the new face.
+        let direction = from.direction(state.facing);
+        let point = add(
+            self.to_space(from, state.pos),
+            add(direction, scale(from.normal, -1)),
+        );
+        let heading = scale(from.normal, -1);
+
+        let Some(to) = self.faces.iter().find(|f| f.normal == direction) else {
+            return state;
+        };
+        let facing = Direction::ALL
+            .into_iter()
+            .find(|&d| to.direction(d) == heading)
+            .unwrap_or(state.facing);
+
+        State {
+            pos: self.to_board(to, point),
+            facing,
+        }
+    }
+}
+
+impl Notes {
+    /// Where the path ends, starting from the leftmost open tile of the top row facing right.
+    fn follow(&self, wrap: &impl Wrap) -> Result<State, Error> {
+        let start = (0..self.board.width())
+            .map(|x| (x, 0))
+            .find(|&pos| self.board[pos] == Tile::Open)
+            .ok_or_else(|| Error::input("No open tile on the top row"))?;
+
+        let mut state = State {
+            pos: start,
+            facing: Direction::Right,
+        };
+        for &step in &self.path {
+            state = match step {
+                Move::Left => State {
+                    facing: state.facing.turn_left(),
+                    ..state
+                },
+                Move::Right => State {
+                    facing: state.facing.turn_right(),
+                    ..state
+                },
+                Move::Forward(steps) => self.forward(state, steps, wrap),
+            };
+        }
+
+        Ok(state)
+    }
+
+    /// Goes up to `steps` tiles forward, stopping at a wall.
+    fn forward(&self, mut state: State, steps: u32, wrap: &impl Wrap) -> State {
+        let start = state;
+        let mut left = steps;
+        let mut taken = 0;
+
+        while left > 0 {
+            let ahead = (Point::from(state.pos) + state.facing.vec())
+                .to_pos()
+                .filter(|&pos| self.board.get(pos).is_some_and(|&t| t != Tile::Void));
+            let next = ahead.map_or_else(
+                || wrap.wrap(&self.board, state),
+                |pos| State { pos, ..state },
+            );
+            if self.board.get(next.pos) != Some(&Tile::Open) {
+                break;
+            }
+
+            state = next;
+            left -= 1;
+            taken += 1;
+            // Back where it began, so the rest of the way goes round the same loop.
+            if state == start {
+                left %= taken;
+            }
+        }
+
+        state
+    }
+}
+
+#[allow(clippy::cast_possible_wrap)]
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    let notes = parse_notes(file)?;
+    let end = notes.follow(&Flat)?;
+    Ok(Solution::Integer(end.password() as i64))
+}
+
+#[allow(clippy::cast_possible_wrap)]
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    let notes = parse_notes(file)?;
+    let cube = Cube::fold(&notes.board)?;
+    let end = notes.follow(&cube)?;
+    Ok(Solution::Integer(end.password() as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_test_data() -> String {
+        let path = std::env::current_dir()
+            .unwrap()
+            .join("input")
+            .join("day22_test.txt");
+        std::fs::read_to_string(&path).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let notes = parse_notes(&read_test_data()).unwrap();
+
+        assert_eq!((16, 12), (notes.board.width(), notes.board.height()));
+        assert_eq!(Tile::Void, notes.board[(0, 0)]);
+        assert_eq!(Tile::Wall, notes.board[(11, 0)]);
+        assert_eq!(
+            vec![Move::Forward(10), Move::Right, Move::Forward(5), Move::Left],
+            notes.path[..4]
+        );
+
+        assert!(parse_notes("..#\n").is_err());
+        assert!(parse_notes("..x\n\n10R\n").is_err());
+        assert!(parse_notes("...\n\n10X\n").is_err());
+    }
+
+    /// Checks that stepping off every edge of every face, then turning round and stepping back,
+    /// lands where it started, and that edges already joined in the net stay joined.
+    fn assert_edges_join(board: &Grid<Tile>) -> Cube {
+        let cube = Cube::fold(board).unwrap();
+        let step = |state: State| {
+            (Point::from(state.pos) + state.facing.vec())
+                .to_pos()
+                .filter(|&p| board.get(p).is_some_and(|&t| t != Tile::Void))
+        };
+
+        for face in &cube.faces {
+            for i in 0..cube.side {
+                for facing in Direction::ALL {
+                    let (x, y) = face.corner;
+                    let pos = match facing {
+                        Direction::Up => (x + i, y),
+                        Direction::Down => (x + i, y + cube.side - 1),
+                        Direction::Left => (x, y + i),
+                        Direction::Right => (x + cube.side - 1, y + i),
+                    };
+                    let start = State { pos, facing };
+
+                    let over = cube.wrap(board, start);
+                    if let Some(next) = step(start) {
+                        assert_eq!(State { pos: next, facing }, over);
+                    }
+                    let back = State {
+                        facing: over.facing.reverse(),
+                        ..over
+                    };
+                    let returned = step(back).unwrap_or_else(|| cube.wrap(board, back).pos);
+                    assert_eq!(pos, returned);
+                }
+            }
+        }
+
+        cube
+    }
+
+    #[test]
+    fn test_fold() {
+        let notes = parse_notes(&read_test_data()).unwrap();
+        let cube = assert_edges_join(&notes.board);
+        assert_eq!(4, cube.side);
+        assert_eq!(6, cube.faces.len());
+
+        // The shape of the real inputs, which is folded differently from the example.
+        let net = [".##", ".#.", "##.", "#.."];
+        let mut board = String::new();
+        for row in net {
+            let line: String = row
+                .chars()
+                .map(|c| {
+                    if c == '#' {
+                        ".".repeat(50)
+                    } else {
+                        " ".repeat(50)
+                    }
+                })
+                .collect();
+            board.push_str(&format!("{}\n", line).repeat(50));
+        }
+        let notes = parse_notes(&format!("{}\n10R5\n", board)).unwrap();
+        assert_eq!(50, assert_edges_join(&notes.board).side);
+
+        assert!(Cube::fold(&parse_notes("..\n..\n\n1\n").unwrap().board).is_err());
+        assert!(Cube::fold(&parse_notes("......\n\n1\n").unwrap().board).is_err());
+    }
+
+    #[test]
+    fn test_long_walks() {
+        let notes = parse_notes("...\n\n4000000000\n").unwrap();
+        assert_eq!((1, 0), notes.follow(&Flat).unwrap().pos);
+    }
+
+    #[test]
+    fn test_part_a() {
+        assert_eq!(Solution::Integer(6032), part_a(&read_test_data()).unwrap());
+    }
+
+    #[test]
+    fn test_part_b() {
+        assert_eq!(Solution::Integer(5031), part_b(&read_test_data()).unwrap());
+    }
+}
@@ -0,0 +1,243 @@
+use core::fmt;
+
+use crate::error::Error;
+use crate::geometry::{BoundingBox, Point};
+use crate::grid::{Grid, Pos, ALL_AROUND};
+use crate::prelude::*;
+use crate::solutions::Solution;
+
+/// Rounds to give up after, in case the elves never settle.
+const MAX_ROUNDS: usize = 20_000;
+
+/// The directions the elves consider, in the order of the first round: each as the neighbours it
+/// needs empty, by their index in `ALL_AROUND`, and the step to take.
+const DIRECTIONS: [([usize; 3], (isize, isize)); 4] = [
+    ([0, 1, 2], (0, -1)),
+    ([4, 5, 6], (0, 1)),
+    ([6, 7, 0], (-1, 0)),
+    ([2, 3, 4], (1, 0)),
+];
+
+/// The elves on a grid with room around them, which is grown whenever they get near its edge so
+/// every elf always has all eight neighbours inside it.
+#[derive(Clone, Debug)]
+struct Grove {
+    occupied: Grid<bool>,
+    elves: Vec<Pos>,
+    /// How many elves propose each tile, kept all zero between rounds.
+    proposals: Grid<u8>,
+    /// The index in `DIRECTIONS` considered first this round.
+    first: usize,
+}
+
+fn parse_grove(file: &str) -> Result<Grove, Error> {
+    let occupied = Grid::parse(file.trim_end(), |c| match c {
+        '#' => Ok(true),
+        '.' => Ok(false),
+        _ => Err(Error::input("Expected # or .")),
+    })?;
+
+    let elves: Vec<Pos> = occupied
+        .iter()
+        .filter_map(|(pos, &elf)| elf.then_some(pos))
+        .collect();
+    if elves.is_empty() {
+        return Err(Error::input("No elves"));
+    }
+
+    Ok(Grove::new(&elves))
+}
+
+const fn step(pos: Pos, (dx, dy): (isize, isize)) -> Pos {
+    // Every elf has room around it, so this stays inside the grid.
+    (pos.0.wrapping_add_signed(dx), pos.1.wrapping_add_signed(dy))
+}
+
+impl Grove {
+    fn new(elves: &[Pos]) -> Self {
+        let mut grove = Self {
+            occupied: Grid::filled(0, 0, false),
+            elves: elves.to_vec(),
+            proposals: Grid::filled(0, 0, 0),
+            first: 0,
+        };
+        grove.make_room();
+        grove
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::of(self.elves.iter().map(|&pos| pos.into())).expect("A grove always has elves")
+    }
+
+    /// Moves everything onto a new grid with a margin around the elves as wide as the spread of
+    /// the elves, so growing it again is rarely needed.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn make_room(&mut self) {
+        let bounds = self.bounds();
+        let margin = (bounds.width().max(bounds.height()) as usize / 2).max(8);
+        let width = bounds.width() as usize + 2 * margin;
+        let height = bounds.height() as usize + 2 * margin;
+
+        let (left, top) = (bounds.min.x as usize, bounds.min.y as usize);
+        for elf in &mut self.elves {
+            *elf = (elf.0 - left + margin, elf.1 - top + margin);
+        }
+
+        self.occupied = Grid::filled(width, height, false);
+        for &elf in &self.elves {
+            self.occupied[elf] = true;
+        }
+        self.proposals = Grid::filled(width, height, 0);
+    }
+
+    fn near_edge(&self) -> bool {
+        let (width, height) = (self.occupied.width(), self.occupied.height());
+        self.elves
+            .iter()
+            .any(|&(x, y)| x < 2 || y < 2 || x + 2 >= width || y + 2 >= height)
+    }
+
+    /// Where the elf at `elf` wants to go, if anywhere.
+    fn propose(&self, elf: Pos) -> Option<Pos> {
+        let around = ALL_AROUND.map(|offset| self.occupied[step(elf, offset)]);
+        if !around.contains(&true) {
+            return None;
+        }
+
+        (0..DIRECTIONS.len())
+            .map(|i| DIRECTIONS[(self.first + i) % DIRECTIONS.len()])
+            .find(|(checks, _)| checks.iter().all(|&c| !around[c]))
+            .map(|(_, offset)| step(elf, offset))
+    }
+
+    /// Runs a round, and says how many elves moved.
+    fn round(&mut self) -> usize {
+        if self.near_edge() {
+            self.make_room();
+        }
+
+        let proposed: Vec<Option<Pos>> = self.elves.iter().map(|&elf| self.propose(elf)).collect();
+        for &to in proposed.iter().flatten() {
+            self.proposals[to] = self.proposals[to].saturating_add(1);
+        }
+
+        let mut moved = 0;
+        for (elf, to) in self.elves.iter_mut().zip(&proposed) {
+            if let &Some(to) = to {
+                if self.proposals[to] == 1 {
+                    self.occupied[*elf] = false;
+                    self.occupied[to] = true;
+                    *elf = to;
+                    moved += 1;
+                }
+            }
+        }
+
+        for &to in proposed.iter().flatten() {
+            self.proposals[to] = 0;
+        }
+        self.first = (self.first + 1) % DIRECTIONS.len();
+        moved
+    }
+
+    /// Empty ground tiles in the smallest rectangle holding every elf.
+    fn empty_ground(&self) -> u64 {
+        self.bounds().area() - self.elves.len() as u64
+    }
+}
+
+impl fmt::Display for Grove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bounds = self.bounds();
+        for y in bounds.min.y..=bounds.max.y {
+            for x in bounds.min.x..=bounds.max.x {
+                let pos = Point::new(x, y).to_pos().unwrap_or_default();
+                f.write_str(if self.occupied[pos] { "#" } else { "." })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[allow(clippy::cast_possible_wrap)]
+pub fn part_a(file: &str) -> Result<Solution, Error> {
+    let mut grove = parse_grove(file)?;
+    for _ in 0..10 {
+        grove.round();
+    }
+
+    Ok(Solution::Integer(grove.empty_ground() as i64))
+}
+
+#[allow(clippy::cast_possible_wrap)]
+pub fn part_b(file: &str) -> Result<Solution, Error> {
+    let mut grove = parse_grove(file)?;
+
+    for round in 1..=MAX_ROUNDS {
+        if grove.round() == 0 {
+            return Ok(Solution::Integer(round as i64));
+        }
+    }
+
+    Err(Error::input("The elves never stop moving"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "\
+....#..
+..###.#
+#...#.#
+.#...##
+#.###..
+##.#.##
+.#..#..
+";
+
+    #[test]
+    fn test_small_example() {
+        let mut grove = parse_grove(".....\n..##.\n..#..\n.....\n..##.\n.....\n").unwrap();
+
+        assert_eq!(3, grove.round());
+        assert_eq!("##\n..\n#.\n.#\n#.\n", grove.to_string());
+        assert_eq!(5, grove.round());
+        assert_eq!(".##.\n#...\n...#\n....\n.#..\n", grove.to_string());
+        assert_eq!(3, grove.round());
+        assert_eq!(
+            "..#..\n....#\n#....\n....#\n.....\n..#..\n",
+            grove.to_string()
+        );
+        assert_eq!(0, grove.round());
+    }
+
+    #[test]
+    fn test_grows_room() {
+        let mut grove = parse_grove("###\n###\n###\n").unwrap();
+        for _ in 0..100 {
+            grove.round();
+        }
+
+        assert_eq!(9, grove.elves.len());
+        assert_eq!(9, grove.occupied.iter().filter(|&(_, &elf)| elf).count());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_grove("..\n..\n").is_err());
+        assert!(parse_grove("#x\n").is_err());
+        assert!(parse_grove("#.\n#\n").is_err());
+    }
+
+    #[test]
+    fn test_part_a() {
+        assert_eq!(Solution::Integer(110), part_a(TEST_INPUT).unwrap());
+    }
+
+    #[test]
+    fn test_part_b() {
+        assert_eq!(Solution::Integer(20), part_b(TEST_INPUT).unwrap());
+    }
+}
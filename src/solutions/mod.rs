@@ -11,6 +11,21 @@ mod day10;
 mod day11;
 mod day12;
 mod day13;
+mod day14;
+mod day15;
+mod day16;
+mod day17;
+mod day18;
+mod day19;
+mod day20;
+mod day21;
+mod day22;
+mod day23;
+
+use crate::error::Error;
+use crate::prelude::*;
+use crate::render::Scene;
+use crate::search::Stats;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum Solution {
@@ -18,9 +33,9 @@ pub enum Solution {
     String(String),
 }
 
-pub type PuzzleSolution = Option<fn(&str) -> Solution>;
+pub type PuzzleSolution = Option<fn(&str) -> Result<Solution, Error>>;
 
-const DAYS: [(PuzzleSolution, PuzzleSolution); 13] = [
+const DAYS: [(PuzzleSolution, PuzzleSolution); 23] = [
     (Some(day01::part_a), Some(day01::part_b)),
     (Some(day02::part_a), Some(day02::part_b)),
     (Some(day03::part_a), Some(day03::part_b)),
@@ -34,6 +49,16 @@ const DAYS: [(PuzzleSolution, PuzzleSolution); 13] = [
     (Some(day11::part_a), Some(day11::part_b)),
     (Some(day12::part_a), Some(day12::part_b)),
     (Some(day13::part_a), Some(day13::part_b)),
+    (Some(day14::part_a), Some(day14::part_b)),
+    (Some(day15::part_a), Some(day15::part_b)),
+    (Some(day16::part_a), Some(day16::part_b)),
+    (Some(day17::part_a), Some(day17::part_b)),
+    (Some(day18::part_a), Some(day18::part_b)),
+    (Some(day19::part_a), Some(day19::part_b)),
+    (Some(day20::part_a), Some(day20::part_b)),
+    (Some(day21::part_a), Some(day21::part_b)),
+    (Some(day22::part_a), Some(day22::part_b)),
+    (Some(day23::part_a), Some(day23::part_b)),
 ];
 
 #[must_use]
@@ -45,5 +70,175 @@ pub fn get_solution(day: u8) -> (PuzzleSolution, PuzzleSolution) {
     }
 }
 
+/// Slow but obviously correct implementations of the days whose solutions rely on clever
+/// optimisations, used to check those solutions against.
+const REFERENCES: [(PuzzleSolution, PuzzleSolution); 23] = [
+    (None, None),
+    (None, None),
+    (None, None),
+    (None, None),
+    (None, None),
+    (Some(day06::reference_part_a), Some(day06::reference_part_b)),
+    (Some(day07::reference_part_a), Some(day07::reference_part_b)),
+    (None, None),
+    (None, None),
+    (None, None),
+    (None, Some(day11::reference_part_b)),
+    (Some(day12::reference_part_a), Some(day12::reference_part_b)),
+    (None, None),
+    (Some(day14::reference_part_a), Some(day14::reference_part_b)),
+    (None, None),
+    (Some(day16::reference_part_a), Some(day16::reference_part_b)),
+    (None, None),
+    (None, None),
+    (None, None),
+    (Some(day20::reference_part_a), Some(day20::reference_part_b)),
+    (None, None),
+    (None, None),
+    (None, None),
+];
+
+#[must_use]
+pub fn get_reference(day: u8) -> (PuzzleSolution, PuzzleSolution) {
+    if day > 0 && day <= MAX_SOLVED_DAY {
+        REFERENCES[day as usize - 1]
+    } else {
+        (None, None)
+    }
+}
+
+/// Draws a day's state as it changes, keeping at most about the given number of frames.
+pub type PuzzleScene = Option<fn(&str, usize) -> Result<Scene, Error>>;
+
+/// The days whose state is worth looking at.
+const SCENES: [PuzzleScene; 23] = [
+    None,
+    None,
+    None,
+    None,
+    Some(day05::scene),
+    None,
+    None,
+    Some(day08::scene),
+    Some(day09::scene),
+    Some(day10::scene),
+    Some(day11::scene),
+    Some(day12::scene),
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+];
+
+#[must_use]
+pub fn get_scene(day: u8) -> PuzzleScene {
+    if day > 0 && day <= MAX_SOLVED_DAY {
+        SCENES[day as usize - 1]
+    } else {
+        None
+    }
+}
+
+/// Reports how much work each of a day's searches did, labelled by what it searched for.
+pub type PuzzleStats = Option<fn(&str) -> Result<Vec<(String, Stats)>, Error>>;
+
+/// The days whose searches can say how much work they took.
+const SEARCH_STATS: [PuzzleStats; 23] = [
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    None,
+    Some(day19::search_stats),
+    None,
+    None,
+    None,
+    None,
+];
+
+#[must_use]
+pub fn get_search_stats(day: u8) -> PuzzleStats {
+    if day > 0 && day <= MAX_SOLVED_DAY {
+        SEARCH_STATS[day as usize - 1]
+    } else {
+        None
+    }
+}
+
 #[allow(clippy::cast_possible_truncation)]
 pub const MAX_SOLVED_DAY: u8 = DAYS.len() as u8;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::{get_generator, Rng};
+
+    /// A cheap stand-in for the fuzz targets: damaged inputs may be rejected, but never panic.
+    #[test]
+    fn test_damaged_inputs_do_not_panic() {
+        for day in 1..=MAX_SOLVED_DAY {
+            let (part_a, part_b) = get_solution(day);
+            let mut rng = Rng::new(u64::from(day));
+            let input = get_generator(day).unwrap()(&mut rng, 3).input;
+
+            let mut damaged = vec![String::new(), "\n\n\n".to_string(), input.to_uppercase()];
+            for _ in 0..50 {
+                let mut bytes = input.clone().into_bytes();
+                bytes.truncate(rng.below(bytes.len() + 1));
+                if !bytes.is_empty() {
+                    let i = rng.below(bytes.len());
+                    bytes[i] = *rng.choose(b"09 \n[],-$aAzZSE+*\xff");
+                }
+                damaged.push(String::from_utf8_lossy(&bytes).into_owned());
+            }
+
+            for file in &damaged {
+                for part in [part_a, part_b].into_iter().flatten() {
+                    let _ = part(file);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_references_agree_with_solutions() {
+        for day in 1..=MAX_SOLVED_DAY {
+            let (part_a, part_b) = get_solution(day);
+            let (reference_a, reference_b) = get_reference(day);
+
+            for seed in 0..5 {
+                let input = get_generator(day).unwrap()(&mut Rng::new(seed), 10).input;
+
+                for (part, reference) in [(part_a, reference_a), (part_b, reference_b)] {
+                    if let (Some(part), Some(reference)) = (part, reference) {
+                        assert_eq!(
+                            reference(&input).unwrap(),
+                            part(&input).unwrap(),
+                            "day {day}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}